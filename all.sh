@@ -1,9 +1,9 @@
 #!/bin/sh
 
 echo "building all crates..."
-cd ../geom && cargo $1 &&
-cd ../path && cargo $1 &&
-cd ../tessellation && cargo $1 &&
+cd ../geom && cargo $1 && cargo $1 --features serialization &&
+cd ../path && cargo $1 && cargo $1 --features serialization &&
+cd ../tessellation && cargo $1 && cargo $1 --features serialization &&
 cd ../svg && cargo $1 &&
 cd ../extra && cargo $1 &&
 cd ../renderer && cargo $1 &&
@@ -6,13 +6,14 @@ use std::f32;
 use Line;
 use math::{Point, point, Vector, vector, Rotation2D, Transform2D, Radians, Rect};
 use utils::directed_angle;
-use segment::{Segment, FlattenedForEach, FlatteningStep, BoundingRect};
+use segment::{Segment, FlattenedForEach, FlatteningStep, FlatteningIterator, FlattenedWithT, BoundingRect};
 use segment;
 
 /// A flattening iterator for arc segments.
 pub type Flattened = segment::Flattened<Arc>;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct SvgArc {
     pub from: Point,
     pub to: Point,
@@ -22,6 +23,7 @@ pub struct SvgArc {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Arc {
     pub center: Point,
     pub radii: Vector,
@@ -230,8 +232,15 @@ impl Arc {
         // Here we make the approximation that for small tolerance values we consider
         // the radius to be constant over each approximated segment.
         let r = (self.from() - self.center).length();
-        let a = 2.0 * tolerance * r - tolerance * tolerance;
-        f32::acos((a * a) / r)
+        // Angle of the longest chord that stays within the tolerance of the circle.
+        let a = 2.0 * f32::acos(1.0 - tolerance / r);
+        let t = a / self.sweep_angle.get().abs();
+        // Also covers NaN for very small radii and empty sweeps.
+        if !(t < 1.0) {
+            return 1.0;
+        }
+
+        t
     }
 
     /// Returns the flattened representation of the curve as an iterator, starting *after* the
@@ -240,6 +249,12 @@ impl Arc {
         Flattened::new(*self, tolerance)
     }
 
+    /// Returns the flattened representation of the curve as an iterator, starting *after* the
+    /// current point, yielding each point along with its `t` parameter on the curve.
+    pub fn flattened_with_t(&self, tolerance: f32) -> FlattenedWithT<Flattened> {
+        self.flattened(tolerance).with_t()
+    }
+
     /// Returns a conservative rectangle that contains the curve.
     pub fn bounding_rect(&self) -> Rect {
         Transform2D::create_rotation(self.x_rotation).transform_rect(
@@ -287,6 +302,7 @@ impl SvgArc {
 
 /// Flag parameters for arcs as described by the SVG specification.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct ArcFlags {
     pub large_arc: bool,
     pub sweep: bool,
@@ -305,10 +321,11 @@ fn arc_to_to_quadratic_beziers<F: FnMut(Point, Point)>(
     arc: &Arc,
     call_back: &mut F,
 ) {
+    let sign = arc.sweep_angle.get().signum();
     let sweep_angle = arc.sweep_angle.get().abs().min(consts::PI * 2.0);
 
     let n_steps = (sweep_angle / consts::FRAC_PI_4).ceil();
-    let step = sweep_angle / n_steps;
+    let step = sign * sweep_angle / n_steps;
 
     for i in 0..(n_steps as i32) {
         let a1 = arc.start_angle.get() + step * (i as f32);
@@ -362,3 +379,47 @@ impl FlatteningStep for Arc {
         self.flattening_step(tolerance)
     }
 }
+
+#[test]
+fn flattened_arc() {
+    let arc = Arc {
+        center: point(0.0, 0.0),
+        radii: vector(10.0, 10.0),
+        start_angle: Radians::new(0.0),
+        sweep_angle: Radians::new(-f32::consts::PI),
+        x_rotation: Radians::new(0.0),
+    };
+
+    let mut count = 0;
+    let mut prev = arc.from();
+    arc.flattened_for_each(0.01, &mut |p| {
+        assert!(((p - arc.center).length() - 10.0).abs() < 0.001);
+        // The middle of each chord is within the tolerance.
+        let mid = prev.lerp(p, 0.5);
+        assert!(10.0 - (mid - arc.center).length() <= 0.0101);
+        prev = p;
+        count += 1;
+    });
+    assert!(count > 2 && count < 100);
+    assert!((prev - arc.to()).length() < 0.0001);
+}
+
+#[test]
+fn negative_sweep_to_quadratic_beziers() {
+    let arc = Arc {
+        center: point(0.0, 0.0),
+        radii: vector(1.0, 1.0),
+        start_angle: Radians::new(0.0),
+        sweep_angle: Radians::new(-f32::consts::PI * 0.5),
+        x_rotation: Radians::new(0.0),
+    };
+
+    let mut last = arc.from();
+    arc.to_quadratic_beziers(&mut |ctrl, to| {
+        // Going clockwise from (1, 0) to (0, -1).
+        assert!(ctrl.y < 0.0);
+        assert!(to.y <= last.y);
+        last = to;
+    });
+    assert!((last - point(0.0, -1.0)).length() < 0.0001);
+}
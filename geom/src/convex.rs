@@ -0,0 +1,340 @@
+//! Convex hulls, oriented bounding rectangles and intersection tests between convex polygons.
+//!
+//! Unless specified otherwise, the convex polygons produced in this module are in
+//! counter-clockwise order assuming the y axis points upwards (which means clockwise
+//! order on screen, with the y axis pointing downwards).
+
+use math::{Point, Vector, Rect, rect};
+use predicates::orient2d;
+use utils::tangent;
+
+use std::cmp::Ordering;
+use std::f32;
+
+/// Computes the convex hull of a set of points.
+///
+/// The hull is returned as a counter-clockwise (assuming y points upwards) polygon
+/// without duplicate or collinear points. If all of the input points are aligned, the
+/// result contains the two extremities of the point set, and if the input contains a
+/// single distinct point the result contains that point.
+///
+/// The implementation is based on Andrew's monotone chain algorithm and relies on
+/// the robust orientation predicate of the `predicates` module.
+pub fn convex_hull(points: &[Point]) -> Vec<Point> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| {
+        a.x.partial_cmp(&b.x).unwrap_or(Ordering::Equal)
+            .then(a.y.partial_cmp(&b.y).unwrap_or(Ordering::Equal))
+    });
+    sorted.dedup();
+
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let mut hull: Vec<Point> = Vec::with_capacity(sorted.len() + 1);
+
+    // Lower hull.
+    for &p in &sorted {
+        while hull.len() >= 2 && orient2d(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+
+    // Upper hull.
+    let lower_len = hull.len() + 1;
+    for &p in sorted.iter().rev().skip(1) {
+        while hull.len() >= lower_len && orient2d(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+
+    // The first point was pushed again at the end of the upper hull.
+    hull.pop();
+
+    hull
+}
+
+/// A rectangle that is not necessarily aligned with the x and y axes.
+///
+/// The rectangle is described by one of its corners and two perpendicular vectors
+/// going from that corner along the sides of the rectangle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct OrientedRect {
+    pub origin: Point,
+    pub x_axis: Vector,
+    pub y_axis: Vector,
+}
+
+impl OrientedRect {
+    /// Creates an oriented rectangle from an axis-aligned one.
+    pub fn from_rect(r: &Rect) -> Self {
+        OrientedRect {
+            origin: r.origin,
+            x_axis: Vector::new(r.size.width, 0.0),
+            y_axis: Vector::new(0.0, r.size.height),
+        }
+    }
+
+    /// Returns the four corners of the rectangle.
+    ///
+    /// The corners are in the same order as `origin`, `origin + x_axis`,
+    /// `origin + x_axis + y_axis` and `origin + y_axis`.
+    pub fn corners(&self) -> [Point; 4] {
+        [
+            self.origin,
+            self.origin + self.x_axis,
+            self.origin + self.x_axis + self.y_axis,
+            self.origin + self.y_axis,
+        ]
+    }
+
+    /// Returns the center of the rectangle.
+    pub fn center(&self) -> Point {
+        self.origin + (self.x_axis + self.y_axis) * 0.5
+    }
+
+    /// Returns the area of the rectangle.
+    pub fn area(&self) -> f32 {
+        self.x_axis.cross(self.y_axis).abs()
+    }
+
+    /// Returns whether the point is inside the rectangle, including its boundary.
+    pub fn contains_point(&self, point: Point) -> bool {
+        let v = point - self.origin;
+        let x = v.dot(self.x_axis);
+        let y = v.dot(self.y_axis);
+
+        return x >= 0.0 && x <= self.x_axis.square_length()
+            && y >= 0.0 && y <= self.y_axis.square_length();
+    }
+
+    /// Returns the smallest axis-aligned rectangle containing this rectangle.
+    pub fn bounding_rect(&self) -> Rect {
+        let corners = self.corners();
+        let mut min_x = corners[0].x;
+        let mut max_x = corners[0].x;
+        let mut min_y = corners[0].y;
+        let mut max_y = corners[0].y;
+        for p in &corners[1..] {
+            min_x = min_x.min(p.x);
+            max_x = max_x.max(p.x);
+            min_y = min_y.min(p.y);
+            max_y = max_y.max(p.y);
+        }
+
+        rect(min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+
+    /// Test for intersection with another oriented rectangle.
+    ///
+    /// Rectangles that only touch along their boundary are not considered intersecting.
+    pub fn intersects(&self, other: &Self) -> bool {
+        convex_polygons_intersect(&self.corners(), &other.corners())
+    }
+}
+
+/// Computes the minimum-area rectangle containing all of the provided points.
+///
+/// The result is found by testing a rectangle aligned with each edge of the convex
+/// hull of the points, which is guaranteed to contain the optimal one.
+/// Returns `None` if `points` is empty.
+pub fn oriented_bounding_rect(points: &[Point]) -> Option<OrientedRect> {
+    let hull = convex_hull(points);
+
+    match hull.len() {
+        0 => { return None; }
+        1 => {
+            return Some(OrientedRect {
+                origin: hull[0],
+                x_axis: Vector::zero(),
+                y_axis: Vector::zero(),
+            });
+        }
+        2 => {
+            return Some(OrientedRect {
+                origin: hull[0],
+                x_axis: hull[1] - hull[0],
+                y_axis: Vector::zero(),
+            });
+        }
+        _ => {}
+    }
+
+    let mut best: Option<(f32, OrientedRect)> = None;
+    for i in 0..hull.len() {
+        let edge = hull[(i + 1) % hull.len()] - hull[i];
+        let edge_length = edge.length();
+        if edge_length == 0.0 {
+            continue;
+        }
+
+        let u = edge / edge_length;
+        let v = tangent(u);
+
+        let mut min_u = f32::MAX;
+        let mut max_u = -f32::MAX;
+        let mut min_v = f32::MAX;
+        let mut max_v = -f32::MAX;
+        for p in &hull {
+            let d = *p - hull[i];
+            let pu = d.dot(u);
+            let pv = d.dot(v);
+            min_u = min_u.min(pu);
+            max_u = max_u.max(pu);
+            min_v = min_v.min(pv);
+            max_v = max_v.max(pv);
+        }
+
+        let area = (max_u - min_u) * (max_v - min_v);
+        if best.map_or(true, |(best_area, _)| area < best_area) {
+            best = Some((area, OrientedRect {
+                origin: hull[i] + u * min_u + v * min_v,
+                x_axis: u * (max_u - min_u),
+                y_axis: v * (max_v - min_v),
+            }));
+        }
+    }
+
+    best.map(|(_, r)| r)
+}
+
+/// Test for intersection between two convex polygons using the separating axis theorem.
+///
+/// The polygons can be in either clockwise or counter-clockwise order.
+/// Polygons that only touch along their boundary are not considered intersecting.
+pub fn convex_polygons_intersect(a: &[Point], b: &[Point]) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+
+    return !has_separating_axis(a, b) && !has_separating_axis(b, a);
+}
+
+// Returns true if one of the edges of `polygon` defines an axis along which the
+// projections of the two polygons don't overlap.
+fn has_separating_axis(polygon: &[Point], other: &[Point]) -> bool {
+    for i in 0..polygon.len() {
+        let edge = polygon[(i + 1) % polygon.len()] - polygon[i];
+        if edge.x == 0.0 && edge.y == 0.0 {
+            continue;
+        }
+
+        let axis = tangent(edge);
+        let (min_a, max_a) = project(polygon, axis);
+        let (min_b, max_b) = project(other, axis);
+        if max_a <= min_b || max_b <= min_a {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn project(polygon: &[Point], axis: Vector) -> (f32, f32) {
+    let mut min = f32::MAX;
+    let mut max = -f32::MAX;
+    for p in polygon {
+        let d = p.to_vector().dot(axis);
+        min = min.min(d);
+        max = max.max(d);
+    }
+
+    (min, max)
+}
+
+#[cfg(test)]
+use math::point;
+
+#[test]
+fn convex_hull_square() {
+    let points = [
+        point(1.0, 1.0),
+        point(0.0, 0.0),
+        point(0.5, 0.2),
+        point(1.0, 0.0),
+        point(0.0, 1.0),
+        point(0.5, 0.5),
+        point(0.5, 0.0), // collinear with an edge of the hull.
+        point(1.0, 1.0), // duplicate.
+    ];
+
+    let hull = convex_hull(&points);
+    assert_eq!(
+        hull,
+        vec![point(0.0, 0.0), point(1.0, 0.0), point(1.0, 1.0), point(0.0, 1.0)]
+    );
+}
+
+#[test]
+fn convex_hull_degenerate() {
+    assert!(convex_hull(&[]).is_empty());
+    assert_eq!(convex_hull(&[point(1.0, 2.0), point(1.0, 2.0)]), vec![point(1.0, 2.0)]);
+    assert_eq!(
+        convex_hull(&[point(2.0, 2.0), point(0.0, 0.0), point(1.0, 1.0)]),
+        vec![point(0.0, 0.0), point(2.0, 2.0)]
+    );
+}
+
+#[test]
+fn oriented_bounding_rect_rotated_square() {
+    // A unit square rotated by 45 degrees.
+    let s = 0.5f32.sqrt();
+    let points = [
+        point(0.0, 0.0),
+        point(s, s),
+        point(0.0, 2.0 * s),
+        point(-s, s),
+        point(0.0, s),
+    ];
+
+    let r = oriented_bounding_rect(&points).unwrap();
+    assert!((r.area() - 1.0).abs() < 0.0001);
+    for p in &points {
+        let c = r.center();
+        // Slightly shrink the points toward the center to avoid precision issues on the boundary.
+        assert!(r.contains_point(c + (*p - c) * 0.999));
+    }
+    assert!(!r.contains_point(point(s, 0.0)));
+
+    // The axis-aligned bounding rectangle is twice as big.
+    let aabb = r.bounding_rect();
+    assert!((aabb.size.width * aabb.size.height - 2.0).abs() < 0.0001);
+
+    assert!(oriented_bounding_rect(&[]).is_none());
+}
+
+#[test]
+fn convex_polygon_intersections() {
+    let square = [point(0.0, 0.0), point(2.0, 0.0), point(2.0, 2.0), point(0.0, 2.0)];
+    let diamond = [point(3.0, 1.0), point(2.5, 1.5), point(1.5, 1.0), point(2.5, 0.5)];
+    let far = [point(5.0, 0.0), point(6.0, 0.0), point(5.0, 1.0)];
+    let touching = [point(2.0, 0.0), point(3.0, 0.0), point(3.0, 2.0), point(2.0, 2.0)];
+
+    assert!(convex_polygons_intersect(&square, &diamond));
+    assert!(convex_polygons_intersect(&diamond, &square));
+    assert!(!convex_polygons_intersect(&square, &far));
+    assert!(!convex_polygons_intersect(&square, &touching));
+
+    // Winding order doesn't matter.
+    let mut reversed = diamond;
+    reversed.reverse();
+    assert!(convex_polygons_intersect(&square, &reversed));
+
+    let r1 = OrientedRect::from_rect(&rect(0.0, 0.0, 2.0, 2.0));
+    let r2 = OrientedRect {
+        origin: point(2.5, 0.0),
+        x_axis: Vector::new(1.0, 1.0),
+        y_axis: Vector::new(-1.0, 1.0),
+    };
+    let r3 = OrientedRect {
+        origin: point(3.1, 0.0),
+        x_axis: Vector::new(1.0, 1.0),
+        y_axis: Vector::new(-1.0, 1.0),
+    };
+    assert!(r1.intersects(&r2));
+    assert!(!r1.intersects(&r3));
+}
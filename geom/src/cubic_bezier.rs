@@ -6,7 +6,9 @@ pub use flatten_cubic::Flattened;
 pub use cubic_to_quadratic::cubic_to_quadratic;
 use monotone::{XMonotone, YMonotone};
 use utils::cubic_polynomial_roots;
-use segment::{Segment, FlattenedForEach, approximate_length_from_flattening, BoundingRect};
+use convex::convex_hull;
+use segment::{Segment, FlattenedForEach, FlatteningIterator, FlattenedWithT, approximate_length_from_flattening, BoundingRect};
+use segment::{rect_sides, clip_ranges_to_rect};
 
 /// A 2d curve segment defined by four points: the beginning of the segment, two control
 /// points and the end of the segment.
@@ -14,6 +16,7 @@ use segment::{Segment, FlattenedForEach, approximate_length_from_flattening, Bou
 /// The curve is defined by equation:²
 /// ```∀ t ∈ [0..1],  P(t) = (1 - t)³ * from + 3 * (1 - t)² * t * ctrl1 + 3 * t² * (1 - t) * ctrl2 + t³ * to```
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct CubicBezierSegment {
     pub from: Point,
     pub ctrl1: Point,
@@ -175,6 +178,12 @@ impl CubicBezierSegment {
         Flattened::new(*self, tolerance)
     }
 
+    /// Returns the flattened representation of the curve as an iterator, starting *after* the
+    /// current point, yielding each point along with its `t` parameter on the curve.
+    pub fn flattened_with_t(&self, tolerance: f32) -> FlattenedWithT<Flattened> {
+        self.flattened(tolerance).with_t()
+    }
+
     /// Iterates through the curve invoking a callback at each point.
     pub fn flattened_for_each<F: FnMut(Point)>(&self, tolerance: f32, call_back: &mut F) {
         flatten_cubic_bezier(*self, tolerance, call_back);
@@ -390,6 +399,13 @@ impl CubicBezierSegment {
         (min_y, max_y)
     }
 
+    /// Returns the convex hull of the control points, which contains the curve.
+    ///
+    /// See `convex::convex_hull` for the order of the points.
+    pub fn convex_hull(&self) -> Vec<Point> {
+        convex_hull(&[self.from, self.ctrl1, self.ctrl2, self.to])
+    }
+
     /// Cast this curve into a x-montone curve without checking that the monotonicity
     /// assumption is correct.
     pub fn assume_x_montone(&self) -> XMonotoneCubicBezierSegment {
@@ -442,6 +458,21 @@ impl CubicBezierSegment {
         return result;
     }
 
+    /// Computes the parts of the curve that are inside of a rectangle.
+    ///
+    /// The result is provided in the form of ranges of `t` parameters. Points on the
+    /// boundary of the rectangle are considered inside.
+    pub fn clip_ranges_to_rect(&self, rect: &Rect) -> ArrayVec<[(f32, f32); 8]> {
+        let mut splits = ArrayVec::new();
+        for side in &rect_sides(rect) {
+            for t in self.line_intersections(side) {
+                splits.push(t);
+            }
+        }
+
+        clip_ranges_to_rect(self, rect, splits)
+    }
+
     /// Computes the intersections (if any) between this segment a line segment.
     ///
     /// The result is provided in the form of the `t` parameters of each
@@ -662,3 +693,79 @@ fn monotone_solve_t_for_x() {
         assert!(x_diff.abs() <= tolerance);
     }
 }
+
+#[test]
+fn convex_hull_for_cubic_bezier_segment() {
+    let c1 = CubicBezierSegment {
+        from: Point::new(0.0, 0.0),
+        ctrl1: Point::new(1.0, 2.0),
+        ctrl2: Point::new(2.0, 1.0),
+        to: Point::new(3.0, 3.0),
+    };
+
+    let hull = c1.convex_hull();
+    assert_eq!(hull.len(), 4);
+
+    // The control points of this curve form a concave polygon.
+    let c2 = CubicBezierSegment {
+        from: Point::new(0.0, 0.0),
+        ctrl1: Point::new(4.0, 0.0),
+        ctrl2: Point::new(1.0, 1.0),
+        to: Point::new(0.0, 4.0),
+    };
+
+    let hull = c2.convex_hull();
+    assert_eq!(hull, vec![Point::new(0.0, 0.0), Point::new(4.0, 0.0), Point::new(0.0, 4.0)]);
+}
+
+#[test]
+fn clip_ranges_to_rect_for_cubic_bezier_segment() {
+    let r = rect(0.0, 0.0, 10.0, 10.0);
+
+    // Goes in and out of the rectangle through the bottom side twice.
+    let c1 = CubicBezierSegment {
+        from: Point::new(-2.0, 5.0),
+        ctrl1: Point::new(8.0, 25.0),
+        ctrl2: Point::new(2.0, -15.0),
+        to: Point::new(12.0, 5.0),
+    };
+
+    let ranges = c1.clip_ranges_to_rect(&r);
+    assert!(ranges.len() > 1);
+    for &(t0, t1) in &ranges {
+        assert!(t0 < t1);
+        let mid = c1.sample((t0 + t1) * 0.5);
+        assert!(mid.x >= 0.0 && mid.x <= 10.0 && mid.y >= 0.0 && mid.y <= 10.0);
+        for &t in &[t0, t1] {
+            let p = c1.sample(t);
+            assert!(p.x >= -0.001 && p.x <= 10.001 && p.y >= -0.001 && p.y <= 10.001);
+        }
+    }
+    for i in 0..100 {
+        let t = i as f32 / 100.0;
+        let p = c1.sample(t);
+        let in_rect = p.x > 0.01 && p.x < 9.99 && p.y > 0.01 && p.y < 9.99;
+        let in_ranges = ranges.iter().any(|&(t0, t1)| t >= t0 && t <= t1);
+        if in_rect {
+            assert!(in_ranges);
+        }
+    }
+
+    // Entirely inside.
+    let c2 = CubicBezierSegment {
+        from: Point::new(1.0, 1.0),
+        ctrl1: Point::new(2.0, 9.0),
+        ctrl2: Point::new(8.0, 9.0),
+        to: Point::new(9.0, 1.0),
+    };
+    assert_eq!(&c2.clip_ranges_to_rect(&r)[..], &[(0.0, 1.0)]);
+
+    // Entirely outside.
+    let c3 = CubicBezierSegment {
+        from: Point::new(11.0, 1.0),
+        ctrl1: Point::new(12.0, 9.0),
+        ctrl2: Point::new(18.0, 9.0),
+        to: Point::new(19.0, 1.0),
+    };
+    assert!(c3.clip_ranges_to_rect(&r).is_empty());
+}
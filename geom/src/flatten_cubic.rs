@@ -8,6 +8,7 @@
 
 use CubicBezierSegment;
 use math::Point;
+use segment::FlatteningIterator;
 use arrayvec::ArrayVec;
 use std::f32;
 use std::mem::swap;
@@ -25,6 +26,9 @@ pub struct Flattened {
     following_inflection: Option<f32>,
     tolerance: f32,
     check_inflection: bool,
+    // The t parameters of the remaining and current curves on the original curve.
+    remaining_t_start: f32,
+    current_t_range: (f32, f32),
 }
 
 impl Flattened {
@@ -40,12 +44,16 @@ impl Flattened {
             following_inflection: inflections.get(1).cloned(),
             tolerance: tolerance,
             check_inflection: false,
+            remaining_t_start: 0.0,
+            current_t_range: (0.0, 1.0),
         };
 
         if let Some(&t1) = inflections.get(0) {
             let (before, after) = bezier.split(t1);
             iter.current_curve = Some(before);
             iter.remaining_curve = after;
+            iter.current_t_range = (0.0, t1);
+            iter.remaining_t_start = t1;
             if let Some(&t2) = inflections.get(1) {
                 // Adjust the second inflection since we removed the part before the
                 // first inflection from the bezier curve.
@@ -65,6 +73,12 @@ impl Flattened {
 impl Iterator for Flattened {
     type Item = Point;
     fn next(&mut self) -> Option<Point> {
+        self.next_with_t().map(|(point, _)| point)
+    }
+}
+
+impl FlatteningIterator for Flattened {
+    fn next_with_t(&mut self) -> Option<(Point, f32)> {
 
         if self.current_curve.is_none() && self.next_inflection.is_some() {
             if let Some(t2) = self.following_inflection {
@@ -73,9 +87,13 @@ impl Iterator for Flattened {
                 let (before, after) = self.remaining_curve.split(t2);
                 self.current_curve = Some(before);
                 self.remaining_curve = after;
+                let split_t = self.remaining_t_start + t2 * (1.0 - self.remaining_t_start);
+                self.current_t_range = (self.remaining_t_start, split_t);
+                self.remaining_t_start = split_t;
             } else {
                 // The last chunk doesn't have inflection points, use it.
                 self.current_curve = Some(self.remaining_curve);
+                self.current_t_range = (self.remaining_t_start, 1.0);
             }
 
             // Pop the inflection stack.
@@ -90,7 +108,7 @@ impl Iterator for Flattened {
                 if let Some(tf) = inflection_approximation_range(&sub_curve, self.tolerance) {
                     let next = sub_curve.after_split(tf);
                     self.current_curve = Some(next);
-                    return Some(next.from);
+                    return Some((next.from, self.advance_current_t(tf)));
                 }
             }
 
@@ -99,18 +117,30 @@ impl Iterator for Flattened {
             if t >= 1.0 {
                 let to = sub_curve.to;
                 self.current_curve = None;
-                return Some(to);
+                return Some((to, self.current_t_range.1));
             }
 
             let next_curve = sub_curve.after_split(t);
             self.current_curve = Some(next_curve);
-            return Some(next_curve.from);
+            return Some((next_curve.from, self.advance_current_t(t)));
         }
 
         return None;
     }
 }
 
+impl Flattened {
+    // Moves the start of the current curve's range to the provided local t parameter
+    // and returns the corresponding t on the original curve.
+    fn advance_current_t(&mut self, t: f32) -> f32 {
+        let (start, end) = self.current_t_range;
+        let new_start = start + t * (end - start);
+        self.current_t_range.0 = new_start;
+
+        new_start
+    }
+}
+
 pub fn flatten_cubic_bezier<F: FnMut(Point)>(
     mut bezier: CubicBezierSegment,
     tolerance: f32,
@@ -426,3 +456,45 @@ fn test_issue_194() {
 
     assert!(points.len() > 2);
 }
+
+#[test]
+fn test_flattened_with_t() {
+    let curves = [
+        // No inflection.
+        CubicBezierSegment {
+            from: Point::new(0.0, 0.0),
+            ctrl1: Point::new(1.0, 0.0),
+            ctrl2: Point::new(1.0, 1.0),
+            to: Point::new(0.0, 1.0),
+        },
+        // One inflection.
+        CubicBezierSegment {
+            from: Point::new(0.0, 0.0),
+            ctrl1: Point::new(1.0, 0.0),
+            ctrl2: Point::new(0.0, 1.0),
+            to: Point::new(1.0, 1.0),
+        },
+        // Two inflections.
+        CubicBezierSegment {
+            from: Point::new(0.0, 0.0),
+            ctrl1: Point::new(2.0, 1.0),
+            ctrl2: Point::new(-1.0, 1.0),
+            to: Point::new(1.0, 0.0),
+        },
+    ];
+
+    for curve in &curves {
+        let points: Vec<Point> = curve.flattened(0.01).collect();
+        let points_with_t: Vec<(Point, f32)> = curve.flattened_with_t(0.01).collect();
+        assert_eq!(points.len(), points_with_t.len());
+
+        let mut prev_t = 0.0;
+        for (p, &(p2, t)) in points.iter().zip(points_with_t.iter()) {
+            assert_eq!(*p, p2);
+            assert!(t > prev_t);
+            assert!((curve.sample(t) - p2).length() < 0.001);
+            prev_t = t;
+        }
+        assert_eq!(prev_t, 1.0);
+    }
+}
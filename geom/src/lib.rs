@@ -68,11 +68,19 @@
 pub extern crate arrayvec;
 pub extern crate euclid;
 
+#[cfg(feature = "serialization")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "serialization")]
+pub extern crate serde;
+
 #[macro_use] mod segment;
 pub mod quadratic_bezier;
 pub mod cubic_bezier;
 pub mod arc;
 pub mod utils;
+pub mod predicates;
+pub mod convex;
 mod flatten_cubic;
 mod cubic_to_quadratic;
 mod triangle;
@@ -84,9 +92,10 @@ pub use cubic_to_quadratic::cubic_to_quadratic;
 pub use quadratic_bezier::QuadraticBezierSegment;
 pub use cubic_bezier::CubicBezierSegment;
 pub use triangle::{Triangle};
+pub use convex::OrientedRect;
 pub use line::{LineSegment, Line};
 pub use arc::{Arc, SvgArc, ArcFlags};
-pub use segment::Segment;
+pub use segment::{Segment, FlattenedWithT};
 pub use monotone::{XMonotone, YMonotone, XMonotoneSegment, YMonotoneSegment};
 
 pub mod math {
@@ -136,6 +145,6 @@ pub mod math {
 
 
 pub mod traits {
-    pub use segment::{Segment, FlattenedForEach, FlatteningStep};
+    pub use segment::{Segment, BoundingRect, FlattenedForEach, FlatteningStep, FlatteningIterator};
     pub use monotone::{XMonotoneSegment, YMonotoneSegment};
 }
@@ -1,10 +1,11 @@
-use math::{Point, point, Vector, Rect, Size, Transform2D};
+use math::{Point, point, Vector, vector, Rect, Size, Transform2D};
 use segment::{Segment, FlatteningStep, BoundingRect};
 use utils::min_max;
 
 // TODO: Perhaps it would be better to have LineSegment<T> where T can be f32, f64
 // or some fixed precision number (See comment in the intersection function).
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct LineSegment {
     pub from: Point,
     pub to: Point,
@@ -163,6 +164,126 @@ impl LineSegment {
     pub fn intersects(&self, other: &Self) -> bool {
         self.intersection(other).is_some()
     }
+
+    /// Computes the range of `t` parameters for which the segment is inside of a rectangle,
+    /// using the Liang-Barsky algorithm.
+    ///
+    /// Points on the boundary of the rectangle are considered inside.
+    /// Returns `None` if the segment doesn't overlap the rectangle.
+    pub fn clip_range_to_rect(&self, rect: &Rect) -> Option<(f32, f32)> {
+        let v = self.to_vector();
+        let min = rect.origin;
+        let max = rect.bottom_right();
+
+        let mut t0 = 0.0;
+        let mut t1 = 1.0;
+        let constraints = [
+            (-v.x, self.from.x - min.x),
+            (v.x, max.x - self.from.x),
+            (-v.y, self.from.y - min.y),
+            (v.y, max.y - self.from.y),
+        ];
+        for &(p, q) in &constraints {
+            if !clip_range(p, q, &mut t0, &mut t1) {
+                return None;
+            }
+        }
+
+        Some((t0, t1))
+    }
+
+    /// Returns the part of this segment that is inside of a rectangle, if any.
+    ///
+    /// See `clip_range_to_rect`.
+    pub fn clip_to_rect(&self, rect: &Rect) -> Option<Self> {
+        self.clip_range_to_rect(rect).map(|(t0, t1)| self.sub_segment(t0, t1))
+    }
+
+    /// Computes the range of `t` parameters for which the segment is inside of a convex
+    /// polygon, using the Cyrus-Beck algorithm.
+    ///
+    /// The polygon can be in either clockwise or counter-clockwise order. Points on the
+    /// boundary of the polygon are considered inside.
+    /// Returns `None` if the segment doesn't overlap the polygon.
+    pub fn clip_range_to_convex_polygon(&self, polygon: &[Point]) -> Option<(f32, f32)> {
+        if polygon.len() < 3 {
+            return None;
+        }
+
+        // Twice the signed area of the polygon, used to orient the edge normals
+        // toward the inside of the polygon.
+        let mut area = 0.0;
+        for i in 0..polygon.len() {
+            let next = polygon[(i + 1) % polygon.len()];
+            area += polygon[i].to_vector().cross(next.to_vector());
+        }
+        if area == 0.0 {
+            return None;
+        }
+        let sign = area.signum();
+
+        let v = self.to_vector();
+        let mut t0 = 0.0;
+        let mut t1 = 1.0;
+        for i in 0..polygon.len() {
+            let edge_from = polygon[i];
+            let edge = polygon[(i + 1) % polygon.len()] - edge_from;
+            let inward_normal = vector(-edge.y, edge.x) * sign;
+            if !clip_range(
+                -inward_normal.dot(v),
+                inward_normal.dot(self.from - edge_from),
+                &mut t0, &mut t1,
+            ) {
+                return None;
+            }
+        }
+
+        Some((t0, t1))
+    }
+
+    /// Returns the part of this segment that is inside of a convex polygon, if any.
+    ///
+    /// See `clip_range_to_convex_polygon`.
+    pub fn clip_to_convex_polygon(&self, polygon: &[Point]) -> Option<Self> {
+        self.clip_range_to_convex_polygon(polygon).map(|(t0, t1)| self.sub_segment(t0, t1))
+    }
+
+    #[inline]
+    fn sub_segment(&self, t0: f32, t1: f32) -> Self {
+        LineSegment {
+            from: if t0 == 0.0 { self.from } else { self.sample(t0) },
+            to: if t1 == 1.0 { self.to } else { self.sample(t1) },
+        }
+    }
+}
+
+// Restricts the [t0, t1] range with the constraint `p * t <= q`.
+// Returns false if the resulting range is empty.
+#[inline]
+fn clip_range(p: f32, q: f32, t0: &mut f32, t1: &mut f32) -> bool {
+    if p == 0.0 {
+        // The segment is parallel to the boundary.
+        return q >= 0.0;
+    }
+
+    let r = q / p;
+    if p < 0.0 {
+        if r > *t1 {
+            return false;
+        }
+        if r > *t0 {
+            *t0 = r;
+        }
+    } else {
+        if r < *t0 {
+            return false;
+        }
+        if r < *t1 {
+            *t1 = r;
+        }
+    }
+
+    return true;
 }
 
 impl Segment for LineSegment {
@@ -202,6 +323,7 @@ impl FlatteningStep for LineSegment {
 // }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Line {
     pub point: Point,
     pub vector: Vector,
@@ -356,3 +478,60 @@ fn bounding_rect() {
         assert_eq!(ls.bounding_rect(), r);
     }
 }
+
+#[test]
+fn clip_to_rect() {
+    let r = rect(0.0, 0.0, 10.0, 10.0);
+
+    // Crossing the rectangle.
+    let l1 = LineSegment { from: point(-5.0, 5.0), to: point(15.0, 5.0) };
+    assert_eq!(l1.clip_range_to_rect(&r), Some((0.25, 0.75)));
+    assert_eq!(l1.clip_to_rect(&r), Some(LineSegment { from: point(0.0, 5.0), to: point(10.0, 5.0) }));
+
+    // Entirely inside.
+    let l2 = LineSegment { from: point(1.0, 1.0), to: point(9.0, 2.0) };
+    assert_eq!(l2.clip_to_rect(&r), Some(l2));
+
+    // Entirely outside.
+    let l3 = LineSegment { from: point(-5.0, -5.0), to: point(15.0, -1.0) };
+    assert_eq!(l3.clip_to_rect(&r), None);
+
+    // Diagonal, starting inside.
+    let l4 = LineSegment { from: point(5.0, 5.0), to: point(15.0, 15.0) };
+    assert_eq!(l4.clip_to_rect(&r), Some(LineSegment { from: point(5.0, 5.0), to: point(10.0, 10.0) }));
+
+    // Passing next to a corner.
+    let l5 = LineSegment { from: point(9.0, 13.0), to: point(13.0, 9.0) };
+    assert_eq!(l5.clip_to_rect(&r), None);
+
+    // Along the boundary.
+    let l6 = LineSegment { from: point(-1.0, 10.0), to: point(4.0, 10.0) };
+    assert_eq!(l6.clip_to_rect(&r), Some(LineSegment { from: point(0.0, 10.0), to: point(4.0, 10.0) }));
+}
+
+#[test]
+fn clip_to_convex_polygon() {
+    let triangle = [point(0.0, 0.0), point(10.0, 0.0), point(0.0, 10.0)];
+    let mut reversed = triangle;
+    reversed.reverse();
+
+    let l1 = LineSegment { from: point(-5.0, 2.0), to: point(15.0, 2.0) };
+    let expected = Some(LineSegment { from: point(0.0, 2.0), to: point(8.0, 2.0) });
+    assert_eq!(l1.clip_to_convex_polygon(&triangle), expected);
+    assert_eq!(l1.clip_to_convex_polygon(&reversed), expected);
+
+    let l2 = LineSegment { from: point(6.0, 6.0), to: point(10.0, 10.0) };
+    assert_eq!(l2.clip_to_convex_polygon(&triangle), None);
+    assert_eq!(l2.clip_to_convex_polygon(&reversed), None);
+
+    let l3 = LineSegment { from: point(1.0, 1.0), to: point(2.0, 3.0) };
+    assert_eq!(l3.clip_to_convex_polygon(&triangle), Some(l3));
+
+    // The result is consistent with clip_to_rect.
+    let square = [point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)];
+    let l4 = LineSegment { from: point(-5.0, 0.0), to: point(15.0, 10.0) };
+    assert_eq!(
+        l4.clip_to_convex_polygon(&square),
+        l4.clip_to_rect(&rect(0.0, 0.0, 10.0, 10.0))
+    );
+}
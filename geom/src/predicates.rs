@@ -0,0 +1,400 @@
+//! Robust geometric predicates.
+//!
+//! The predicates in this module are implemented following Jonathan Richard Shewchuk's
+//! [Adaptive Precision Floating-Point Arithmetic and Fast Robust Geometric Predicates](https://www.cs.cmu.edu/~quake/robust.html).
+//!
+//! Each predicate first evaluates the determinant with regular floating point arithmetic
+//! and checks the result against a conservative error bound. When the sign of the result
+//! can't be trusted, the determinant is evaluated exactly using floating point expansions.
+//! The sign of the returned value is therefore always correct, while the common case stays
+//! about as fast as the naive computation.
+//!
+//! The `f32` versions convert their input to `f64` and always produce exact signs.
+//!
+//! The orientation conventions below assume that the y axis points upwards. With the y axis
+//! pointing downwards (the usual convention for screen coordinates), clockwise and
+//! counter-clockwise are swapped.
+
+use math::{Point, F64Point};
+
+/// The orientation of three points.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// The points are in counter-clockwise order (assuming y points upwards).
+    CounterClockwise,
+    /// The points are in clockwise order (assuming y points upwards).
+    Clockwise,
+    /// The points are aligned.
+    Collinear,
+}
+
+impl Orientation {
+    #[inline]
+    fn from_sign(value: f64) -> Self {
+        if value > 0.0 {
+            Orientation::CounterClockwise
+        } else if value < 0.0 {
+            Orientation::Clockwise
+        } else {
+            Orientation::Collinear
+        }
+    }
+}
+
+/// Returns a positive value if `a`, `b` and `c` are in counter-clockwise order, a negative
+/// value if they are in clockwise order and zero if they are collinear.
+///
+/// The returned value approximates twice the signed area of the triangle `abc`. Its sign is exact.
+#[inline]
+pub fn orient2d(a: Point, b: Point, c: Point) -> f64 {
+    orient2d_f64(a.to_f64(), b.to_f64(), c.to_f64())
+}
+
+/// Returns the orientation of the triangle `abc`.
+#[inline]
+pub fn orientation(a: Point, b: Point, c: Point) -> Orientation {
+    Orientation::from_sign(orient2d(a, b, c))
+}
+
+/// Returns a positive value if `d` lies inside the circle passing through `a`, `b` and `c`,
+/// a negative value if it lies outside and zero if the four points are cocircular.
+///
+/// `a`, `b` and `c` must be in counter-clockwise order, otherwise the sign of the result is
+/// reversed. The sign of the returned value is exact.
+#[inline]
+pub fn incircle(a: Point, b: Point, c: Point, d: Point) -> f64 {
+    incircle_f64(a.to_f64(), b.to_f64(), c.to_f64(), d.to_f64())
+}
+
+/// Same as `orient2d` with double precision input.
+pub fn orient2d_f64(a: F64Point, b: F64Point, c: F64Point) -> f64 {
+    let det_left = (a.x - c.x) * (b.y - c.y);
+    let det_right = (a.y - c.y) * (b.x - c.x);
+    let det = det_left - det_right;
+
+    let det_sum = if det_left > 0.0 {
+        if det_right <= 0.0 {
+            return det;
+        }
+        det_left + det_right
+    } else if det_left < 0.0 {
+        if det_right >= 0.0 {
+            return det;
+        }
+        -det_left - det_right
+    } else {
+        return det;
+    };
+
+    let err_bound = CCW_ERR_BOUND_A * det_sum;
+    if det >= err_bound || -det >= err_bound {
+        return det;
+    }
+
+    orient2d_exact(a, b, c)
+}
+
+/// Same as `orientation` with double precision input.
+#[inline]
+pub fn orientation_f64(a: F64Point, b: F64Point, c: F64Point) -> Orientation {
+    Orientation::from_sign(orient2d_f64(a, b, c))
+}
+
+/// Same as `incircle` with double precision input.
+pub fn incircle_f64(a: F64Point, b: F64Point, c: F64Point, d: F64Point) -> f64 {
+    let adx = a.x - d.x;
+    let bdx = b.x - d.x;
+    let cdx = c.x - d.x;
+    let ady = a.y - d.y;
+    let bdy = b.y - d.y;
+    let cdy = c.y - d.y;
+
+    let bdx_cdy = bdx * cdy;
+    let cdx_bdy = cdx * bdy;
+    let a_lift = adx * adx + ady * ady;
+
+    let cdx_ady = cdx * ady;
+    let adx_cdy = adx * cdy;
+    let b_lift = bdx * bdx + bdy * bdy;
+
+    let adx_bdy = adx * bdy;
+    let bdx_ady = bdx * ady;
+    let c_lift = cdx * cdx + cdy * cdy;
+
+    let det = a_lift * (bdx_cdy - cdx_bdy)
+        + b_lift * (cdx_ady - adx_cdy)
+        + c_lift * (adx_bdy - bdx_ady);
+
+    let permanent = (bdx_cdy.abs() + cdx_bdy.abs()) * a_lift
+        + (cdx_ady.abs() + adx_cdy.abs()) * b_lift
+        + (adx_bdy.abs() + bdx_ady.abs()) * c_lift;
+
+    let err_bound = ICC_ERR_BOUND_A * permanent;
+    if det > err_bound || -det > err_bound {
+        return det;
+    }
+
+    incircle_exact(a, b, c, d)
+}
+
+// Half of the distance between 1.0 and the next representable f64.
+const EPSILON: f64 = 1.1102230246251565e-16;
+// Used to split a f64 into two non-overlapping halves: 2^27 + 1.
+const SPLITTER: f64 = 134217729.0;
+const CCW_ERR_BOUND_A: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const ICC_ERR_BOUND_A: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+
+fn orient2d_exact(a: F64Point, b: F64Point, c: F64Point) -> f64 {
+    // a.x * b.y - a.x * c.y - a.y * b.x + a.y * c.x + b.x * c.y - b.y * c.x
+    let mut det = Vec::with_capacity(12);
+    for &(x, y) in &[
+        (a.x, b.y), (-a.x, c.y),
+        (-a.y, b.x), (a.y, c.x),
+        (b.x, c.y), (-b.y, c.x),
+    ] {
+        let (hi, lo) = two_product(x, y);
+        grow_expansion(&mut det, lo);
+        grow_expansion(&mut det, hi);
+    }
+
+    expansion_estimate(&det)
+}
+
+fn incircle_exact(a: F64Point, b: F64Point, c: F64Point, d: F64Point) -> f64 {
+    let adx = two_diff(a.x, d.x);
+    let bdx = two_diff(b.x, d.x);
+    let cdx = two_diff(c.x, d.x);
+    let ady = two_diff(a.y, d.y);
+    let bdy = two_diff(b.y, d.y);
+    let cdy = two_diff(c.y, d.y);
+
+    let lift = |dx: &[f64], dy: &[f64]| {
+        expansion_sum(&expansion_product(dx, dx), &expansion_product(dy, dy))
+    };
+    let cross = |x0: &[f64], y0: &[f64], x1: &[f64], y1: &[f64]| {
+        expansion_diff(&expansion_product(x0, y1), &expansion_product(x1, y0))
+    };
+
+    let a_term = expansion_product(&lift(&adx, &ady), &cross(&bdx, &bdy, &cdx, &cdy));
+    let b_term = expansion_product(&lift(&bdx, &bdy), &cross(&cdx, &cdy, &adx, &ady));
+    let c_term = expansion_product(&lift(&cdx, &cdy), &cross(&adx, &ady, &bdx, &bdy));
+
+    let det = expansion_sum(&expansion_sum(&a_term, &b_term), &c_term);
+
+    expansion_estimate(&det)
+}
+
+// The floating point expansion arithmetic below represents a number as a sequence
+// of non-overlapping f64 components sorted by increasing magnitude, without zeros.
+// The sign of an expansion is the sign of its last (largest) component.
+
+#[inline]
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    let b_round = b - b_virtual;
+    let a_round = a - a_virtual;
+    (x, a_round + b_round)
+}
+
+#[inline]
+fn two_diff(a: f64, b: f64) -> [f64; 2] {
+    let (hi, lo) = two_sum(a, -b);
+    [lo, hi]
+}
+
+#[inline]
+fn split(a: f64) -> (f64, f64) {
+    let c = SPLITTER * a;
+    let a_big = c - a;
+    let hi = c - a_big;
+    (hi, a - hi)
+}
+
+#[inline]
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    let (a_hi, a_lo) = split(a);
+    let (b_hi, b_lo) = split(b);
+    let err1 = x - (a_hi * b_hi);
+    let err2 = err1 - (a_lo * b_hi);
+    let err3 = err2 - (a_hi * b_lo);
+    (x, (a_lo * b_lo) - err3)
+}
+
+/// Adds a scalar to an expansion in place.
+fn grow_expansion(e: &mut Vec<f64>, b: f64) {
+    let mut q = b;
+    let mut result = Vec::with_capacity(e.len() + 1);
+    for &component in e.iter() {
+        let (sum, err) = two_sum(q, component);
+        q = sum;
+        if err != 0.0 {
+            result.push(err);
+        }
+    }
+    if q != 0.0 {
+        result.push(q);
+    }
+    *e = result;
+}
+
+fn expansion_sum(e: &[f64], f: &[f64]) -> Vec<f64> {
+    let mut result = e.to_vec();
+    for &component in f {
+        grow_expansion(&mut result, component);
+    }
+
+    result
+}
+
+fn expansion_diff(e: &[f64], f: &[f64]) -> Vec<f64> {
+    let mut result = e.to_vec();
+    for &component in f {
+        grow_expansion(&mut result, -component);
+    }
+
+    result
+}
+
+fn scale_expansion(e: &[f64], b: f64) -> Vec<f64> {
+    let mut result = Vec::with_capacity(e.len() * 2);
+    if e.is_empty() || b == 0.0 {
+        return result;
+    }
+
+    let (mut q, lo) = two_product(e[0], b);
+    if lo != 0.0 {
+        result.push(lo);
+    }
+    for &component in &e[1..] {
+        let (product_hi, product_lo) = two_product(component, b);
+        let (sum, err) = two_sum(q, product_lo);
+        if err != 0.0 {
+            result.push(err);
+        }
+        let (new_q, err) = two_sum(product_hi, sum);
+        q = new_q;
+        if err != 0.0 {
+            result.push(err);
+        }
+    }
+    if q != 0.0 {
+        result.push(q);
+    }
+
+    result
+}
+
+fn expansion_product(e: &[f64], f: &[f64]) -> Vec<f64> {
+    let mut result = Vec::new();
+    for &component in f {
+        result = expansion_sum(&result, &scale_expansion(e, component));
+    }
+
+    result
+}
+
+#[inline]
+fn expansion_estimate(e: &[f64]) -> f64 {
+    e.iter().fold(0.0, |sum, &component| sum + component)
+}
+
+#[cfg(test)]
+use math::point;
+
+#[test]
+fn orient2d_simple() {
+    let a = point(0.0, 0.0);
+    let b = point(1.0, 0.0);
+    let c = point(0.0, 1.0);
+
+    assert!(orient2d(a, b, c) > 0.0);
+    assert!(orient2d(a, c, b) < 0.0);
+    assert_eq!(orient2d(a, b, point(2.0, 0.0)), 0.0);
+
+    assert_eq!(orientation(a, b, c), Orientation::CounterClockwise);
+    assert_eq!(orientation(b, a, c), Orientation::Clockwise);
+    assert_eq!(orientation(a, b, point(-3.0, 0.0)), Orientation::Collinear);
+}
+
+#[test]
+fn orient2d_nearly_collinear() {
+    // The naive evaluation of this determinant gives wrong and inconsistent signs
+    // for most of these points.
+    let b = F64Point::new(12.0, 12.0);
+    let c = F64Point::new(24.0, 24.0);
+    let ulp = EPSILON;
+    for i in 0..16 {
+        for j in 0..16 {
+            let a = F64Point::new(0.5 + i as f64 * ulp, 0.5 + j as f64 * ulp);
+            let expected = if j > i {
+                Orientation::CounterClockwise
+            } else if j < i {
+                Orientation::Clockwise
+            } else {
+                Orientation::Collinear
+            };
+            assert_eq!(orientation_f64(a, b, c), expected);
+            assert_eq!(orientation_f64(b, c, a), expected);
+            assert_eq!(orientation_f64(c, a, b), expected);
+        }
+    }
+}
+
+#[test]
+fn orient2d_f32_nearly_collinear() {
+    let b = point(1.0, 1.0);
+    let c = point(3.0, 3.0);
+    let a = point(0.1, 0.1);
+    // 0.1f32 * 3.0 isn't exactly representable so these three points aren't
+    // collinear in f32 space, while a naive f32 evaluation returns zero.
+    let expected = orient2d_exact(a.to_f64(), b.to_f64(), c.to_f64());
+    assert_eq!(orient2d(a, b, c) > 0.0, expected > 0.0);
+    assert_eq!(orient2d(a, b, c) < 0.0, expected < 0.0);
+
+    assert_eq!(orientation(point(0.0, 0.0), point(1.0, 1.0), point(3.0, 3.0)), Orientation::Collinear);
+}
+
+#[test]
+fn incircle_simple() {
+    let a = point(0.0, 0.0);
+    let b = point(1.0, 0.0);
+    let c = point(0.0, 1.0);
+
+    assert!(incircle(a, b, c, point(0.5, 0.5)) > 0.0);
+    assert!(incircle(a, b, c, point(2.0, 2.0)) < 0.0);
+    assert_eq!(incircle(a, b, c, point(1.0, 1.0)), 0.0);
+    // Reversing the orientation of the triangle flips the sign.
+    assert!(incircle(a, c, b, point(0.5, 0.5)) < 0.0);
+}
+
+#[test]
+fn incircle_nearly_cocircular() {
+    let a = F64Point::new(1.0, 0.0);
+    let b = F64Point::new(0.0, 1.0);
+    let c = F64Point::new(-1.0, 0.0);
+    let ulp = EPSILON;
+    for i in 0..8 {
+        let inside = F64Point::new(0.0, -1.0 + (i + 1) as f64 * ulp);
+        let outside = F64Point::new(0.0, -1.0 - (i + 1) as f64 * 2.0 * EPSILON);
+        assert!(incircle_f64(a, b, c, inside) > 0.0);
+        assert!(incircle_f64(a, b, c, outside) < 0.0);
+    }
+    assert_eq!(incircle_f64(a, b, c, F64Point::new(0.0, -1.0)), 0.0);
+}
+
+#[test]
+fn expansion_arithmetic() {
+    let big = 1.0e20;
+    let e = expansion_sum(&[1.0], &[big]);
+    let f = expansion_diff(&e, &[big]);
+    assert_eq!(f, vec![1.0]);
+
+    let p = expansion_product(&two_diff(big, -1.0), &two_diff(big, 1.0));
+    // (big + 1) * (big - 1) - big^2 = -1
+    let (sq_hi, sq_lo) = two_product(big, big);
+    let r = expansion_diff(&p, &[sq_lo, sq_hi]);
+    assert_eq!(expansion_estimate(&r), -1.0);
+}
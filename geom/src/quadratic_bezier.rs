@@ -2,7 +2,8 @@ use {CubicBezierSegment, Triangle, Line, LineSegment};
 use math::{Point, Vector, Rect, rect, Transform2D};
 use monotone::{XMonotone, YMonotone};
 use arrayvec::ArrayVec;
-use segment::{Segment, FlatteningStep, FlattenedForEach, BoundingRect};
+use segment::{Segment, FlatteningStep, FlattenedForEach, FlatteningIterator, FlattenedWithT, BoundingRect};
+use segment::{rect_sides, clip_ranges_to_rect};
 use segment;
 
 /// A flattening iterator for quadratic bézier segments.
@@ -14,6 +15,7 @@ pub type Flattened = segment::Flattened<QuadraticBezierSegment>;
 /// The curve is defined by equation:
 /// ```∀ t ∈ [0..1],  P(t) = (1 - t)² * from + 2 * (1 - t) * t * ctrl + 2 * t² * to```
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct QuadraticBezierSegment {
     pub from: Point,
     pub ctrl: Point,
@@ -243,6 +245,12 @@ impl QuadraticBezierSegment {
         Flattened::new(*self, tolerance)
     }
 
+    /// Returns the flattened representation of the curve as an iterator, starting *after* the
+    /// current point, yielding each point along with its `t` parameter on the curve.
+    pub fn flattened_with_t(&self, tolerance: f32) -> FlattenedWithT<Flattened> {
+        self.flattened(tolerance).with_t()
+    }
+
     /// Compute the length of the segment using a flattened approximation.
     pub fn approximate_length(&self, tolerance: f32) -> f32 {
         segment::approximate_length_from_flattening(self, tolerance)
@@ -327,6 +335,21 @@ impl QuadraticBezierSegment {
         return result;
     }
 
+    /// Computes the parts of the curve that are inside of a rectangle.
+    ///
+    /// The result is provided in the form of ranges of `t` parameters. Points on the
+    /// boundary of the rectangle are considered inside.
+    pub fn clip_ranges_to_rect(&self, rect: &Rect) -> ArrayVec<[(f32, f32); 8]> {
+        let mut splits = ArrayVec::new();
+        for side in &rect_sides(rect) {
+            for t in self.line_intersections(side) {
+                splits.push(t);
+            }
+        }
+
+        clip_ranges_to_rect(self, rect, splits)
+    }
+
     /// Computes the intersections (if any) between this segment a line segment.
     ///
     /// The result is provided in the form of the `t` parameters of each
@@ -564,3 +587,70 @@ fn monotone_solve_t_for_x() {
         assert!(x_diff.abs() <= tolerance);
     }
 }
+
+#[test]
+fn clip_ranges_to_rect_for_quadratic_bezier_segment() {
+    let r = rect(0.0, 0.0, 10.0, 10.0);
+
+    // Enters through the left side and leaves through the right side.
+    let q1 = QuadraticBezierSegment {
+        from: Point::new(-10.0, 5.0),
+        ctrl: Point::new(5.0, 5.0),
+        to: Point::new(20.0, 5.0),
+    };
+    let ranges = q1.clip_ranges_to_rect(&r);
+    assert_eq!(ranges.len(), 1);
+    assert!((ranges[0].0 - 1.0 / 3.0).abs() < 0.001);
+    assert!((ranges[0].1 - 2.0 / 3.0).abs() < 0.001);
+
+    // Leaves and comes back through the top side.
+    let q2 = QuadraticBezierSegment {
+        from: Point::new(2.0, 2.0),
+        ctrl: Point::new(5.0, -6.0),
+        to: Point::new(8.0, 2.0),
+    };
+    let ranges = q2.clip_ranges_to_rect(&r);
+    assert_eq!(ranges.len(), 2);
+    assert_eq!(ranges[0].0, 0.0);
+    assert_eq!(ranges[1].1, 1.0);
+    assert!((q2.sample(ranges[0].1).y).abs() < 0.001);
+    assert!((q2.sample(ranges[1].0).y).abs() < 0.001);
+
+    // NaN intersections are ignored.
+    use std::f32;
+    let mut splits = ArrayVec::new();
+    splits.push(f32::NAN);
+    splits.push(2.0 / 3.0);
+    splits.push(1.0 / 3.0);
+    splits.push(f32::NAN);
+    let ranges = clip_ranges_to_rect(&q1, &r, splits);
+    assert_eq!(ranges.len(), 1);
+    assert!((ranges[0].0 - 1.0 / 3.0).abs() < 0.001);
+    assert!((ranges[0].1 - 2.0 / 3.0).abs() < 0.001);
+
+    let nan_rect = rect(f32::NAN, 0.0, 10.0, 10.0);
+    assert!(q1.clip_ranges_to_rect(&nan_rect).is_empty());
+}
+
+#[test]
+fn flattened_with_t() {
+    let curve = QuadraticBezierSegment {
+        from: Point::new(0.0, 0.0),
+        ctrl: Point::new(10.0, 0.0),
+        to: Point::new(10.0, 10.0),
+    };
+
+    let points: Vec<Point> = curve.flattened(0.01).collect();
+    let points_with_t: Vec<(Point, f32)> = curve.flattened_with_t(0.01).collect();
+    assert!(points.len() > 2);
+    assert_eq!(points.len(), points_with_t.len());
+
+    let mut prev_t = 0.0;
+    for (p, &(p2, t)) in points.iter().zip(points_with_t.iter()) {
+        assert_eq!(*p, p2);
+        assert!(t > prev_t);
+        assert!((curve.sample(t) - p2).length() < 0.001);
+        prev_t = t;
+    }
+    assert_eq!(prev_t, 1.0);
+}
@@ -1,4 +1,7 @@
-use math::{Point, Vector, Rect};
+use math::{Point, Vector, Rect, vector};
+use arrayvec::ArrayVec;
+use std::cmp::Ordering;
+use Line;
 
 /// Common APIs to segment types.
 pub trait Segment: Copy + Sized {
@@ -108,6 +111,8 @@ where T: FlatteningStep
 pub struct Flattened<T> {
     curve: T,
     tolerance: f32,
+    // The t parameter of the start of the remaining curve on the original curve.
+    t_start: f32,
     done: bool,
 }
 
@@ -117,6 +122,7 @@ impl<T: FlatteningStep> Flattened<T> {
         Flattened {
             curve: curve,
             tolerance: tolerance,
+            t_start: 0.0,
             done: false,
         }
     }
@@ -125,16 +131,50 @@ impl<T: FlatteningStep> Flattened<T> {
 impl<T: FlatteningStep> Iterator for Flattened<T> {
     type Item = Point;
     fn next(&mut self) -> Option<Point> {
+        self.next_with_t().map(|(point, _)| point)
+    }
+}
+
+impl<T: FlatteningStep> FlatteningIterator for Flattened<T> {
+    fn next_with_t(&mut self) -> Option<(Point, f32)> {
         if self.done {
             return None;
         }
         let t = self.curve.flattening_step(self.tolerance);
         if t == 1.0 {
             self.done = true;
-            return Some(self.curve.to());
+            return Some((self.curve.to(), 1.0));
         }
         self.curve = self.curve.after_split(t);
-        return Some(self.curve.from());
+        self.t_start += t * (1.0 - self.t_start);
+        return Some((self.curve.from(), self.t_start));
+    }
+}
+
+/// Flattening iterators that can also provide the curve parameter of the points they yield.
+pub trait FlatteningIterator: Iterator<Item = Point> + Sized {
+    /// Returns the next point along with its `t` parameter on the original curve.
+    fn next_with_t(&mut self) -> Option<(Point, f32)>;
+
+    /// Returns an iterator yielding each point along with its `t` parameter on
+    /// the original curve.
+    fn with_t(self) -> FlattenedWithT<Self> {
+        FlattenedWithT { it: self }
+    }
+}
+
+/// An iterator yielding the points of a flattened curve along with their `t` parameter
+/// on the original curve.
+///
+/// See `FlatteningIterator::with_t`.
+pub struct FlattenedWithT<Iter> {
+    it: Iter,
+}
+
+impl<Iter: FlatteningIterator> Iterator for FlattenedWithT<Iter> {
+    type Item = (Point, f32);
+    fn next(&mut self) -> Option<(Point, f32)> {
+        self.it.next_with_t()
     }
 }
 
@@ -149,6 +189,60 @@ where T: FlattenedForEach {
     return len;
 }
 
+/// Returns the four lines supporting the sides of a rectangle.
+pub(crate) fn rect_sides(rect: &Rect) -> [Line; 4] {
+    let max = rect.bottom_right();
+    [
+        Line { point: rect.origin, vector: vector(1.0, 0.0) },
+        Line { point: rect.origin, vector: vector(0.0, 1.0) },
+        Line { point: max, vector: vector(1.0, 0.0) },
+        Line { point: max, vector: vector(0.0, 1.0) },
+    ]
+}
+
+/// Computes the ranges of t for which the curve is inside of the rectangle,
+/// given the t parameters of the intersections between the curve and the
+/// sides of the rectangle.
+pub(crate) fn clip_ranges_to_rect<T: Segment>(
+    curve: &T,
+    rect: &Rect,
+    mut splits: ArrayVec<[f32; 16]>,
+) -> ArrayVec<[(f32, f32); 8]> {
+    splits.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let min = rect.origin;
+    let max = rect.bottom_right();
+    let inside = |p: Point| {
+        p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y
+    };
+
+    let mut result: ArrayVec<[(f32, f32); 8]> = ArrayVec::new();
+    let mut start = 0.0;
+    for end in splits.iter().cloned().chain(Some(1.0)) {
+        // Also skips NaN splits.
+        if !(end > start) {
+            continue;
+        }
+
+        if inside(curve.sample((start + end) * 0.5)) {
+            let merged = match result.last_mut() {
+                Some(prev) if prev.1 == start => {
+                    prev.1 = end;
+                    true
+                }
+                _ => false,
+            };
+            if !merged {
+                result.push((start, end));
+            }
+        }
+
+        start = end;
+    }
+
+    return result;
+}
+
 macro_rules! impl_segment {
     () => (
         fn from(&self) -> Point { self.from() }
@@ -1,7 +1,10 @@
-use math::{Point, Rect, Size, Transform2D};
+use math::{Point, Vector, Rect, Size, Transform2D};
 use LineSegment;
+use convex::convex_polygons_intersect;
+use predicates::{orientation, Orientation};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Triangle {
     pub a: Point,
     pub b: Point,
@@ -9,6 +12,161 @@ pub struct Triangle {
 }
 
 impl Triangle {
+    /// Creates a triangle from the `triangle_index`-th triplet of an index buffer.
+    ///
+    /// This is typically used to read back the output of the tessellators from
+    /// `VertexBuffers`, for example:
+    /// `Triangle::from_indices(&buffers.vertices, &buffers.indices, i, |v| v.position)`.
+    ///
+    /// Panics if the indices are out of bounds.
+    pub fn from_indices<V, F>(vertices: &[V], indices: &[u16], triangle_index: usize, position: F) -> Self
+    where
+        F: Fn(&V) -> Point
+    {
+        let i = triangle_index * 3;
+        Triangle {
+            a: position(&vertices[indices[i] as usize]),
+            b: position(&vertices[indices[i + 1] as usize]),
+            c: position(&vertices[indices[i + 2] as usize]),
+        }
+    }
+
+    /// Returns the area of the triangle.
+    #[inline]
+    pub fn area(&self) -> f32 {
+        self.signed_area().abs()
+    }
+
+    /// Returns the signed area of the triangle, positive if the vertices are in
+    /// counter-clockwise order assuming y points upwards.
+    #[inline]
+    pub fn signed_area(&self) -> f32 {
+        (self.b - self.a).cross(self.c - self.a) * 0.5
+    }
+
+    /// Returns the orientation of the triangle, computed using a robust predicate.
+    #[inline]
+    pub fn orientation(&self) -> Orientation {
+        orientation(self.a, self.b, self.c)
+    }
+
+    /// Returns the barycentric coordinates `(u, v, w)` of a point, such that
+    /// `point = a * u + b * v + c * w` and `u + v + w = 1`.
+    ///
+    /// The result contains non-finite values if the triangle is degenerate.
+    pub fn barycentric_coordinates(&self, point: Point) -> (f32, f32, f32) {
+        let v0 = self.b - self.a;
+        let v1 = self.c - self.a;
+        let v2 = point - self.a;
+
+        let d00 = v0.dot(v0);
+        let d01 = v0.dot(v1);
+        let d11 = v1.dot(v1);
+        let d20 = v2.dot(v0);
+        let d21 = v2.dot(v1);
+        let inv = 1.0 / (d00 * d11 - d01 * d01);
+        let v = (d11 * d20 - d01 * d21) * inv;
+        let w = (d00 * d21 - d01 * d20) * inv;
+
+        (1.0 - v - w, v, w)
+    }
+
+    /// Returns the center and the radius of the circle passing through the three vertices.
+    ///
+    /// Returns `None` if the triangle is degenerate.
+    pub fn circumcircle(&self) -> Option<(Point, f32)> {
+        let ab = self.b - self.a;
+        let ac = self.c - self.a;
+        let d = 2.0 * ab.cross(ac);
+        if d == 0.0 {
+            return None;
+        }
+
+        let ab2 = ab.square_length();
+        let ac2 = ac.square_length();
+        let offset = Vector::new(
+            (ac.y * ab2 - ab.y * ac2) / d,
+            (ab.x * ac2 - ac.x * ab2) / d,
+        );
+
+        Some((self.a + offset, offset.length()))
+    }
+
+    /// Returns the center and the radius of the largest circle contained in the triangle.
+    ///
+    /// If the triangle is degenerate the radius is zero.
+    pub fn incircle(&self) -> (Point, f32) {
+        let len_a = (self.c - self.b).length();
+        let len_b = (self.a - self.c).length();
+        let len_c = (self.b - self.a).length();
+        let perimeter = len_a + len_b + len_c;
+        if perimeter == 0.0 {
+            return (self.a, 0.0);
+        }
+
+        let center = (self.a.to_vector() * len_a
+            + self.b.to_vector() * len_b
+            + self.c.to_vector() * len_c) / perimeter;
+
+        (center.to_point(), 2.0 * self.area() / perimeter)
+    }
+
+    /// Returns the point of the triangle (including its interior) that is the
+    /// closest to the provided point.
+    pub fn closest_point(&self, point: Point) -> Point {
+        // See Real-Time Collision Detection, Christer Ericson, section 5.1.5.
+        let ab = self.b - self.a;
+        let ac = self.c - self.a;
+
+        let ap = point - self.a;
+        let d1 = ab.dot(ap);
+        let d2 = ac.dot(ap);
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return self.a;
+        }
+
+        let bp = point - self.b;
+        let d3 = ab.dot(bp);
+        let d4 = ac.dot(bp);
+        if d3 >= 0.0 && d4 <= d3 {
+            return self.b;
+        }
+
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return self.a + ab * (d1 / (d1 - d3));
+        }
+
+        let cp = point - self.c;
+        let d5 = ab.dot(cp);
+        let d6 = ac.dot(cp);
+        if d6 >= 0.0 && d5 <= d6 {
+            return self.c;
+        }
+
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return self.a + ac * (d2 / (d2 - d6));
+        }
+
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+            let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+            return self.b + (self.c - self.b) * w;
+        }
+
+        // The point is inside the triangle.
+        return point;
+    }
+
+    /// Returns whether the point is inside the triangle or within `tolerance`
+    /// distance of it.
+    ///
+    /// Unlike `contains_point`, points exactly on the edges are considered inside.
+    pub fn contains_point_with_tolerance(&self, point: Point, tolerance: f32) -> bool {
+        (self.closest_point(point) - point).square_length() <= tolerance * tolerance
+    }
+
     pub fn contains_point(&self, point: Point) -> bool {
         // see http://blackpawn.com/texts/pointinpoly/
         let v0 = self.c - self.a;
@@ -100,6 +258,15 @@ impl Triangle {
             || *self == *other;
     }
 
+    /// Test for intersection between this triangle and a convex polygon.
+    ///
+    /// The polygon can be in either clockwise or counter-clockwise order.
+    /// As with `intersects`, shapes that only touch along their boundary are not
+    /// considered intersecting.
+    pub fn intersects_convex_polygon(&self, polygon: &[Point]) -> bool {
+        convex_polygons_intersect(&[self.a, self.b, self.c], polygon)
+    }
+
     /// Test for triangle-segment intersection.
     #[inline]
     pub fn intersects_line_segment(&self, segment: &LineSegment) -> bool {
@@ -141,6 +308,128 @@ fn test_triangle_contains() {
     );
 }
 
+#[test]
+fn test_triangle_contains_with_tolerance() {
+    let t = Triangle {
+        a: point(0.0, 0.0),
+        b: point(1.0, 0.0),
+        c: point(0.0, 1.0),
+    };
+
+    assert!(t.contains_point_with_tolerance(point(0.2, 0.2), 0.0));
+    assert!(t.contains_point_with_tolerance(point(0.0, 0.0), 0.0));
+    assert!(t.contains_point_with_tolerance(point(0.5, 0.5), 0.0));
+    assert!(t.contains_point_with_tolerance(point(0.5, -0.05), 0.1));
+    assert!(!t.contains_point_with_tolerance(point(0.5, -0.2), 0.1));
+    assert!(!t.contains_point_with_tolerance(point(1.0, 1.0), 0.5));
+}
+
+#[test]
+fn test_triangle_area_and_orientation() {
+    let t = Triangle {
+        a: point(0.0, 0.0),
+        b: point(2.0, 0.0),
+        c: point(0.0, 3.0),
+    };
+    let flipped = Triangle { a: t.a, b: t.c, c: t.b };
+    let flat = Triangle { a: t.a, b: t.b, c: point(4.0, 0.0) };
+
+    assert_eq!(t.area(), 3.0);
+    assert_eq!(t.signed_area(), 3.0);
+    assert_eq!(flipped.area(), 3.0);
+    assert_eq!(flipped.signed_area(), -3.0);
+    assert_eq!(flat.area(), 0.0);
+
+    assert_eq!(t.orientation(), Orientation::CounterClockwise);
+    assert_eq!(flipped.orientation(), Orientation::Clockwise);
+    assert_eq!(flat.orientation(), Orientation::Collinear);
+}
+
+#[test]
+fn test_barycentric_coordinates() {
+    let t = Triangle {
+        a: point(1.0, 1.0),
+        b: point(5.0, 1.0),
+        c: point(1.0, 5.0),
+    };
+
+    assert_eq!(t.barycentric_coordinates(t.a), (1.0, 0.0, 0.0));
+    assert_eq!(t.barycentric_coordinates(t.b), (0.0, 1.0, 0.0));
+    assert_eq!(t.barycentric_coordinates(t.c), (0.0, 0.0, 1.0));
+
+    let (u, v, w) = t.barycentric_coordinates(point(2.0, 3.0));
+    assert!((u + v + w - 1.0).abs() < 0.0001);
+    let p = t.a.to_vector() * u + t.b.to_vector() * v + t.c.to_vector() * w;
+    assert!((p - point(2.0, 3.0).to_vector()).length() < 0.0001);
+
+    // Points outside of the triangle have at least one negative coordinate.
+    let (u, v, w) = t.barycentric_coordinates(point(6.0, 6.0));
+    assert!(u < 0.0 && v > 0.0 && w > 0.0);
+}
+
+#[test]
+fn test_circumcircle_and_incircle() {
+    let t = Triangle {
+        a: point(0.0, 0.0),
+        b: point(4.0, 0.0),
+        c: point(0.0, 3.0),
+    };
+
+    let (center, radius) = t.circumcircle().unwrap();
+    assert_eq!(center, point(2.0, 1.5));
+    assert_eq!(radius, 2.5);
+
+    // 3-4-5 right triangle: the inradius is (3 + 4 - 5) / 2.
+    let (center, radius) = t.incircle();
+    assert!((center - point(1.0, 1.0)).length() < 0.0001);
+    assert!((radius - 1.0).abs() < 0.0001);
+
+    let flat = Triangle { a: t.a, b: t.b, c: point(2.0, 0.0) };
+    assert!(flat.circumcircle().is_none());
+    assert_eq!(flat.incircle().1, 0.0);
+}
+
+#[test]
+fn test_closest_point() {
+    let t = Triangle {
+        a: point(0.0, 0.0),
+        b: point(4.0, 0.0),
+        c: point(0.0, 4.0),
+    };
+
+    // Inside.
+    assert_eq!(t.closest_point(point(1.0, 1.0)), point(1.0, 1.0));
+    // Vertex regions.
+    assert_eq!(t.closest_point(point(-1.0, -1.0)), t.a);
+    assert_eq!(t.closest_point(point(5.0, -1.0)), t.b);
+    assert_eq!(t.closest_point(point(-1.0, 5.0)), t.c);
+    // Edge regions.
+    assert_eq!(t.closest_point(point(2.0, -3.0)), point(2.0, 0.0));
+    assert_eq!(t.closest_point(point(-3.0, 2.0)), point(0.0, 2.0));
+    assert_eq!(t.closest_point(point(3.0, 3.0)), point(2.0, 2.0));
+
+    // The result doesn't depend on the orientation of the triangle.
+    let flipped = Triangle { a: t.a, b: t.c, c: t.b };
+    assert_eq!(flipped.closest_point(point(3.0, 3.0)), point(2.0, 2.0));
+    assert_eq!(flipped.closest_point(point(2.0, -3.0)), point(2.0, 0.0));
+}
+
+#[test]
+fn test_from_indices() {
+    struct Vertex { position: Point }
+
+    let vertices = [
+        Vertex { position: point(0.0, 0.0) },
+        Vertex { position: point(1.0, 0.0) },
+        Vertex { position: point(1.0, 1.0) },
+        Vertex { position: point(0.0, 1.0) },
+    ];
+    let indices = [0, 1, 2, 0, 2, 3];
+
+    let t = Triangle::from_indices(&vertices, &indices, 1, |v| v.position);
+    assert_eq!(t, Triangle { a: point(0.0, 0.0), b: point(1.0, 1.0), c: point(0.0, 1.0) });
+}
+
 #[test]
 fn test_segments() {
     let t = Triangle {
@@ -198,6 +487,26 @@ fn test_triangle_intersections() {
     assert!(t4.intersects(&t4));
 }
 
+#[test]
+fn test_convex_polygon_intersection() {
+    let tri = Triangle {
+        a: point(1.0, 1.0),
+        b: point(6.0, 1.0),
+        c: point(3.0, 6.0),
+    };
+
+    let inside = [point(2.0, 2.0), point(4.0, 2.0), point(4.0, 3.0), point(2.0, 3.0)];
+    let outside = [point(7.0, 0.0), point(9.0, 0.0), point(9.0, 2.0), point(7.0, 2.0)];
+    let overlapping = [point(0.0, 0.0), point(2.0, 0.0), point(2.0, 2.0), point(0.0, 2.0)];
+    let touching = [point(1.0, 0.0), point(6.0, 0.0), point(6.0, 1.0), point(1.0, 1.0)];
+
+    assert!(tri.intersects_convex_polygon(&inside));
+    assert!(!tri.intersects_convex_polygon(&outside));
+    assert!(tri.intersects_convex_polygon(&overlapping));
+    assert!(!tri.intersects_convex_polygon(&touching));
+    assert!(tri.intersects_convex_polygon(&[tri.c, tri.b, tri.a]));
+}
+
 #[test]
 fn test_segment_intersection() {
     let tri = Triangle {
@@ -74,14 +74,21 @@ pub fn cubic_polynomial_roots(a: f32, b: f32, c: f32, d: f32) -> ArrayVec<[f32;
     let mut result = ArrayVec::new();
 
     if a.abs() < 1e-6 {
+        if b.abs() < 1e-6 {
+            // linear equation
+            if c.abs() > 1e-6 {
+                result.push(-d / c);
+            }
+            return result;
+        }
         // quadratic equation
-        let delta = b * b - 4.0 * a * c;
+        let delta = c * c - 4.0 * b * d;
         if delta > 0.0 {
             let sqrt_delta = delta.sqrt();
-            result.push((-b - sqrt_delta) / (2.0 * a));
-            result.push((-b + sqrt_delta) / (2.0 * a));
+            result.push((-c - sqrt_delta) / (2.0 * b));
+            result.push((-c + sqrt_delta) / (2.0 * b));
         } else if delta.abs() < 1e-6 {
-            result.push(-b / (2.0 * a));
+            result.push(-c / (2.0 * b));
         }
         return result;
     }
@@ -136,4 +143,7 @@ fn cubic_polynomial() {
     assert_approx_eq(cubic_polynomial_roots(2.0, -4.0, 2.0, 0.0), &[0.0, 1.0], 0.0000001);
     assert_approx_eq(cubic_polynomial_roots(-1.0, 1.0, -1.0, 1.0), &[1.0], 0.000001);
     assert_approx_eq(cubic_polynomial_roots(-2.0, 2.0, -1.0, 10.0), &[2.0], 0.00005);
+    // (t - 1) * (t - 2) = t^2 - 3t + 2
+    assert_approx_eq(cubic_polynomial_roots(0.0, 1.0, -3.0, 2.0), &[1.0, 2.0], 0.000001);
+    assert_approx_eq(cubic_polynomial_roots(0.0, 0.0, 2.0, -1.0), &[0.5], 0.000001);
 }
@@ -0,0 +1,440 @@
+//! # Paths with custom attributes
+//!
+//! A path data structure that stores a fixed number of `f32` attributes (for example colors,
+//! widths or texture ids) alongside each endpoint.
+//!
+//! ## Overview
+//!
+//! The attributes are provided to the [AttributePathBuilder](struct.AttributePathBuilder.html)
+//! along with the endpoint of each segment. Control points don't have attributes and arcs
+//! are not supported.
+//!
+//! [AttributePath::iter](struct.AttributePath.html#method.iter) yields the events along with
+//! the attributes of their endpoint, and
+//! [AttributePath::flattened_for_each](struct.AttributePath.html#method.flattened_for_each)
+//! approximates the curves with line segments and linearly interpolates the attributes at each
+//! of the generated points, which is what tessellators need to produce vertices.
+//!
+//! [AttributePath::path_iter](struct.AttributePath.html#method.path_iter) ignores the
+//! attributes so that the path can be given to anything that consumes a `PathIterator`.
+//!
+//! ## Attributes of tessellated vertices
+//!
+//! The tessellators of the `lyon_tessellation` crate don't know about attributes. Instead,
+//! give them the geometry of the path through `path_iter()`: each generated vertex then has
+//! a `VertexSource` whose `from` and `to` endpoint ids identify the segment it comes from,
+//! and `t` its position on that segment. Endpoints are numbered in the same order as the
+//! endpoints of the attribute path, so the attributes of a vertex are obtained by
+//! interpolating `endpoint_attributes(source.from.to_usize())` and
+//! `endpoint_attributes(source.to.to_usize())` with `interpolate_attributes` at `source.t`.
+//!
+//! ## Examples
+//!
+//! ```
+//! # extern crate lyon_path;
+//! # fn main() {
+//! use lyon_path::attributes::AttributePath;
+//! use lyon_path::math::point;
+//!
+//! // Two attributes per endpoint, for example a line width and an opacity.
+//! let mut builder = AttributePath::builder(2);
+//! builder.move_to(point(0.0, 0.0), &[1.0, 1.0]);
+//! builder.line_to(point(10.0, 0.0), &[2.0, 0.5]);
+//! builder.quadratic_bezier_to(point(20.0, 0.0), point(20.0, 10.0), &[4.0, 0.0]);
+//! let path = builder.build();
+//!
+//! path.flattened_for_each(0.1, &mut |event, attributes| {
+//!     println!("{:?} width: {} opacity: {}", event, attributes[0], attributes[1]);
+//! });
+//! # }
+//! ```
+
+use math::*;
+use geom::{QuadraticBezierSegment, CubicBezierSegment};
+use default::{Verb, Iter, PathSlice};
+use iterator::PathIter;
+use FlattenedEvent;
+
+/// An event of an [AttributePath](struct.AttributePath.html) along with the attributes of
+/// its endpoint.
+///
+/// `Close` events carry the attributes of the first endpoint of the sub-path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AttributeEvent<'l> {
+    MoveTo(Point, &'l [f32]),
+    LineTo(Point, &'l [f32]),
+    QuadraticTo(Point, Point, &'l [f32]),
+    CubicTo(Point, Point, Point, &'l [f32]),
+    Close(&'l [f32]),
+}
+
+/// A path data structure that stores custom attributes for each endpoint.
+///
+/// It can be created using an [AttributePathBuilder](struct.AttributePathBuilder.html).
+#[derive(Clone, Debug)]
+pub struct AttributePath {
+    points: Vec<Point>,
+    verbs: Vec<Verb>,
+    attributes: Vec<f32>,
+    num_attributes: usize,
+}
+
+impl AttributePath {
+    /// Creates an [AttributePathBuilder](struct.AttributePathBuilder.html) for a path with
+    /// `num_attributes` attributes per endpoint.
+    pub fn builder(num_attributes: usize) -> AttributePathBuilder {
+        AttributePathBuilder::new(num_attributes)
+    }
+
+    pub fn new(num_attributes: usize) -> AttributePath {
+        AttributePath {
+            points: Vec::new(),
+            verbs: Vec::new(),
+            attributes: Vec::new(),
+            num_attributes: num_attributes,
+        }
+    }
+
+    /// The number of attributes per endpoint.
+    pub fn num_attributes(&self) -> usize { self.num_attributes }
+
+    pub fn points(&self) -> &[Point] { &self.points[..] }
+
+    pub fn verbs(&self) -> &[Verb] { &self.verbs[..] }
+
+    /// The attributes of all endpoints, stored contiguously in the order of the endpoints.
+    pub fn attributes(&self) -> &[f32] { &self.attributes[..] }
+
+    /// The attributes of the n-th endpoint.
+    pub fn endpoint_attributes(&self, endpoint: usize) -> &[f32] {
+        let start = endpoint * self.num_attributes;
+        &self.attributes[start..start + self.num_attributes]
+    }
+
+    /// Returns the geometry of the path without the attributes.
+    pub fn as_slice(&self) -> PathSlice { PathSlice::new(&self.points[..], &self.verbs[..]) }
+
+    /// Iterates over the events of the path, ignoring the attributes.
+    pub fn path_iter(&self) -> PathIter<Iter> {
+        PathIter::new(Iter::new(&self.points[..], &self.verbs[..]))
+    }
+
+    /// Iterates over the events of the path along with the attributes of their endpoint.
+    pub fn iter(&self) -> AttributeIter {
+        AttributeIter {
+            points: self.points.iter(),
+            verbs: self.verbs.iter(),
+            attributes: &self.attributes[..],
+            num_attributes: self.num_attributes,
+            endpoint: 0,
+            first_endpoint: 0,
+        }
+    }
+
+    /// Approximates the curves with line segments and invokes a callback for each of the
+    /// resulting events, along with the attributes linearly interpolated at its position.
+    ///
+    /// The attributes are interpolated using the curve parameter, which doesn't necessarily
+    /// vary at a constant speed along curves.
+    pub fn flattened_for_each<F>(&self, tolerance: f32, callback: &mut F)
+    where
+        F: FnMut(FlattenedEvent, &[f32])
+    {
+        let mut interpolated = vec![0.0; self.num_attributes];
+        let mut first = point(0.0, 0.0);
+        let mut current = first;
+        // A sub-path that doesn't start with a MoveTo starts with the attributes of the
+        // first endpoint.
+        let mut current_attributes = if self.attributes.is_empty() {
+            &self.attributes[..]
+        } else {
+            self.endpoint_attributes(0)
+        };
+        for evt in self.iter() {
+            match evt {
+                AttributeEvent::MoveTo(to, attributes) => {
+                    callback(FlattenedEvent::MoveTo(to), attributes);
+                    first = to;
+                    current = to;
+                    current_attributes = attributes;
+                }
+                AttributeEvent::LineTo(to, attributes) => {
+                    callback(FlattenedEvent::LineTo(to), attributes);
+                    current = to;
+                    current_attributes = attributes;
+                }
+                AttributeEvent::QuadraticTo(ctrl, to, attributes) => {
+                    let curve = QuadraticBezierSegment { from: current, ctrl: ctrl, to: to };
+                    for (p, t) in curve.flattened_with_t(tolerance) {
+                        interpolate_attributes(current_attributes, attributes, t, &mut interpolated);
+                        callback(FlattenedEvent::LineTo(p), &interpolated);
+                    }
+                    current = to;
+                    current_attributes = attributes;
+                }
+                AttributeEvent::CubicTo(ctrl1, ctrl2, to, attributes) => {
+                    let curve = CubicBezierSegment { from: current, ctrl1: ctrl1, ctrl2: ctrl2, to: to };
+                    for (p, t) in curve.flattened_with_t(tolerance) {
+                        interpolate_attributes(current_attributes, attributes, t, &mut interpolated);
+                        callback(FlattenedEvent::LineTo(p), &interpolated);
+                    }
+                    current = to;
+                    current_attributes = attributes;
+                }
+                AttributeEvent::Close(attributes) => {
+                    callback(FlattenedEvent::Close, attributes);
+                    current = first;
+                    current_attributes = attributes;
+                }
+            }
+        }
+    }
+}
+
+/// Linearly interpolates two sets of attributes.
+pub fn interpolate_attributes(from: &[f32], to: &[f32], t: f32, output: &mut [f32]) {
+    debug_assert_eq!(from.len(), to.len());
+    debug_assert_eq!(from.len(), output.len());
+    for i in 0..output.len() {
+        output[i] = from[i] * (1.0 - t) + to[i] * t;
+    }
+}
+
+/// Builds an [AttributePath](struct.AttributePath.html).
+///
+/// Each endpoint must be provided with exactly as many attributes as specified when
+/// creating the builder.
+pub struct AttributePathBuilder {
+    path: AttributePath,
+    first_position: Point,
+    current_position: Point,
+}
+
+impl AttributePathBuilder {
+    pub fn new(num_attributes: usize) -> Self {
+        AttributePathBuilder {
+            path: AttributePath::new(num_attributes),
+            first_position: point(0.0, 0.0),
+            current_position: point(0.0, 0.0),
+        }
+    }
+
+    fn push_attributes(&mut self, attributes: &[f32]) {
+        assert_eq!(attributes.len(), self.path.num_attributes);
+        self.path.attributes.extend_from_slice(attributes);
+    }
+
+    pub fn move_to(&mut self, to: Point, attributes: &[f32]) {
+        self.push_attributes(attributes);
+        self.path.points.push(to);
+        self.path.verbs.push(Verb::MoveTo);
+        self.first_position = to;
+        self.current_position = to;
+    }
+
+    pub fn line_to(&mut self, to: Point, attributes: &[f32]) {
+        self.push_attributes(attributes);
+        self.path.points.push(to);
+        self.path.verbs.push(Verb::LineTo);
+        self.current_position = to;
+    }
+
+    pub fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point, attributes: &[f32]) {
+        self.push_attributes(attributes);
+        self.path.points.push(ctrl);
+        self.path.points.push(to);
+        self.path.verbs.push(Verb::QuadraticTo);
+        self.current_position = to;
+    }
+
+    pub fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point, attributes: &[f32]) {
+        self.push_attributes(attributes);
+        self.path.points.push(ctrl1);
+        self.path.points.push(ctrl2);
+        self.path.points.push(to);
+        self.path.verbs.push(Verb::CubicTo);
+        self.current_position = to;
+    }
+
+    /// Closes the current sub-path.
+    ///
+    /// Does nothing if no endpoint has been added yet, since there is no sub-path to close.
+    pub fn close(&mut self) {
+        if self.path.points.is_empty() {
+            return;
+        }
+        self.path.verbs.push(Verb::Close);
+        self.current_position = self.first_position;
+    }
+
+    pub fn current_position(&self) -> Point { self.current_position }
+
+    pub fn build(self) -> AttributePath { self.path }
+}
+
+/// An iterator over the events of an [AttributePath](struct.AttributePath.html).
+#[derive(Clone, Debug)]
+pub struct AttributeIter<'l> {
+    points: ::std::slice::Iter<'l, Point>,
+    verbs: ::std::slice::Iter<'l, Verb>,
+    attributes: &'l [f32],
+    num_attributes: usize,
+    endpoint: usize,
+    first_endpoint: usize,
+}
+
+impl<'l> AttributeIter<'l> {
+    fn next_attributes(&mut self) -> &'l [f32] {
+        let start = self.endpoint * self.num_attributes;
+        self.endpoint += 1;
+        &self.attributes[start..start + self.num_attributes]
+    }
+}
+
+impl<'l> Iterator for AttributeIter<'l> {
+    type Item = AttributeEvent<'l>;
+    fn next(&mut self) -> Option<AttributeEvent<'l>> {
+        return match self.verbs.next() {
+            Some(&Verb::MoveTo) => {
+                self.first_endpoint = self.endpoint;
+                let to = *self.points.next().unwrap();
+                Some(AttributeEvent::MoveTo(to, self.next_attributes()))
+            }
+            Some(&Verb::LineTo) => {
+                let to = *self.points.next().unwrap();
+                Some(AttributeEvent::LineTo(to, self.next_attributes()))
+            }
+            Some(&Verb::QuadraticTo) => {
+                let ctrl = *self.points.next().unwrap();
+                let to = *self.points.next().unwrap();
+                Some(AttributeEvent::QuadraticTo(ctrl, to, self.next_attributes()))
+            }
+            Some(&Verb::CubicTo) => {
+                let ctrl1 = *self.points.next().unwrap();
+                let ctrl2 = *self.points.next().unwrap();
+                let to = *self.points.next().unwrap();
+                Some(AttributeEvent::CubicTo(ctrl1, ctrl2, to, self.next_attributes()))
+            }
+            Some(&Verb::Close) => {
+                let start = self.first_endpoint * self.num_attributes;
+                Some(AttributeEvent::Close(&self.attributes[start..start + self.num_attributes]))
+            }
+            Some(&Verb::Arc) => {
+                unreachable!("Attribute paths don't support arcs.");
+            }
+            None => None,
+        };
+    }
+}
+
+#[cfg(test)]
+use PathEvent;
+
+#[test]
+fn attribute_path_events() {
+    let mut builder = AttributePath::builder(2);
+    builder.move_to(point(0.0, 0.0), &[0.0, 1.0]);
+    builder.line_to(point(1.0, 0.0), &[1.0, 2.0]);
+    builder.cubic_bezier_to(point(2.0, 0.0), point(2.0, 1.0), point(1.0, 1.0), &[2.0, 3.0]);
+    builder.close();
+    builder.move_to(point(5.0, 5.0), &[3.0, 4.0]);
+    builder.quadratic_bezier_to(point(6.0, 5.0), point(6.0, 6.0), &[4.0, 5.0]);
+    let path = builder.build();
+
+    assert_eq!(path.num_attributes(), 2);
+    assert_eq!(path.endpoint_attributes(3), &[3.0, 4.0]);
+
+    let events: Vec<AttributeEvent> = path.iter().collect();
+    assert_eq!(
+        events,
+        vec![
+            AttributeEvent::MoveTo(point(0.0, 0.0), &[0.0, 1.0]),
+            AttributeEvent::LineTo(point(1.0, 0.0), &[1.0, 2.0]),
+            AttributeEvent::CubicTo(point(2.0, 0.0), point(2.0, 1.0), point(1.0, 1.0), &[2.0, 3.0]),
+            AttributeEvent::Close(&[0.0, 1.0]),
+            AttributeEvent::MoveTo(point(5.0, 5.0), &[3.0, 4.0]),
+            AttributeEvent::QuadraticTo(point(6.0, 5.0), point(6.0, 6.0), &[4.0, 5.0]),
+        ]
+    );
+
+    let path_events: Vec<PathEvent> = path.path_iter().collect();
+    assert_eq!(path_events[2], PathEvent::CubicTo(point(2.0, 0.0), point(2.0, 1.0), point(1.0, 1.0)));
+    assert_eq!(path_events.len(), 6);
+}
+
+#[test]
+fn attribute_path_flattened() {
+    let mut builder = AttributePath::builder(1);
+    builder.move_to(point(0.0, 0.0), &[0.0]);
+    builder.quadratic_bezier_to(point(1.0, 0.0), point(1.0, 1.0), &[1.0]);
+    builder.line_to(point(0.0, 1.0), &[2.0]);
+    builder.close();
+    let path = builder.build();
+
+    let curve = QuadraticBezierSegment { from: point(0.0, 0.0), ctrl: point(1.0, 0.0), to: point(1.0, 1.0) };
+    let mut prev = -1.0;
+    let mut events = Vec::new();
+    path.flattened_for_each(0.01, &mut |evt, attributes| {
+        assert_eq!(attributes.len(), 1);
+        if let FlattenedEvent::LineTo(p) = evt {
+            if attributes[0] <= 1.0 {
+                // On the curve, the attribute is the curve parameter.
+                assert!((curve.sample(attributes[0]) - p).length() < 0.0001);
+            }
+        }
+        // The attribute increases along the path until the Close event.
+        if evt != FlattenedEvent::Close {
+            assert!(attributes[0] > prev);
+            prev = attributes[0];
+        }
+        events.push((evt, attributes[0]));
+    });
+
+    assert!(events.len() > 4);
+    assert_eq!(events[0], (FlattenedEvent::MoveTo(point(0.0, 0.0)), 0.0));
+    let n = events.len();
+    assert_eq!(events[n - 2], (FlattenedEvent::LineTo(point(0.0, 1.0)), 2.0));
+    assert_eq!(events[n - 1], (FlattenedEvent::Close, 0.0));
+}
+
+#[test]
+fn attribute_path_flattened_without_move_to() {
+    let mut builder = AttributePath::builder(1);
+    builder.quadratic_bezier_to(point(1.0, 0.0), point(1.0, 1.0), &[1.0]);
+    builder.cubic_bezier_to(point(1.0, 2.0), point(0.0, 2.0), point(0.0, 3.0), &[3.0]);
+    let path = builder.build();
+
+    let mut events = Vec::new();
+    path.flattened_for_each(0.01, &mut |evt, attributes| {
+        events.push((evt, attributes[0]));
+    });
+
+    assert!(events.len() > 2);
+    // The first curve uses the attributes of its endpoint.
+    assert!(events.iter().take_while(|e| e.0 != FlattenedEvent::LineTo(point(1.0, 1.0))).all(|e| e.1 == 1.0));
+    assert_eq!(events[events.len() - 1], (FlattenedEvent::LineTo(point(0.0, 3.0)), 3.0));
+}
+
+#[test]
+fn attribute_path_close_without_endpoint() {
+    let mut builder = AttributePath::builder(2);
+    builder.close();
+    let path = builder.build();
+    assert_eq!(path.iter().next(), None);
+    path.flattened_for_each(0.1, &mut |_, _| { panic!(); });
+
+    let mut builder = AttributePath::builder(2);
+    builder.close();
+    builder.line_to(point(1.0, 0.0), &[1.0, 2.0]);
+    builder.close();
+    let path = builder.build();
+    assert_eq!(path.path_iter().count(), 2);
+    assert_eq!(path.iter().last(), Some(AttributeEvent::Close(&[1.0, 2.0])));
+}
+
+#[test]
+#[should_panic]
+fn attribute_path_wrong_attribute_count() {
+    let mut builder = AttributePath::builder(2);
+    builder.move_to(point(0.0, 0.0), &[0.0]);
+}
@@ -0,0 +1,661 @@
+//! # Binary encoding
+//!
+//! A compact and versioned binary representation of [Path](../default/struct.Path.html)
+//! objects, to store precomputed paths in assets and load them without parsing.
+//!
+//! ## Overview
+//!
+//! Paths are written with `encode` using one of the following coordinate formats:
+//!
+//! - `Format::F32` stores the points as 32 bits floats. It is lossless and can be decoded
+//!   without copying the data into a `PathSlice` using `decode_slice`.
+//! - `Format::F16` stores the points as 16 bits floats.
+//! - `Format::I16` multiplies the coordinates by a scale factor and stores them as rounded
+//!   16 bits integers.
+//! - `Format::I16Delta` quantizes coordinates the same way as `I16` and stores the difference
+//!   with the previous point using a variable amount of bytes, which is a lot more compact
+//!   for paths made of many small segments.
+//!
+//! Note that the coordinate formats apply to all of the points stored in the path, including
+//! the radii and angles of arcs, which should be taken into account when choosing a scale.
+//!
+//! Any format can be decoded into a `Path` using `decode`.
+//!
+//! ## Layout
+//!
+//! All values are stored in little-endian byte order.
+//!
+//! | Offset | Size | Content                                                |
+//! |--------|------|--------------------------------------------------------|
+//! | 0      | 4    | The magic number `b"LYNP"`.                            |
+//! | 4      | 1    | The version of the encoding, currently `1`.            |
+//! | 5      | 1    | The format: `0`: F32, `1`: F16, `2`: I16, `3`: I16Delta. |
+//! | 6      | 2    | Reserved, zero.                                        |
+//! | 8      | 4    | The number of verbs (u32).                             |
+//! | 12     | 4    | The number of points (u32).                            |
+//! | 16     | 4    | The quantization scale (f32), `1.0` for float formats. |
+//! | 20     |      | The points, x and y coordinates interleaved.           |
+//! |        |      | The verbs, one byte each.                              |
+//!
+//! In the `I16Delta` format each coordinate is the difference with the same coordinate of
+//! the previous point (starting from zero) in quantized units, zigzag-encoded into an
+//! unsigned integer which is written 7 bits at a time, the high bit of each byte telling
+//! whether more bytes follow.
+//!
+//! Verbs are stored with the values of the [Verb](../default/enum.Verb.html) enum.
+//!
+//! ## Examples
+//!
+//! ```
+//! # extern crate lyon_path;
+//! # fn main() {
+//! use lyon_path::default::Path;
+//! use lyon_path::math::point;
+//! use lyon_path::builder::*;
+//! use lyon_path::binary::{encode, decode, Format};
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.quadratic_bezier_to(point(20.0, 0.0), point(20.0, 10.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let mut bytes = Vec::new();
+//! encode(path.as_slice(), Format::I16Delta { scale: 16.0 }, &mut bytes);
+//!
+//! let decoded = decode(&bytes).unwrap();
+//! assert_eq!(decoded.points(), path.points());
+//! assert_eq!(decoded.verbs(), path.verbs());
+//! # }
+//! ```
+
+use math::{Point, point};
+use default::{Path, PathSlice, Verb, num_points};
+
+use std::mem;
+use std::slice;
+
+/// The magic number at the beginning of encoded paths.
+pub const MAGIC: [u8; 4] = [b'L', b'Y', b'N', b'P'];
+
+/// The version of the encoding produced by `encode`.
+pub const VERSION: u8 = 1;
+
+const HEADER_SIZE: usize = 20;
+
+/// The way coordinates are stored.
+///
+/// See the [binary module](index.html).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Format {
+    /// 32 bits floats.
+    F32,
+    /// 16 bits floats.
+    F16,
+    /// 16 bits integers, in units of `1.0 / scale`.
+    I16 { scale: f32 },
+    /// Variable-length differences between consecutive 16 bits integers, in units of
+    /// `1.0 / scale`.
+    I16Delta { scale: f32 },
+}
+
+impl Format {
+    fn id(&self) -> u8 {
+        match *self {
+            Format::F32 => 0,
+            Format::F16 => 1,
+            Format::I16 { .. } => 2,
+            Format::I16Delta { .. } => 3,
+        }
+    }
+
+    fn scale(&self) -> f32 {
+        match *self {
+            Format::F32 | Format::F16 => 1.0,
+            Format::I16 { scale } | Format::I16Delta { scale } => scale,
+        }
+    }
+}
+
+/// The errors that can happen while decoding a path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DecodeError {
+    /// The data doesn't start with the magic number.
+    InvalidMagicNumber,
+    /// The data was encoded with an unsupported version of the encoding.
+    UnsupportedVersion(u8),
+    /// The format of the coordinates isn't known.
+    UnknownFormat(u8),
+    /// The quantization scale isn't a positive number.
+    InvalidScale,
+    /// The data ends before the end of the path.
+    UnexpectedEnd,
+    /// The data continues after the end of the path.
+    TrailingBytes,
+    /// A verb has an invalid value.
+    InvalidVerb(u8),
+    /// The number of points doesn't match the verbs.
+    InvalidPointCount,
+    /// The path can't be decoded without copying the data, because it doesn't use the `F32`
+    /// format, the data isn't 4-bytes aligned or the target isn't little-endian.
+    NotZeroCopy,
+}
+
+/// Appends the binary representation of a path to a vector of bytes.
+///
+/// Coordinates outside of the range of the chosen format are clamped.
+pub fn encode(path: PathSlice, format: Format, output: &mut Vec<u8>) {
+    let scale = format.scale();
+    debug_assert!(scale > 0.0 && scale.is_finite());
+
+    let points = path.points();
+    let verbs = path.verbs();
+
+    output.extend_from_slice(&MAGIC);
+    output.push(VERSION);
+    output.push(format.id());
+    output.extend_from_slice(&[0, 0]);
+    write_u32(verbs.len() as u32, output);
+    write_u32(points.len() as u32, output);
+    write_u32(scale.to_bits(), output);
+
+    match format {
+        Format::F32 => {
+            for p in points {
+                write_u32(p.x.to_bits(), output);
+                write_u32(p.y.to_bits(), output);
+            }
+        }
+        Format::F16 => {
+            for p in points {
+                write_u16(f32_to_f16(p.x), output);
+                write_u16(f32_to_f16(p.y), output);
+            }
+        }
+        Format::I16 { scale } => {
+            for p in points {
+                write_u16(quantize(p.x, scale) as u16, output);
+                write_u16(quantize(p.y, scale) as u16, output);
+            }
+        }
+        Format::I16Delta { scale } => {
+            let mut prev = (0, 0);
+            for p in points {
+                let x = quantize(p.x, scale) as i32;
+                let y = quantize(p.y, scale) as i32;
+                write_varint(zigzag(x - prev.0), output);
+                write_varint(zigzag(y - prev.1), output);
+                prev = (x, y);
+            }
+        }
+    }
+
+    for verb in verbs {
+        output.push(*verb as u8);
+    }
+}
+
+/// Decodes a path from its binary representation.
+///
+/// The data must contain exactly one encoded path.
+pub fn decode(bytes: &[u8]) -> Result<Path, DecodeError> {
+    let header = read_header(bytes)?;
+    let mut reader = Reader { bytes: bytes, offset: HEADER_SIZE };
+
+    // Check the size before allocating anything.
+    let min_point_size = match header.format {
+        Format::F32 => 8,
+        Format::F16 | Format::I16 { .. } => 4,
+        Format::I16Delta { .. } => 2,
+    };
+    let min_size = header.num_points.checked_mul(min_point_size)
+        .and_then(|size| size.checked_add(header.num_verbs));
+    match min_size {
+        Some(size) if size <= bytes.len() - HEADER_SIZE => {}
+        _ => { return Err(DecodeError::UnexpectedEnd); }
+    }
+
+    let mut points = Vec::with_capacity(header.num_points);
+    match header.format {
+        Format::F32 => {
+            for _ in 0..header.num_points {
+                let x = f32::from_bits(reader.u32()?);
+                let y = f32::from_bits(reader.u32()?);
+                points.push(point(x, y));
+            }
+        }
+        Format::F16 => {
+            for _ in 0..header.num_points {
+                let x = f16_to_f32(reader.u16()?);
+                let y = f16_to_f32(reader.u16()?);
+                points.push(point(x, y));
+            }
+        }
+        Format::I16 { scale } => {
+            for _ in 0..header.num_points {
+                let x = reader.u16()? as i16 as f32 / scale;
+                let y = reader.u16()? as i16 as f32 / scale;
+                points.push(point(x, y));
+            }
+        }
+        Format::I16Delta { scale } => {
+            let mut prev: (i32, i32) = (0, 0);
+            for _ in 0..header.num_points {
+                let x = prev.0.wrapping_add(unzigzag(reader.varint()?));
+                let y = prev.1.wrapping_add(unzigzag(reader.varint()?));
+                points.push(point(x as f32 / scale, y as f32 / scale));
+                prev = (x, y);
+            }
+        }
+    }
+
+    let verb_bytes = reader.bytes(header.num_verbs)?;
+    if reader.offset != bytes.len() {
+        return Err(DecodeError::TrailingBytes);
+    }
+
+    let mut verbs = Vec::with_capacity(header.num_verbs);
+    for byte in verb_bytes {
+        verbs.push(verb_from_u8(*byte)?);
+    }
+
+    check_point_count(&verbs, header.num_points)?;
+
+    Ok(Path::from_raw_parts(points, verbs))
+}
+
+/// Decodes a path encoded with the `F32` format without copying the data.
+///
+/// The data must contain exactly one encoded path and start at a 4-bytes aligned address.
+/// Zero-copy decoding is only supported on little-endian targets, other cases produce
+/// `DecodeError::NotZeroCopy`.
+pub fn decode_slice(bytes: &[u8]) -> Result<PathSlice, DecodeError> {
+    let header = read_header(bytes)?;
+    if header.format != Format::F32
+        || !cfg!(target_endian = "little")
+        || (bytes.as_ptr() as usize) % mem::align_of::<Point>() != 0 {
+        return Err(DecodeError::NotZeroCopy);
+    }
+
+    let mut reader = Reader { bytes: bytes, offset: HEADER_SIZE };
+    let point_bytes = match header.num_points.checked_mul(mem::size_of::<Point>()) {
+        Some(size) => reader.bytes(size)?,
+        None => { return Err(DecodeError::UnexpectedEnd); }
+    };
+    let verb_bytes = reader.bytes(header.num_verbs)?;
+    if reader.offset != bytes.len() {
+        return Err(DecodeError::TrailingBytes);
+    }
+
+    for byte in verb_bytes {
+        verb_from_u8(*byte)?;
+    }
+
+    // Verb is represented as a u8 and all of the values were checked above.
+    // Point is made of two f32 with a C layout, and the alignment and byte order
+    // were checked above.
+    let verbs: &[Verb] = unsafe {
+        slice::from_raw_parts(verb_bytes.as_ptr() as *const Verb, header.num_verbs)
+    };
+    let points: &[Point] = if header.num_points == 0 {
+        &[]
+    } else {
+        unsafe {
+            slice::from_raw_parts(point_bytes.as_ptr() as *const Point, header.num_points)
+        }
+    };
+
+    check_point_count(verbs, header.num_points)?;
+
+    Ok(PathSlice::new(points, verbs))
+}
+
+struct Header {
+    format: Format,
+    num_verbs: usize,
+    num_points: usize,
+}
+
+fn read_header(bytes: &[u8]) -> Result<Header, DecodeError> {
+    if bytes.len() < 4 || bytes[0..4] != MAGIC {
+        return Err(DecodeError::InvalidMagicNumber);
+    }
+    if bytes.len() < HEADER_SIZE {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+    if bytes[4] != VERSION {
+        return Err(DecodeError::UnsupportedVersion(bytes[4]));
+    }
+
+    let mut reader = Reader { bytes: bytes, offset: 8 };
+    let num_verbs = reader.u32()? as usize;
+    let num_points = reader.u32()? as usize;
+    let scale = f32::from_bits(reader.u32()?);
+
+    let format = match bytes[5] {
+        0 => Format::F32,
+        1 => Format::F16,
+        2 => Format::I16 { scale },
+        3 => Format::I16Delta { scale },
+        id => { return Err(DecodeError::UnknownFormat(id)); }
+    };
+
+    if !(scale > 0.0 && scale.is_finite()) {
+        return Err(DecodeError::InvalidScale);
+    }
+
+    Ok(Header {
+        format: format,
+        num_verbs: num_verbs,
+        num_points: num_points,
+    })
+}
+
+fn check_point_count(verbs: &[Verb], count: usize) -> Result<(), DecodeError> {
+    let expected: usize = verbs.iter().map(|verb| num_points(*verb)).sum();
+    if expected != count {
+        return Err(DecodeError::InvalidPointCount);
+    }
+
+    Ok(())
+}
+
+fn verb_from_u8(byte: u8) -> Result<Verb, DecodeError> {
+    Ok(match byte {
+        0 => Verb::MoveTo,
+        1 => Verb::LineTo,
+        2 => Verb::QuadraticTo,
+        3 => Verb::CubicTo,
+        4 => Verb::Arc,
+        5 => Verb::Close,
+        _ => { return Err(DecodeError::InvalidVerb(byte)); }
+    })
+}
+
+struct Reader<'l> {
+    bytes: &'l [u8],
+    offset: usize,
+}
+
+impl<'l> Reader<'l> {
+    fn bytes(&mut self, n: usize) -> Result<&'l [u8], DecodeError> {
+        if n > self.bytes.len() - self.offset {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let bytes = &self.bytes[self.offset..self.offset + n];
+        self.offset += n;
+
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, DecodeError> {
+        let b = self.bytes(2)?;
+        Ok(b[0] as u16 | (b[1] as u16) << 8)
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        let b = self.bytes(4)?;
+        Ok(b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16 | (b[3] as u32) << 24)
+    }
+
+    fn varint(&mut self) -> Result<u32, DecodeError> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.bytes(1)?[0];
+            if shift > 28 {
+                return Err(DecodeError::UnexpectedEnd);
+            }
+            value |= ((byte & 0x7f) as u32) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+}
+
+fn write_u16(value: u16, output: &mut Vec<u8>) {
+    output.push(value as u8);
+    output.push((value >> 8) as u8);
+}
+
+fn write_u32(value: u32, output: &mut Vec<u8>) {
+    output.push(value as u8);
+    output.push((value >> 8) as u8);
+    output.push((value >> 16) as u8);
+    output.push((value >> 24) as u8);
+}
+
+fn write_varint(mut value: u32, output: &mut Vec<u8>) {
+    while value >= 0x80 {
+        output.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+fn zigzag(value: i32) -> u32 { ((value << 1) ^ (value >> 31)) as u32 }
+
+fn unzigzag(value: u32) -> i32 { (value >> 1) as i32 ^ -((value & 1) as i32) }
+
+fn quantize(value: f32, scale: f32) -> i16 {
+    (value * scale).round().max(-32768.0).min(32767.0) as i16
+}
+
+// Rounds to the nearest representable value, ties to even.
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = (bits >> 16) & 0x8000;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        // Infinity or NaN.
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return (sign | 0x7c00 | nan) as u16;
+    }
+
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        // Too large, becomes infinity.
+        return (sign | 0x7c00) as u16;
+    }
+
+    let (value, shift) = if exponent <= 0 {
+        // Subnormal or zero.
+        if exponent < -10 {
+            return sign as u16;
+        }
+        (mantissa | 0x80_0000, (14 - exponent) as u32)
+    } else {
+        (((exponent as u32) << 23) | mantissa, 13)
+    };
+
+    let truncated = value >> shift;
+    let remainder = value & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    let rounded = if remainder > halfway || (remainder == halfway && truncated & 1 == 1) {
+        truncated + 1
+    } else {
+        truncated
+    };
+
+    // A carry out of the mantissa correctly increments the exponent.
+    (sign | rounded) as u16
+}
+
+fn f16_to_f32(value: u16) -> f32 {
+    let sign = ((value & 0x8000) as u32) << 16;
+    let exponent = ((value >> 10) & 0x1f) as u32;
+    let mantissa = (value & 0x3ff) as u32;
+
+    if exponent == 0x1f {
+        return f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13));
+    }
+
+    if exponent == 0 {
+        // Subnormal or zero.
+        let abs = mantissa as f32 * (1.0 / 16_777_216.0);
+        return if sign != 0 { -abs } else { abs };
+    }
+
+    f32::from_bits(sign | ((exponent + 127 - 15) << 23) | (mantissa << 13))
+}
+
+#[cfg(test)]
+use builder::{FlatPathBuilder, PathBuilder};
+#[cfg(test)]
+use math::{vector, Radians};
+
+#[cfg(test)]
+fn test_path() -> Path {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(20.0, 0.0), point(20.0, 10.0));
+    builder.cubic_bezier_to(point(20.0, 20.0), point(10.0, 25.5), point(-5.25, 20.0));
+    builder.close();
+    builder.move_to(point(100.0, 100.0));
+    builder.arc(point(100.0, 50.0), vector(50.0, 50.0), Radians::new(1.5), Radians::new(0.0));
+    builder.build()
+}
+
+#[test]
+fn test_round_trip_f32() {
+    let path = test_path();
+    let mut bytes = Vec::new();
+    encode(path.as_slice(), Format::F32, &mut bytes);
+
+    assert_eq!(&bytes[0..4], &MAGIC);
+    assert_eq!(bytes.len(), HEADER_SIZE + path.points().len() * 8 + path.verbs().len());
+
+    let decoded = decode(&bytes).unwrap();
+    assert_eq!(decoded.points(), path.points());
+    assert_eq!(decoded.verbs(), path.verbs());
+}
+
+#[test]
+fn test_round_trip_quantized() {
+    let path = test_path();
+
+    for &format in &[Format::F16, Format::I16 { scale: 4.0 }, Format::I16Delta { scale: 4.0 }] {
+        let mut bytes = Vec::new();
+        encode(path.as_slice(), format, &mut bytes);
+
+        let decoded = decode(&bytes).unwrap();
+        assert_eq!(decoded.verbs(), path.verbs());
+        // All of the coordinates of the test path are representable in these formats.
+        assert_eq!(decoded.points(), path.points());
+    }
+
+    let mut bytes = Vec::new();
+    encode(path.as_slice(), Format::I16 { scale: 1.0 }, &mut bytes);
+    let decoded = decode(&bytes).unwrap();
+    for (a, b) in decoded.points().iter().zip(path.points().iter()) {
+        assert!((*a - *b).length() <= 0.75);
+    }
+}
+
+#[test]
+fn test_delta_compression() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    for i in 0..100 {
+        builder.line_to(point(i as f32 * 0.5, (i % 2) as f32));
+    }
+    let path = builder.build();
+
+    let mut quantized = Vec::new();
+    let mut delta = Vec::new();
+    encode(path.as_slice(), Format::I16 { scale: 2.0 }, &mut quantized);
+    encode(path.as_slice(), Format::I16Delta { scale: 2.0 }, &mut delta);
+
+    assert!(delta.len() < quantized.len());
+    assert_eq!(decode(&delta).unwrap().points(), path.points());
+}
+
+#[test]
+fn test_f16() {
+    for &v in &[0.0, -0.0, 1.0, -2.5, 0.1, 65504.0, 6.1035156e-5, 5.9604645e-8] {
+        let h = f16_to_f32(f32_to_f16(v));
+        assert!((h - v).abs() <= v.abs() / 1024.0, "{} -> {}", v, h);
+    }
+    assert_eq!(f16_to_f32(f32_to_f16(1.0e-9)), 0.0);
+    assert_eq!(f16_to_f32(f32_to_f16(1.0e6)), ::std::f32::INFINITY);
+    assert!(f16_to_f32(f32_to_f16(::std::f32::NAN)).is_nan());
+    // Rounds to the nearest representable value.
+    assert_eq!(f16_to_f32(f32_to_f16(2049.0)), 2048.0);
+    assert_eq!(f16_to_f32(f32_to_f16(2051.0)), 2052.0);
+}
+
+#[test]
+fn test_decode_slice() {
+    let path = test_path();
+    let mut bytes = Vec::new();
+    encode(path.as_slice(), Format::F32, &mut bytes);
+
+    // Copy the data into a 4-bytes aligned buffer.
+    let mut words = vec![0u32; (bytes.len() + 3) / 4];
+    let aligned: &mut [u8] = unsafe {
+        slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, bytes.len())
+    };
+    aligned.copy_from_slice(&bytes);
+
+    let slice = decode_slice(aligned).unwrap();
+    assert_eq!(slice.points(), path.points());
+    assert_eq!(slice.verbs(), path.verbs());
+    assert_eq!(slice.points().as_ptr() as usize, aligned.as_ptr() as usize + HEADER_SIZE);
+
+    assert_eq!(decode_slice(&aligned[1..]).err(), Some(DecodeError::InvalidMagicNumber));
+
+    let mut bytes = Vec::new();
+    encode(path.as_slice(), Format::F16, &mut bytes);
+    assert_eq!(decode_slice(&bytes).err(), Some(DecodeError::NotZeroCopy));
+}
+
+#[test]
+fn test_decode_errors() {
+    let path = test_path();
+    let mut bytes = Vec::new();
+    encode(path.as_slice(), Format::I16Delta { scale: 1.0 }, &mut bytes);
+    let len = bytes.len();
+
+    assert_eq!(decode(&bytes[..len - 1]).err(), Some(DecodeError::UnexpectedEnd));
+    assert_eq!(decode(&bytes[..10]).err(), Some(DecodeError::UnexpectedEnd));
+    assert_eq!(decode(&[]).err(), Some(DecodeError::InvalidMagicNumber));
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert_eq!(decode(&trailing).err(), Some(DecodeError::TrailingBytes));
+
+    let mut version = bytes.clone();
+    version[4] = 2;
+    assert_eq!(decode(&version).err(), Some(DecodeError::UnsupportedVersion(2)));
+
+    let mut format = bytes.clone();
+    format[5] = 9;
+    assert_eq!(decode(&format).err(), Some(DecodeError::UnknownFormat(9)));
+
+    let mut verb = bytes.clone();
+    verb[len - 1] = 42;
+    assert_eq!(decode(&verb).err(), Some(DecodeError::InvalidVerb(42)));
+
+    // Replace the final arc with a line.
+    let mut count = bytes.clone();
+    count[len - 1] = Verb::LineTo as u8;
+    assert_eq!(decode(&count).err(), Some(DecodeError::InvalidPointCount));
+}
+
+#[test]
+fn test_empty_path() {
+    let path = Path::new();
+    let mut bytes = Vec::new();
+    encode(path.as_slice(), Format::F32, &mut bytes);
+    assert_eq!(bytes.len(), HEADER_SIZE);
+
+    let decoded = decode(&bytes).unwrap();
+    assert!(decoded.points().is_empty());
+    assert!(decoded.verbs().is_empty());
+}
@@ -0,0 +1,823 @@
+//! # Boolean operations
+//!
+//! Compute the union, intersection, difference or exclusive-or of two paths.
+//!
+//! The input paths are flattened, the edges are split at their intersections and each
+//! resulting edge is kept or discarded depending on whether the areas on each side of it
+//! are inside of the result. Each input is stored in a
+//! [SegmentIndex](../spatial_index/struct.SegmentIndex.html) to find what is inside of it
+//! efficiently. The remaining edges are then chained into closed sub-paths.
+//!
+//! The inputs can be any `PathIterator` and each input can use its own fill rule. Sub-paths
+//! that are not explicitly closed are treated as if they were. In the output, outer contours
+//! are in counter-clockwise order and holes in clockwise order, assuming the y axis points
+//! upwards. The output doesn't have overlapping sub-paths, so it can be filled with either
+//! fill rule.
+//!
+//! `boolean_op` produces flattened results while `boolean_op_with_curves` replaces
+//! the parts of the result that follow curves of the input with pieces of the original curves.
+//!
+//! ## Examples
+//!
+//! ```
+//! # extern crate lyon_path;
+//! # fn main() {
+//! use lyon_path::default::Path;
+//! use lyon_path::math::point;
+//! use lyon_path::builder::*;
+//! use lyon_path::boolean::{boolean_op, BooleanOp};
+//! use lyon_path::FillRule;
+//!
+//! let mut builder = Path::builder();
+//! builder.polygon(&[point(0.0, 0.0), point(2.0, 0.0), point(2.0, 2.0), point(0.0, 2.0)]);
+//! let a = builder.build();
+//!
+//! let mut builder = Path::builder();
+//! builder.polygon(&[point(1.0, 1.0), point(3.0, 1.0), point(3.0, 3.0), point(1.0, 3.0)]);
+//! let b = builder.build();
+//!
+//! let mut output = Path::builder();
+//! boolean_op(
+//!     BooleanOp::Union,
+//!     a.path_iter(), FillRule::EvenOdd,
+//!     b.path_iter(), FillRule::EvenOdd,
+//!     0.01,
+//!     &mut output,
+//! );
+//! let union = output.build();
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use math::{Point, F64Point};
+use builder::{FlatPathBuilder, PathBuilder};
+use iterator::{PathIterator, PathIter};
+use geom::predicates::orient2d_f64;
+use spatial_index::SegmentIndex;
+use {PathEvent, PathSegment, FlattenedEvent, PathState, FillRule};
+
+/// The type of boolean operation to apply.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// The areas covered by either input.
+    Union,
+    /// The areas covered by both inputs.
+    Intersection,
+    /// The areas covered by the first input and not by the second one.
+    Difference,
+    /// The areas covered by exactly one of the inputs.
+    Xor,
+}
+
+impl BooleanOp {
+    #[inline]
+    fn is_in(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            BooleanOp::Union => in_a || in_b,
+            BooleanOp::Intersection => in_a && in_b,
+            BooleanOp::Difference => in_a && !in_b,
+            BooleanOp::Xor => in_a != in_b,
+        }
+    }
+}
+
+/// Computes a boolean operation between two paths and writes the flattened result
+/// into a builder.
+///
+/// The curves of the inputs are approximated with line segments according to the
+/// `tolerance` threshold.
+pub fn boolean_op<A, B, Output>(
+    op: BooleanOp,
+    a: A, a_fill_rule: FillRule,
+    b: B, b_fill_rule: FillRule,
+    tolerance: f32,
+    output: &mut Output,
+)
+where
+    A: PathIterator,
+    B: PathIterator,
+    Output: FlatPathBuilder,
+{
+    let (_, contours) = compute(op, a, a_fill_rule, b, b_fill_rule, tolerance);
+
+    for contour in &contours {
+        output.move_to(to_f32(contour[0].from));
+        for (i, edge) in contour.iter().enumerate() {
+            let next = &contour[(i + 1) % contour.len()];
+            if i + 1 < contour.len() && !can_merge(edge, next) {
+                output.line_to(to_f32(edge.to));
+            }
+        }
+        output.close();
+    }
+}
+
+/// Computes a boolean operation between two paths and writes the result into a builder,
+/// preserving the curves of the inputs where possible.
+///
+/// The intersections are computed on flattened approximations of the inputs according to the
+/// `tolerance` threshold. The parts of the result that follow a curve of the input are then
+/// replaced with the corresponding portion of the original curve.
+pub fn boolean_op_with_curves<A, B, Output>(
+    op: BooleanOp,
+    a: A, a_fill_rule: FillRule,
+    b: B, b_fill_rule: FillRule,
+    tolerance: f32,
+    output: &mut Output,
+)
+where
+    A: PathIterator,
+    B: PathIterator,
+    Output: PathBuilder,
+{
+    let (curves, contours) = compute(op, a, a_fill_rule, b, b_fill_rule, tolerance);
+
+    for contour in &contours {
+        output.move_to(to_f32(contour[0].from));
+        let mut i = 0;
+        while i < contour.len() {
+            let edge = &contour[i];
+            let is_last = i + 1 == contour.len();
+
+            let curve = edge.curve.map(|index| &curves[edge.operand][index]);
+            if let Some(&Some(ref curve)) = curve {
+                // Gather the following edges that continue along the same curve.
+                let mut end = i;
+                while end + 1 < contour.len() {
+                    let next = &contour[end + 1];
+                    if next.operand != edge.operand || next.curve != edge.curve
+                        || next.t_from != contour[end].t_to {
+                        break;
+                    }
+                    end += 1;
+                }
+
+                let t_from = edge.t_from;
+                let t_to = contour[end].t_to;
+                let to = to_f32(contour[end].to);
+                if t_from != t_to {
+                    emit_sub_curve(curve, t_from, t_to, to, tolerance, output);
+                } else if end + 1 < contour.len() {
+                    output.line_to(to);
+                }
+
+                i = end + 1;
+                continue;
+            }
+
+            if !is_last && !can_merge(edge, &contour[i + 1]) {
+                output.line_to(to_f32(edge.to));
+            }
+            i += 1;
+        }
+        output.close();
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Edge {
+    from: F64Point,
+    to: F64Point,
+    operand: usize,
+    // Index of the event in the operand's path if this edge approximates a curve.
+    curve: Option<usize>,
+    // Parameters of the edge's endpoints on the curve.
+    t_from: f32,
+    t_to: f32,
+}
+
+impl Edge {
+    fn flip(&self) -> Self {
+        Edge {
+            from: self.to,
+            to: self.from,
+            t_from: self.t_to,
+            t_to: self.t_from,
+            .. *self
+        }
+    }
+}
+
+// Adds the part of a curve between two parameters, ending at a given position.
+fn emit_sub_curve<Output: PathBuilder>(
+    curve: &PathSegment,
+    t_from: f32,
+    t_to: f32,
+    to: Point,
+    tolerance: f32,
+    output: &mut Output,
+) {
+    match curve.split_range(t_from, t_to) {
+        PathSegment::Line(..) => {
+            output.line_to(to);
+        }
+        PathSegment::Quadratic(sub_curve) => {
+            output.quadratic_bezier_to(sub_curve.ctrl, to);
+        }
+        PathSegment::Cubic(sub_curve) => {
+            output.cubic_bezier_to(sub_curve.ctrl1, sub_curve.ctrl2, to);
+        }
+        PathSegment::Arc(sub_arc) => {
+            output.arc(sub_arc.center, sub_arc.radii, sub_arc.sweep_angle, sub_arc.x_rotation);
+            // The end of the arc is computed from the curve. If it doesn't match the
+            // expected position, connect them so that the contour stays closed.
+            if (sub_arc.to() - to).square_length() > ARC_END_EPSILON * tolerance * tolerance {
+                output.line_to(to);
+            }
+        }
+    }
+}
+
+// Squared fraction of the tolerance under which the end of an arc is considered to be
+// at the expected position.
+const ARC_END_EPSILON: f32 = 0.0001;
+
+// Returns the curves of the input (indexed by event) and the output contours.
+fn compute<A, B>(
+    op: BooleanOp,
+    a: A, a_fill_rule: FillRule,
+    b: B, b_fill_rule: FillRule,
+    tolerance: f32,
+) -> ([Vec<Option<PathSegment>>; 2], Vec<Vec<Edge>>)
+where
+    A: PathIterator,
+    B: PathIterator,
+{
+    let mut a_edges = Vec::new();
+    let mut b_edges = Vec::new();
+    let a_curves = flatten_operand(a, 0, tolerance, &mut a_edges);
+    let b_curves = flatten_operand(b, 1, tolerance, &mut b_edges);
+
+    let mut all_edges = a_edges.clone();
+    all_edges.extend_from_slice(&b_edges);
+    let edges = split_edges(&all_edges);
+
+    let fill_rules = [a_fill_rule, b_fill_rule];
+    let indices = [index_operand(&a_edges, tolerance), index_operand(&b_edges, tolerance)];
+    let is_in = |p: F64Point| {
+        let p = to_f32(p);
+        let in_a = fill_rules[0].is_in(indices[0].winding_number(p));
+        let in_b = fill_rules[1].is_in(indices[1].winding_number(p));
+        op.is_in(in_a, in_b)
+    };
+
+    let max_offset = tolerance as f64 * 0.01;
+    let mut kept = Vec::new();
+    for edge in &edges {
+        let v = edge.to - edge.from;
+        let length = v.length();
+        let mid = edge.from + v * 0.5;
+        // The sample points are rounded to f32 precision, make sure that it doesn't bring
+        // them back onto the edge.
+        let min_offset = mid.x.abs().max(mid.y.abs()) * F32_RELATIVE_PRECISION;
+        let offset = (length * 0.001).min(max_offset).max(min_offset);
+        let normal = F64Point::new(-v.y, v.x).to_vector() * (offset / length);
+
+        let left = is_in(mid + normal);
+        let right = is_in(mid - normal);
+        if left == right {
+            continue;
+        }
+
+        // Orient the edges so that the inside of the result is on their left side.
+        kept.push(if left { *edge } else { edge.flip() });
+    }
+
+    ([a_curves, b_curves], build_contours(&kept))
+}
+
+fn flatten_operand<Iter: PathIterator>(
+    it: Iter,
+    operand: usize,
+    tolerance: f32,
+    edges: &mut Vec<Edge>,
+) -> Vec<Option<PathSegment>> {
+    let events: Vec<PathEvent> = it.collect();
+
+    let mut curves = Vec::with_capacity(events.len());
+    let mut state = PathState::new();
+    for evt in &events {
+        let from = state.current;
+        // Only curves are kept, line segments are not replaced in the output.
+        curves.push(match PathSegment::from_event(from, *evt) {
+            Some(PathSegment::Line(..)) => None,
+            segment => segment,
+        });
+        state.path_event(*evt);
+    }
+
+    let mut first = None;
+    let mut prev = F64Point::new(0.0, 0.0);
+    let mut prev_t: Option<(usize, f32)> = None;
+    let close = |edges: &mut Vec<Edge>, prev: F64Point, first: Option<F64Point>| {
+        if let Some(first) = first {
+            if first != prev {
+                edges.push(Edge {
+                    from: prev, to: first,
+                    operand: operand, curve: None,
+                    t_from: 0.0, t_to: 1.0,
+                });
+            }
+        }
+    };
+
+    for evt in PathIter::new(events.iter().cloned()).flattened_with_t(tolerance) {
+        match evt.event {
+            FlattenedEvent::MoveTo(to) => {
+                close(edges, prev, first);
+                first = Some(to.to_f64());
+                prev = to.to_f64();
+            }
+            FlattenedEvent::LineTo(to) => {
+                let to = to.to_f64();
+                if first.is_none() {
+                    first = Some(prev);
+                }
+                if to != prev {
+                    let curve = if curves[evt.segment_index].is_some() {
+                        Some(evt.segment_index)
+                    } else {
+                        None
+                    };
+                    let t_from = match prev_t {
+                        Some((index, t)) if index == evt.segment_index => t,
+                        _ => 0.0,
+                    };
+                    edges.push(Edge {
+                        from: prev, to: to,
+                        operand: operand, curve: curve,
+                        t_from: t_from, t_to: evt.t,
+                    });
+                }
+                prev = to;
+            }
+            FlattenedEvent::Close => {
+                close(edges, prev, first);
+                if let Some(first) = first {
+                    prev = first;
+                }
+            }
+        }
+        prev_t = Some((evt.segment_index, evt.t));
+    }
+    close(edges, prev, first);
+
+    curves
+}
+
+// About eight times the relative precision of f32 numbers.
+const F32_RELATIVE_PRECISION: f64 = 0.000001;
+
+// Builds a spatial index over the edges of an operand, which form closed loops.
+fn index_operand(edges: &[Edge], tolerance: f32) -> SegmentIndex {
+    let mut events = Vec::with_capacity(edges.len() + 1);
+    let mut current = None;
+    for edge in edges {
+        if current != Some(edge.from) {
+            events.push(PathEvent::MoveTo(to_f32(edge.from)));
+        }
+        events.push(PathEvent::LineTo(to_f32(edge.to)));
+        current = Some(edge.to);
+    }
+
+    SegmentIndex::new(PathIter::new(events.into_iter()), tolerance)
+}
+
+// Splits the edges at their intersections and removes duplicate edges.
+fn split_edges(edges: &[Edge]) -> Vec<Edge> {
+    let mut splits: Vec<Vec<F64Point>> = vec![Vec::new(); edges.len()];
+
+    let x_range = |e: &Edge| {
+        if e.from.x < e.to.x { (e.from.x, e.to.x) } else { (e.to.x, e.from.x) }
+    };
+    let y_range = |e: &Edge| {
+        if e.from.y < e.to.y { (e.from.y, e.to.y) } else { (e.to.y, e.from.y) }
+    };
+
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    order.sort_by(|&i, &j| {
+        x_range(&edges[i]).0.partial_cmp(&x_range(&edges[j]).0).unwrap_or(Ordering::Equal)
+    });
+
+    for (k, &i) in order.iter().enumerate() {
+        let (_, max_x) = x_range(&edges[i]);
+        let (min_y, max_y) = y_range(&edges[i]);
+        for &j in &order[k + 1..] {
+            if x_range(&edges[j]).0 > max_x {
+                break;
+            }
+            let (other_min_y, other_max_y) = y_range(&edges[j]);
+            if other_min_y > max_y || other_max_y < min_y {
+                continue;
+            }
+
+            add_intersections(&edges[i], &edges[j], i, j, &mut splits);
+        }
+    }
+
+    let mut result = Vec::with_capacity(edges.len());
+    let mut unique = HashSet::new();
+    for (edge, edge_splits) in edges.iter().zip(splits.iter()) {
+        let v = edge.to - edge.from;
+        let square_length = v.square_length();
+        let mut params: Vec<(f64, F64Point)> = edge_splits.iter().map(|p| {
+            ((*p - edge.from).dot(v) / square_length, *p)
+        }).collect();
+        params.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        params.push((1.0, edge.to));
+
+        let mut prev = (0.0, edge.from);
+        for &(s, p) in &params {
+            if p == prev.1 {
+                continue;
+            }
+
+            let key = edge_key(prev.1, p);
+            if unique.insert(key) {
+                result.push(Edge {
+                    from: prev.1,
+                    to: p,
+                    t_from: lerp_t(edge, prev.0),
+                    t_to: lerp_t(edge, s),
+                    .. *edge
+                });
+            }
+
+            prev = (s, p);
+        }
+    }
+
+    result
+}
+
+fn add_intersections(e1: &Edge, e2: &Edge, i: usize, j: usize, splits: &mut [Vec<F64Point>]) {
+    let o1 = orient2d_f64(e1.from, e1.to, e2.from);
+    let o2 = orient2d_f64(e1.from, e1.to, e2.to);
+    let o3 = orient2d_f64(e2.from, e2.to, e1.from);
+    let o4 = orient2d_f64(e2.from, e2.to, e1.to);
+
+    if o1 * o2 < 0.0 && o3 * o4 < 0.0 {
+        // The segments cross.
+        let v1 = e1.to - e1.from;
+        let v2 = e2.to - e2.from;
+        let t = (e2.from - e1.from).cross(v2) / v1.cross(v2);
+        let p = e1.from + v1 * t;
+        splits[i].push(p);
+        splits[j].push(p);
+        return;
+    }
+
+    // Endpoints lying on the other segment.
+    if o1 == 0.0 && is_strictly_between(e1.from, e1.to, e2.from) {
+        splits[i].push(e2.from);
+    }
+    if o2 == 0.0 && is_strictly_between(e1.from, e1.to, e2.to) {
+        splits[i].push(e2.to);
+    }
+    if o3 == 0.0 && is_strictly_between(e2.from, e2.to, e1.from) {
+        splits[j].push(e1.from);
+    }
+    if o4 == 0.0 && is_strictly_between(e2.from, e2.to, e1.to) {
+        splits[j].push(e1.to);
+    }
+}
+
+// Assuming p is on the line (from, to), returns whether p is strictly between from and to.
+fn is_strictly_between(from: F64Point, to: F64Point, p: F64Point) -> bool {
+    let v = to - from;
+    let d = (p - from).dot(v);
+    d > 0.0 && d < v.square_length()
+}
+
+fn lerp_t(edge: &Edge, s: f64) -> f32 {
+    if s <= 0.0 {
+        return edge.t_from;
+    }
+    if s >= 1.0 {
+        return edge.t_to;
+    }
+
+    edge.t_from + (edge.t_to - edge.t_from) * s as f32
+}
+
+fn point_key(p: F64Point) -> (u64, u64) {
+    // Adding zero turns -0.0 into 0.0.
+    ((p.x + 0.0).to_bits(), (p.y + 0.0).to_bits())
+}
+
+fn edge_key(a: F64Point, b: F64Point) -> ((u64, u64), (u64, u64)) {
+    let ka = point_key(a);
+    let kb = point_key(b);
+    if ka < kb { (ka, kb) } else { (kb, ka) }
+}
+
+// Chains the edges into closed contours.
+fn build_contours(edges: &[Edge]) -> Vec<Vec<Edge>> {
+    let mut outgoing: HashMap<(u64, u64), Vec<usize>> = HashMap::new();
+    for (i, edge) in edges.iter().enumerate() {
+        outgoing.entry(point_key(edge.from)).or_insert_with(Vec::new).push(i);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut contours = Vec::new();
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+
+        let mut contour = Vec::new();
+        let mut current = start;
+        loop {
+            used[current] = true;
+            let edge = edges[current];
+            contour.push(edge);
+
+            if edge.to == edges[start].from {
+                break;
+            }
+
+            // When several edges start at the same point, pick the one that turns the most
+            // to the right, so that the inside of the contour stays on its left.
+            let v_in = edge.to - edge.from;
+            let mut best: Option<(usize, f64)> = None;
+            if let Some(candidates) = outgoing.get(&point_key(edge.to)) {
+                for &candidate in candidates {
+                    if used[candidate] {
+                        continue;
+                    }
+                    let v_out = edges[candidate].to - edges[candidate].from;
+                    let angle = v_in.cross(v_out).atan2(v_in.dot(v_out));
+                    let is_better = match best {
+                        Some((_, best_angle)) => angle < best_angle,
+                        None => true,
+                    };
+                    if is_better {
+                        best = Some((candidate, angle));
+                    }
+                }
+            }
+
+            match best {
+                Some((next, _)) => { current = next; }
+                // Should not happen unless precision issues broke the topology.
+                None => { break; }
+            }
+        }
+
+        if contour.len() >= 2 {
+            contours.push(contour);
+        }
+    }
+
+    contours
+}
+
+// Returns whether the vertex between two consecutive straight edges can be omitted.
+fn can_merge(e1: &Edge, e2: &Edge) -> bool {
+    e1.curve.is_none() && e2.curve.is_none()
+        && orient2d_f64(e1.from, e1.to, e2.to) == 0.0
+        && (e1.to - e1.from).dot(e2.to - e2.from) > 0.0
+}
+
+#[inline]
+fn to_f32(p: F64Point) -> Point {
+    Point::new(p.x as f32, p.y as f32)
+}
+
+#[cfg(test)]
+use default::Path;
+#[cfg(test)]
+use builder::PolygonBuilder;
+#[cfg(test)]
+use math::point;
+#[cfg(test)]
+use test_utils::{square, assert_area_and_count};
+
+#[cfg(test)]
+fn run(op: BooleanOp, a: &Path, b: &Path) -> Path {
+    let mut builder = Path::builder();
+    boolean_op(
+        op,
+        a.path_iter(), FillRule::EvenOdd,
+        b.path_iter(), FillRule::EvenOdd,
+        0.01,
+        &mut builder,
+    );
+    builder.build()
+}
+
+#[test]
+fn overlapping_squares() {
+    let a = square(0.0, 0.0, 2.0);
+    let b = square(1.0, 1.0, 2.0);
+
+    assert_area_and_count(&run(BooleanOp::Union, &a, &b), 7.0, 1);
+    assert_area_and_count(&run(BooleanOp::Intersection, &a, &b), 1.0, 1);
+    assert_area_and_count(&run(BooleanOp::Difference, &a, &b), 3.0, 1);
+    assert_area_and_count(&run(BooleanOp::Difference, &b, &a), 3.0, 1);
+    assert_area_and_count(&run(BooleanOp::Xor, &a, &b), 6.0, 2);
+}
+
+#[test]
+fn disjoint_and_nested_squares() {
+    let a = square(0.0, 0.0, 2.0);
+    let b = square(5.0, 0.0, 2.0);
+    let c = square(0.5, 0.5, 1.0);
+
+    assert_area_and_count(&run(BooleanOp::Union, &a, &b), 8.0, 2);
+    assert_area_and_count(&run(BooleanOp::Intersection, &a, &b), 0.0, 0);
+
+    assert_area_and_count(&run(BooleanOp::Union, &a, &c), 4.0, 1);
+    assert_area_and_count(&run(BooleanOp::Intersection, &a, &c), 1.0, 1);
+    // A square with a hole: the hole is in the opposite orientation.
+    assert_area_and_count(&run(BooleanOp::Difference, &a, &c), 3.0, 2);
+}
+
+#[test]
+fn squares_sharing_an_edge() {
+    let a = square(0.0, 0.0, 1.0);
+    let b = square(1.0, 0.0, 1.0);
+
+    let union = run(BooleanOp::Union, &a, &b);
+    assert_area_and_count(&union, 2.0, 1);
+    // The shared edge is removed and the collinear edges are merged.
+    let line_count = union.iter().filter(|evt| match *evt {
+        PathEvent::LineTo(..) => true,
+        _ => false,
+    }).count();
+    assert!(line_count <= 4);
+
+    assert_area_and_count(&run(BooleanOp::Intersection, &a, &b), 0.0, 0);
+    assert_area_and_count(&run(BooleanOp::Difference, &a, &b), 1.0, 1);
+    assert_area_and_count(&run(BooleanOp::Xor, &a, &b), 2.0, 1);
+
+    // Identical squares.
+    assert_area_and_count(&run(BooleanOp::Union, &a, &a), 1.0, 1);
+    assert_area_and_count(&run(BooleanOp::Intersection, &a, &a), 1.0, 1);
+    assert_area_and_count(&run(BooleanOp::Xor, &a, &a), 0.0, 0);
+}
+
+#[test]
+fn fill_rules() {
+    // Two nested squares with the same orientation.
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, 0.0), point(4.0, 0.0), point(4.0, 4.0), point(0.0, 4.0)]);
+    builder.polygon(&[point(1.0, 1.0), point(3.0, 1.0), point(3.0, 3.0), point(1.0, 3.0)]);
+    let a = builder.build();
+    let empty = Path::new();
+
+    for &(fill_rule, expected_area, expected_count) in &[
+        (FillRule::EvenOdd, 12.0, 2),
+        (FillRule::NonZero, 16.0, 1),
+    ] {
+        let mut builder = Path::builder();
+        boolean_op(
+            BooleanOp::Union,
+            a.path_iter(), fill_rule,
+            empty.path_iter(), FillRule::EvenOdd,
+            0.01,
+            &mut builder,
+        );
+        assert_area_and_count(&builder.build(), expected_area, expected_count);
+    }
+}
+
+#[test]
+fn preserve_curves() {
+    use builder::PathBuilder;
+
+    // A circle of radius 1 made of four cubic bézier curves.
+    let k = 0.5522848;
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 0.0));
+    builder.cubic_bezier_to(point(1.0, k), point(k, 1.0), point(0.0, 1.0));
+    builder.cubic_bezier_to(point(-k, 1.0), point(-1.0, k), point(-1.0, 0.0));
+    builder.cubic_bezier_to(point(-1.0, -k), point(-k, -1.0), point(0.0, -1.0));
+    builder.cubic_bezier_to(point(k, -1.0), point(1.0, -k), point(1.0, 0.0));
+    builder.close();
+    let circle = builder.build();
+
+    // Covers the upper half of the circle.
+    let half = square(-2.0, 0.0, 4.0);
+
+    let mut builder = Path::builder();
+    boolean_op_with_curves(
+        BooleanOp::Intersection,
+        circle.path_iter(), FillRule::EvenOdd,
+        half.path_iter(), FillRule::EvenOdd,
+        0.01,
+        &mut builder,
+    );
+    let result = builder.build();
+
+    let curve_count = result.iter().filter(|evt| match *evt {
+        PathEvent::CubicTo(..) => true,
+        _ => false,
+    }).count();
+    assert_eq!(curve_count, 2);
+    assert_area_and_count(&result, ::std::f32::consts::PI * 0.5, 1);
+
+    let mut builder = Path::builder();
+    boolean_op_with_curves(
+        BooleanOp::Difference,
+        half.path_iter(), FillRule::EvenOdd,
+        circle.path_iter(), FillRule::EvenOdd,
+        0.01,
+        &mut builder,
+    );
+    let result = builder.build();
+    assert_area_and_count(&result, 16.0 - ::std::f32::consts::PI * 0.5, 1);
+}
+
+#[test]
+fn many_edges() {
+    use std::f32::consts::PI;
+
+    // Two overlapping regular polygons with many vertices, far from the origin.
+    let polygon = |cx: f32, cy: f32| {
+        let n = 2000;
+        let points: Vec<Point> = (0..n).map(|i| {
+            let a = i as f32 * 2.0 * PI / n as f32;
+            point(cx + 100.0 * a.cos(), cy + 100.0 * a.sin())
+        }).collect();
+        let mut builder = Path::builder();
+        builder.polygon(&points);
+        builder.build()
+    };
+    let a = polygon(10000.0, 10000.0);
+    let b = polygon(10100.0, 10000.0);
+
+    // Compute the areas relative to the sub-paths to avoid losing precision.
+    let area = |path: &Path| ::orientation::signed_area(path.path_iter(), 0.01);
+    let intersection_path = run(BooleanOp::Intersection, &a, &b);
+    let area_a = area(&a);
+    let intersection = area(&intersection_path);
+    let union = area(&run(BooleanOp::Union, &a, &b));
+    let count = intersection_path.subpaths().count();
+
+    // Area of the intersection of two disks of radius r which centers are r apart.
+    let expected = 100.0 * 100.0 * (2.0 * PI / 3.0 - 3.0f32.sqrt() / 2.0);
+    assert_eq!(count, 1);
+    assert!((intersection - expected).abs() < expected * 0.001);
+    assert!((union - (2.0 * area_a - intersection)).abs() < area_a * 0.001);
+}
+
+#[test]
+fn nan_coordinates() {
+    // Invalid input shouldn't make the operation panic. The path builder doesn't accept
+    // NaN so the events are provided directly.
+    let events = [
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(::std::f32::NAN, 1.0)),
+        PathEvent::LineTo(point(2.0, 2.0)),
+        PathEvent::LineTo(point(0.0, 2.0)),
+        PathEvent::Close,
+    ];
+    let b = square(1.0, 1.0, 2.0);
+
+    for &op in &[BooleanOp::Union, BooleanOp::Intersection, BooleanOp::Difference, BooleanOp::Xor] {
+        let mut builder = Path::builder();
+        boolean_op(
+            op,
+            PathIter::new(events.iter().cloned()), FillRule::EvenOdd,
+            b.path_iter(), FillRule::EvenOdd,
+            0.01,
+            &mut builder,
+        );
+    }
+}
+
+#[test]
+fn preserve_arcs() {
+    use builder::PathBuilder;
+    use math::{vector, Radians};
+
+    // A circle of radius 1 made of two arcs.
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 0.0));
+    builder.arc(point(0.0, 0.0), vector(1.0, 1.0), Radians::new(::std::f32::consts::PI), Radians::new(0.0));
+    builder.arc(point(0.0, 0.0), vector(1.0, 1.0), Radians::new(::std::f32::consts::PI), Radians::new(0.0));
+    builder.close();
+    let circle = builder.build();
+
+    // Covers the upper half of the circle.
+    let half = square(-2.0, 0.0, 4.0);
+
+    let mut builder = Path::builder();
+    boolean_op_with_curves(
+        BooleanOp::Intersection,
+        circle.path_iter(), FillRule::EvenOdd,
+        half.path_iter(), FillRule::EvenOdd,
+        0.01,
+        &mut builder,
+    );
+    let result = builder.build();
+
+    // The arc isn't followed by a degenerate line segment.
+    let events: Vec<PathEvent> = result.iter().collect();
+    assert_eq!(events.iter().filter(|evt| match **evt { PathEvent::Arc(..) => true, _ => false }).count(), 1);
+    assert_eq!(events.iter().filter(|evt| match **evt { PathEvent::LineTo(..) => true, _ => false }).count(), 0);
+    assert_area_and_count(&result, ::std::f32::consts::PI * 0.5, 1);
+}
@@ -0,0 +1,242 @@
+//! # Bounding rectangles
+//!
+//! Compute the axis-aligned bounding rectangle of a path.
+//!
+//! `fast_bounding_rect` computes a rectangle that contains all of the endpoints and control
+//! points of the path, which is cheap but not necessarily the smallest rectangle containing
+//! the path. `bounding_rect` computes the smallest rectangle containing the curves,
+//! except for arcs for which a conservative rectangle containing the whole ellipse is used.
+//!
+//! Both functions include the positions of all `MoveTo` events, including the ones that
+//! don't start any segment. The bounding rectangle of an empty path is an empty rectangle
+//! at the origin.
+//!
+//! ## Examples
+//!
+//! ```
+//! # extern crate lyon_path;
+//! # fn main() {
+//! use lyon_path::default::Path;
+//! use lyon_path::math::point;
+//! use lyon_path::builder::*;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.quadratic_bezier_to(point(1.0, 2.0), point(2.0, 0.0));
+//! let path = builder.build();
+//!
+//! // The control point is included.
+//! assert_eq!(path.fast_bounding_rect().max_y(), 2.0);
+//! // The curve only goes up to y = 1.
+//! assert_eq!(path.bounding_rect().max_y(), 1.0);
+//! # }
+//! ```
+
+use math::{Point, Rect, point};
+use geom::{QuadraticBezierSegment, CubicBezierSegment, Arc};
+use geom::utils::vector_angle;
+use iterator::PathIterator;
+use PathEvent;
+
+use std::f32;
+
+/// Computes a rectangle that contains the path, using its endpoints and control points.
+pub fn fast_bounding_rect<Iter: PathIterator>(path: Iter) -> Rect {
+    let mut bounds = Bounds::new();
+    let mut current = point(0.0, 0.0);
+    let mut first = current;
+    for evt in path {
+        match evt {
+            PathEvent::MoveTo(to) => {
+                bounds.add_point(to);
+                current = to;
+                first = to;
+            }
+            PathEvent::LineTo(to) => {
+                bounds.add_point(to);
+                current = to;
+            }
+            PathEvent::QuadraticTo(ctrl, to) => {
+                bounds.add_point(ctrl);
+                bounds.add_point(to);
+                current = to;
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                bounds.add_point(ctrl1);
+                bounds.add_point(ctrl2);
+                bounds.add_point(to);
+                current = to;
+            }
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                let arc = Arc {
+                    center: center,
+                    radii: radii,
+                    start_angle: vector_angle(current - center),
+                    sweep_angle: sweep_angle,
+                    x_rotation: x_rotation,
+                };
+                bounds.add_rect(&arc.bounding_rect());
+                current = arc.to();
+            }
+            PathEvent::Close => {
+                current = first;
+            }
+        }
+    }
+
+    bounds.to_rect()
+}
+
+/// Computes the smallest rectangle that contains the path.
+///
+/// Arcs are approximated with a rectangle containing their whole ellipse.
+pub fn bounding_rect<Iter: PathIterator>(path: Iter) -> Rect {
+    let mut bounds = Bounds::new();
+    let mut current = point(0.0, 0.0);
+    let mut first = current;
+    for evt in path {
+        match evt {
+            PathEvent::MoveTo(to) => {
+                bounds.add_point(to);
+                current = to;
+                first = to;
+            }
+            PathEvent::LineTo(to) => {
+                bounds.add_point(to);
+                current = to;
+            }
+            PathEvent::QuadraticTo(ctrl, to) => {
+                let curve = QuadraticBezierSegment { from: current, ctrl: ctrl, to: to };
+                bounds.add_rect(&curve.bounding_rect());
+                current = to;
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                let curve = CubicBezierSegment { from: current, ctrl1: ctrl1, ctrl2: ctrl2, to: to };
+                bounds.add_rect(&curve.bounding_rect());
+                current = to;
+            }
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                let arc = Arc {
+                    center: center,
+                    radii: radii,
+                    start_angle: vector_angle(current - center),
+                    sweep_angle: sweep_angle,
+                    x_rotation: x_rotation,
+                };
+                bounds.add_rect(&arc.bounding_rect());
+                current = arc.to();
+            }
+            PathEvent::Close => {
+                current = first;
+            }
+        }
+    }
+
+    bounds.to_rect()
+}
+
+struct Bounds {
+    min: Point,
+    max: Point,
+}
+
+impl Bounds {
+    fn new() -> Self {
+        Bounds {
+            min: point(f32::MAX, f32::MAX),
+            max: point(-f32::MAX, -f32::MAX),
+        }
+    }
+
+    fn add_point(&mut self, p: Point) {
+        self.min.x = self.min.x.min(p.x);
+        self.min.y = self.min.y.min(p.y);
+        self.max.x = self.max.x.max(p.x);
+        self.max.y = self.max.y.max(p.y);
+    }
+
+    fn add_rect(&mut self, r: &Rect) {
+        self.add_point(r.origin);
+        self.add_point(r.bottom_right());
+    }
+
+    fn to_rect(&self) -> Rect {
+        if self.min.x > self.max.x {
+            return Rect::zero();
+        }
+
+        Rect::new(self.min, (self.max - self.min).to_size())
+    }
+}
+
+#[cfg(test)]
+use default::Path;
+#[cfg(test)]
+use builder::{FlatPathBuilder, PathBuilder};
+#[cfg(test)]
+use math::{rect, vector, Radians};
+
+#[test]
+fn path_bounding_rects() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, -1.0));
+    builder.cubic_bezier_to(point(2.0, -3.0), point(3.0, 5.0), point(4.0, 0.0));
+    builder.close();
+    // An isolated move_to.
+    builder.move_to(point(-1.0, 0.5));
+    let path = builder.build();
+
+    assert_eq!(path.fast_bounding_rect(), rect(-1.0, -3.0, 5.0, 8.0));
+
+    let r = path.bounding_rect();
+    let curve = CubicBezierSegment {
+        from: point(1.0, -1.0),
+        ctrl1: point(2.0, -3.0),
+        ctrl2: point(3.0, 5.0),
+        to: point(4.0, 0.0),
+    };
+    assert_eq!(r.min_x(), -1.0);
+    assert_eq!(r.max_x(), 4.0);
+    assert!((r.min_y() - curve.sample(curve.find_y_minimum()).y).abs() < 0.0001);
+    assert!((r.max_y() - curve.sample(curve.find_y_maximum()).y).abs() < 0.0001);
+    assert!(r.min_y() > -3.0 && r.max_y() < 5.0);
+
+    assert_eq!(fast_bounding_rect(path.path_iter()), path.fast_bounding_rect());
+    assert_eq!(bounding_rect(path.path_iter()), path.bounding_rect());
+}
+
+#[test]
+fn curve_after_close_bounding_rect() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.close();
+    // Starts from the first point of the closed sub-path.
+    builder.quadratic_bezier_to(point(-10.0, 5.0), point(0.0, 10.0));
+    let path = builder.build();
+
+    assert_eq!(path.fast_bounding_rect(), rect(-10.0, 0.0, 20.0, 10.0));
+    let r = path.bounding_rect();
+    assert!((r.min_x() + 5.0).abs() < 0.0001);
+    assert_eq!(r.max_x(), 10.0);
+}
+
+#[test]
+fn arc_bounding_rect() {
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 0.0));
+    builder.arc(point(0.0, 0.0), vector(1.0, 1.0), Radians::new(1.0), Radians::new(0.0));
+    let path = builder.build();
+
+    // The whole circle is included.
+    assert_eq!(path.bounding_rect(), rect(-1.0, -1.0, 2.0, 2.0));
+}
+
+#[test]
+fn empty_bounding_rect() {
+    let path = Path::new();
+    assert_eq!(path.bounding_rect(), Rect::zero());
+    assert_eq!(path.fast_bounding_rect(), Rect::zero());
+}
@@ -78,9 +78,12 @@
 
 use math::*;
 use events::{PathEvent, FlattenedEvent, SvgEvent};
+use Winding;
 use geom::{CubicBezierSegment, QuadraticBezierSegment, SvgArc, Arc, ArcFlags};
 use geom::utils::vector_angle;
 
+use std::f32::consts::{PI, FRAC_PI_2};
+
 /// The most basic path building interface. Does not handle any kind of curve.
 pub trait FlatPathBuilder: ::std::marker::Sized {
     /// The type of object that is created by this builder.
@@ -125,6 +128,11 @@ pub trait FlatPathBuilder: ::std::marker::Sized {
     fn flattened(self, tolerance: f32) -> FlatteningBuilder<Self> {
         FlatteningBuilder::new(self, tolerance)
     }
+
+    /// Returns a builder that reports malformed sequences of events when building the path.
+    fn validated(self) -> ValidatingBuilder<Self> {
+        ValidatingBuilder::new(self)
+    }
 }
 
 /// The main path building interface. More elaborate interfaces are built on top
@@ -255,6 +263,9 @@ pub trait SvgBuilder: PathBuilder {
 
 /// Build a path from a simple list of points.
 pub trait PolygonBuilder {
+    /// Adds a closed sub-path going through the points in the order of the slice.
+    ///
+    /// Panics if the slice is empty.
     fn polygon(&mut self, points: &[Point]);
 }
 
@@ -486,6 +497,162 @@ impl<Builder: FlatPathBuilder> FlatteningBuilder<Builder> {
     pub fn set_tolerance(&mut self, tolerance: f32) { self.tolerance = tolerance }
 }
 
+/// The kind of problem detected by a `ValidatingBuilder`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    /// A line, curve or close event was added before the first `move_to`.
+    MissingMoveTo,
+    /// A line, curve or close event was added after `close` without a `move_to` in between.
+    EventAfterClose,
+    /// An event has a NaN or infinite parameter.
+    InvalidNumber,
+}
+
+/// The first problem detected by a `ValidatingBuilder`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The index of the invalid event among all of the events received by the builder.
+    pub event_index: usize,
+    pub kind: ValidationErrorKind,
+}
+
+/// A builder that checks the events it receives before forwarding them to another builder.
+///
+/// Building returns the first problem found, if any. Events after the first problem are
+/// not forwarded.
+pub struct ValidatingBuilder<Builder> {
+    builder: Builder,
+    event_index: usize,
+    state: ValidationState,
+    error: Option<ValidationError>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ValidationState {
+    Start,
+    InSubPath,
+    Closed,
+}
+
+impl<Builder: FlatPathBuilder> ValidatingBuilder<Builder> {
+    pub fn new(builder: Builder) -> ValidatingBuilder<Builder> {
+        ValidatingBuilder {
+            builder: builder,
+            event_index: 0,
+            state: ValidationState::Start,
+            error: None,
+        }
+    }
+
+    /// Returns the first problem found so far, if any.
+    pub fn error(&self) -> Option<ValidationError> { self.error }
+
+    // Returns whether the event should be forwarded.
+    fn check(&mut self, points: &[Point], numbers: &[f32], is_move_to: bool) -> bool {
+        let event_index = self.event_index;
+        self.event_index += 1;
+
+        if self.error.is_some() {
+            return false;
+        }
+
+        let kind = if points.iter().any(|p| !p.x.is_finite() || !p.y.is_finite())
+            || numbers.iter().any(|n| !n.is_finite()) {
+            Some(ValidationErrorKind::InvalidNumber)
+        } else if is_move_to {
+            None
+        } else {
+            match self.state {
+                ValidationState::Start => Some(ValidationErrorKind::MissingMoveTo),
+                ValidationState::Closed => Some(ValidationErrorKind::EventAfterClose),
+                ValidationState::InSubPath => None,
+            }
+        };
+
+        if let Some(kind) = kind {
+            self.error = Some(ValidationError { event_index, kind });
+            return false;
+        }
+
+        true
+    }
+
+    fn result(
+        error: Option<ValidationError>,
+        path: Builder::PathType
+    ) -> Result<Builder::PathType, ValidationError> {
+        match error {
+            Some(error) => Err(error),
+            None => Ok(path),
+        }
+    }
+}
+
+impl<Builder: FlatPathBuilder> FlatPathBuilder for ValidatingBuilder<Builder> {
+    type PathType = Result<Builder::PathType, ValidationError>;
+
+    fn move_to(&mut self, to: Point) {
+        if self.check(&[to], &[], true) {
+            self.state = ValidationState::InSubPath;
+            self.builder.move_to(to);
+        }
+    }
+
+    fn line_to(&mut self, to: Point) {
+        if self.check(&[to], &[], false) {
+            self.builder.line_to(to);
+        }
+    }
+
+    fn close(&mut self) {
+        if self.check(&[], &[], false) {
+            self.state = ValidationState::Closed;
+            self.builder.close();
+        }
+    }
+
+    fn current_position(&self) -> Point { self.builder.current_position() }
+
+    fn build(self) -> Self::PathType {
+        ValidatingBuilder::<Builder>::result(self.error, self.builder.build())
+    }
+
+    fn build_and_reset(&mut self) -> Self::PathType {
+        let error = self.error.take();
+        self.event_index = 0;
+        self.state = ValidationState::Start;
+
+        ValidatingBuilder::<Builder>::result(error, self.builder.build_and_reset())
+    }
+}
+
+impl<Builder: PathBuilder> PathBuilder for ValidatingBuilder<Builder> {
+    fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
+        if self.check(&[ctrl, to], &[], false) {
+            self.builder.quadratic_bezier_to(ctrl, to);
+        }
+    }
+
+    fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+        if self.check(&[ctrl1, ctrl2, to], &[], false) {
+            self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+        }
+    }
+
+    fn arc(
+        &mut self,
+        center: Point,
+        radii: Vector,
+        sweep_angle: Radians,
+        x_rotation: Radians
+    ) {
+        let numbers = [radii.x, radii.y, sweep_angle.get(), x_rotation.get()];
+        if self.check(&[center], &numbers, false) {
+            self.builder.arc(center, radii, sweep_angle, x_rotation);
+        }
+    }
+}
+
 impl<Builder: FlatPathBuilder> PolygonBuilder for Builder {
     fn polygon(&mut self, points: &[Point]) {
         assert!(!points.is_empty());
@@ -497,3 +664,454 @@ impl<Builder: FlatPathBuilder> PolygonBuilder for Builder {
         self.close();
     }
 }
+
+/// The radius of each corner of a rounded rectangle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BorderRadii {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_left: f32,
+    pub bottom_right: f32,
+}
+
+impl BorderRadii {
+    pub fn new(
+        top_left: f32,
+        top_right: f32,
+        bottom_left: f32,
+        bottom_right: f32,
+    ) -> Self {
+        BorderRadii {
+            top_left: top_left.abs(),
+            top_right: top_right.abs(),
+            bottom_left: bottom_left.abs(),
+            bottom_right: bottom_right.abs(),
+        }
+    }
+
+    pub fn new_all_same(radius: f32) -> Self {
+        let r = radius.abs();
+        BorderRadii {
+            top_left: r,
+            top_right: r,
+            bottom_left: r,
+            bottom_right: r,
+        }
+    }
+
+    /// Returns the radii scaled down so that the corners of a rectangle of the given
+    /// size don't overlap.
+    pub fn clamped(&self, size: Size) -> BorderRadii {
+        let w = size.width;
+        let h = size.height;
+        let min_wh = w.min(h);
+        let mut tl = self.top_left.abs().min(min_wh);
+        let mut tr = self.top_right.abs().min(min_wh);
+        let mut bl = self.bottom_left.abs().min(min_wh);
+        let mut br = self.bottom_right.abs().min(min_wh);
+
+        if tl + tr > w {
+            let x = (tl + tr - w) * 0.5;
+            tl -= x;
+            tr -= x;
+        }
+        if bl + br > w {
+            let x = (bl + br - w) * 0.5;
+            bl -= x;
+            br -= x;
+        }
+        if tr + br > h {
+            let x = (tr + br - h) * 0.5;
+            tr -= x;
+            br -= x;
+        }
+        if tl + bl > h {
+            let x = (tl + bl - h) * 0.5;
+            tl -= x;
+            bl -= x;
+        }
+
+        BorderRadii {
+            top_left: tl,
+            top_right: tr,
+            bottom_left: bl,
+            bottom_right: br,
+        }
+    }
+}
+
+/// Adds common shapes to a path, each of them as a closed sub-path.
+///
+/// Curved shapes are made of cubic bézier curves, each approximating a quarter of an
+/// ellipse.
+pub trait ShapeBuilder {
+    /// Adds a circle, starting at the point on the right of its center.
+    fn add_circle(&mut self, center: Point, radius: f32, winding: Winding);
+
+    /// Adds an ellipse, starting at the end of its first radius.
+    fn add_ellipse(&mut self, center: Point, radii: Vector, x_rotation: Radians, winding: Winding);
+
+    /// Adds an axis-aligned rectangle, starting at its minimum corner.
+    fn add_rectangle(&mut self, rect: &Rect, winding: Winding);
+
+    /// Adds an axis-aligned rectangle with rounded corners.
+    ///
+    /// The radii are scaled down when they don't fit in the rectangle.
+    fn add_rounded_rectangle(&mut self, rect: &Rect, radii: &BorderRadii, winding: Winding);
+
+    /// Adds a polygon starting at the first point of the slice.
+    ///
+    /// The points are added in the order of the slice, or in the opposite order if that
+    /// gives the polygon a signed area of the wrong sign for the requested winding.
+    /// Does nothing if there are less than two points.
+    fn add_polygon(&mut self, points: &[Point], winding: Winding);
+
+    /// Adds a regular polygon with a vertex on the right of its center.
+    ///
+    /// Does nothing if there are less than three sides.
+    fn add_regular_polygon(&mut self, center: Point, radius: f32, sides: u32, winding: Winding);
+
+    /// Adds a star with an outer vertex on the right of its center.
+    ///
+    /// Does nothing if there are less than two branches.
+    fn add_star(
+        &mut self,
+        center: Point,
+        outer_radius: f32,
+        inner_radius: f32,
+        branches: u32,
+        winding: Winding,
+    );
+}
+
+// Distance of the control points to the endpoints of a cubic bézier curve approximating a
+// quarter of a circle, relative to its radius.
+const QUARTER_ARC_CTRL: f32 = 0.552_284_8;
+
+impl<Builder: PathBuilder> ShapeBuilder for Builder {
+    fn add_circle(&mut self, center: Point, radius: f32, winding: Winding) {
+        self.add_ellipse(center, vector(radius, radius), Radians::new(0.0), winding);
+    }
+
+    fn add_ellipse(&mut self, center: Point, radii: Vector, x_rotation: Radians, winding: Winding) {
+        let (sin, cos) = x_rotation.get().sin_cos();
+        let rotate = |v: Vector| vector(v.x * cos - v.y * sin, v.x * sin + v.y * cos);
+        let position = |angle: f32| center + rotate(vector(radii.x * angle.cos(), radii.y * angle.sin()));
+        let derivative = |angle: f32| rotate(vector(-radii.x * angle.sin(), radii.y * angle.cos()));
+
+        let sign = match winding {
+            Winding::Positive => 1.0,
+            Winding::Negative => -1.0,
+        };
+        let step = sign * FRAC_PI_2;
+
+        self.move_to(position(0.0));
+        for i in 0..4 {
+            let a0 = i as f32 * step;
+            let a1 = (i + 1) as f32 * step;
+            self.cubic_bezier_to(
+                position(a0) + derivative(a0) * (sign * QUARTER_ARC_CTRL),
+                position(a1) - derivative(a1) * (sign * QUARTER_ARC_CTRL),
+                position(a1),
+            );
+        }
+        self.close();
+    }
+
+    fn add_rectangle(&mut self, rect: &Rect, winding: Winding) {
+        let min = rect.origin;
+        let max = point(rect.max_x(), rect.max_y());
+        self.add_polygon(&[min, point(max.x, min.y), max, point(min.x, max.y)], winding);
+    }
+
+    fn add_rounded_rectangle(&mut self, rect: &Rect, radii: &BorderRadii, winding: Winding) {
+        let x_min = rect.min_x();
+        let y_min = rect.min_y();
+        let x_max = rect.max_x();
+        let y_max = rect.max_y();
+        let radii = radii.clamped(rect.size);
+        let (tl, tr) = (radii.top_left, radii.top_right);
+        let (bl, br) = (radii.bottom_left, radii.bottom_right);
+
+        // Each corner is described by the rectangle's corner and the points where the
+        // rounded corner meets the horizontal and the vertical sides.
+        let corners = [
+            (point(x_max, y_min), point(x_max - tr, y_min), point(x_max, y_min + tr)),
+            (point(x_max, y_max), point(x_max, y_max - br), point(x_max - br, y_max)),
+            (point(x_min, y_max), point(x_min + bl, y_max), point(x_min, y_max - bl)),
+            (point(x_min, y_min), point(x_min, y_min + tl), point(x_min + tl, y_min)),
+        ];
+
+        let mut first = true;
+        let mut add_corner = |builder: &mut Self, corner: Point, from: Point, to: Point| {
+            if first {
+                builder.move_to(from);
+                first = false;
+            } else if builder.current_position() != from {
+                builder.line_to(from);
+            }
+            if from != to {
+                builder.cubic_bezier_to(
+                    from.lerp(corner, QUARTER_ARC_CTRL),
+                    to.lerp(corner, QUARTER_ARC_CTRL),
+                    to,
+                );
+            }
+        };
+
+        match winding {
+            Winding::Positive => {
+                for &(corner, from, to) in &corners {
+                    add_corner(self, corner, from, to);
+                }
+            }
+            Winding::Negative => {
+                for &(corner, from, to) in corners.iter().rev() {
+                    add_corner(self, corner, to, from);
+                }
+            }
+        }
+
+        self.close();
+    }
+
+    fn add_polygon(&mut self, points: &[Point], winding: Winding) {
+        if points.len() < 2 {
+            return;
+        }
+
+        let first = points[0];
+        let mut area = 0.0;
+        for edge in points[1..].windows(2) {
+            area += (edge[0] - first).cross(edge[1] - first);
+        }
+        let reversed = match winding {
+            Winding::Positive => area < 0.0,
+            Winding::Negative => area > 0.0,
+        };
+
+        self.move_to(first);
+        if reversed {
+            for p in points[1..].iter().rev() {
+                self.line_to(*p);
+            }
+        } else {
+            for p in &points[1..] {
+                self.line_to(*p);
+            }
+        }
+        self.close();
+    }
+
+    fn add_regular_polygon(&mut self, center: Point, radius: f32, sides: u32, winding: Winding) {
+        if sides < 3 {
+            return;
+        }
+        let sign = match winding {
+            Winding::Positive => 1.0,
+            Winding::Negative => -1.0,
+        };
+        let step = sign * 2.0 * PI / sides as f32;
+
+        self.move_to(center + vector(radius, 0.0));
+        for i in 1..sides {
+            let (sin, cos) = (i as f32 * step).sin_cos();
+            self.line_to(center + vector(cos, sin) * radius);
+        }
+        self.close();
+    }
+
+    fn add_star(
+        &mut self,
+        center: Point,
+        outer_radius: f32,
+        inner_radius: f32,
+        branches: u32,
+        winding: Winding,
+    ) {
+        if branches < 2 {
+            return;
+        }
+        let sign = match winding {
+            Winding::Positive => 1.0,
+            Winding::Negative => -1.0,
+        };
+        let step = sign * PI / branches as f32;
+
+        self.move_to(center + vector(outer_radius, 0.0));
+        for i in 1..(branches * 2) {
+            let radius = if i % 2 == 0 { outer_radius } else { inner_radius };
+            let (sin, cos) = (i as f32 * step).sin_cos();
+            self.line_to(center + vector(cos, sin) * radius);
+        }
+        self.close();
+    }
+}
+
+#[cfg(test)]
+use default::Path;
+#[cfg(test)]
+use orientation::signed_area;
+
+#[test]
+fn test_circle() {
+    use std::f32::consts::PI;
+
+    let mut builder = Path::builder();
+    builder.add_circle(point(1.0, 2.0), 10.0, Winding::Positive);
+    let positive = builder.build();
+
+    let mut builder = Path::builder();
+    builder.add_circle(point(1.0, 2.0), 10.0, Winding::Negative);
+    let negative = builder.build();
+
+    assert_eq!(positive.points()[0], point(11.0, 2.0));
+    assert_eq!(negative.points()[0], point(11.0, 2.0));
+
+    let area = PI * 100.0;
+    assert!((signed_area(positive.path_iter(), 0.01) - area).abs() < 0.5);
+    assert!((signed_area(negative.path_iter(), 0.01) + area).abs() < 0.5);
+}
+
+#[test]
+fn test_ellipse() {
+    let mut builder = Path::builder();
+    builder.add_ellipse(point(0.0, 0.0), vector(10.0, 5.0), Radians::new(FRAC_PI_2), Winding::Positive);
+    let path = builder.build();
+
+    let first = path.points()[0];
+    assert!((first - point(0.0, 10.0)).length() < 0.001);
+    assert!((signed_area(path.path_iter(), 0.01) - PI * 50.0).abs() < 0.5);
+}
+
+#[test]
+fn test_rectangles() {
+    let rect = ::math::rect(1.0, 2.0, 10.0, 20.0);
+
+    let mut builder = Path::builder();
+    builder.add_rectangle(&rect, Winding::Positive);
+    builder.add_rectangle(&rect, Winding::Negative);
+    let path = builder.build();
+    assert_eq!(path.points().len(), 8);
+    assert_eq!(signed_area(path.path_iter(), 0.01), 0.0);
+
+    let mut builder = Path::builder();
+    builder.add_rectangle(&rect, Winding::Negative);
+    assert_eq!(signed_area(builder.build().path_iter(), 0.01), -200.0);
+
+    // Without radii, rounded rectangles are plain rectangles.
+    let mut builder = Path::builder();
+    builder.add_rounded_rectangle(&rect, &BorderRadii::new_all_same(0.0), Winding::Positive);
+    let path = builder.build();
+    assert_eq!(path.points().len(), 4);
+    assert_eq!(signed_area(path.path_iter(), 0.01), 200.0);
+
+    let mut builder = Path::builder();
+    builder.add_rounded_rectangle(&rect, &BorderRadii::new(1.0, 2.0, 3.0, 4.0), Winding::Negative);
+    let path = builder.build();
+    let area = 200.0 - (1.0 + 4.0 + 9.0 + 16.0) * (1.0 - PI / 4.0);
+    assert!((signed_area(path.path_iter(), 0.01) + area).abs() < 0.1);
+
+    // Radii larger than the rectangle produce a shape close to an ellipse.
+    let mut builder = Path::builder();
+    builder.add_rounded_rectangle(&rect, &BorderRadii::new_all_same(100.0), Winding::Positive);
+    let path = builder.build();
+    assert!(path.points().iter().all(|p| rect.inflate(0.001, 0.001).contains(p)));
+    assert!(signed_area(path.path_iter(), 0.01) > 0.0);
+}
+
+#[test]
+fn test_polygons() {
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, 0.0), point(1.0, 0.0), point(1.0, 1.0)]);
+    builder.add_polygon(&[], Winding::Positive);
+    builder.add_polygon(&[point(5.0, 5.0)], Winding::Positive);
+    builder.add_regular_polygon(point(0.0, 0.0), 1.0, 2, Winding::Positive);
+    builder.add_star(point(0.0, 0.0), 2.0, 1.0, 1, Winding::Positive);
+    let path = builder.build();
+    assert_eq!(signed_area(path.path_iter(), 0.01), 0.5);
+
+    let triangle = [point(0.0, 0.0), point(1.0, 0.0), point(1.0, 1.0)];
+    let mut builder = Path::builder();
+    builder.add_polygon(&triangle, Winding::Positive);
+    let path = builder.build();
+    assert_eq!(path.points(), &triangle);
+    assert_eq!(signed_area(path.path_iter(), 0.01), 0.5);
+
+    let mut builder = Path::builder();
+    builder.add_polygon(&triangle, Winding::Negative);
+    let path = builder.build();
+    assert_eq!(path.points(), &[triangle[0], triangle[2], triangle[1]]);
+    assert_eq!(signed_area(path.path_iter(), 0.01), -0.5);
+
+    let mut builder = Path::builder();
+    builder.add_regular_polygon(point(0.0, 0.0), 1.0, 4, Winding::Negative);
+    let path = builder.build();
+    assert_eq!(path.points().len(), 4);
+    assert_eq!(path.points()[0], point(1.0, 0.0));
+    assert!((path.points()[1] - point(0.0, -1.0)).length() < 0.001);
+    assert!((signed_area(path.path_iter(), 0.01) + 2.0).abs() < 0.001);
+
+    let mut builder = Path::builder();
+    builder.add_star(point(0.0, 0.0), 2.0, 1.0, 5, Winding::Positive);
+    let path = builder.build();
+    assert_eq!(path.points().len(), 10);
+    for (i, p) in path.points().iter().enumerate() {
+        let expected = if i % 2 == 0 { 2.0 } else { 1.0 };
+        assert!((p.to_vector().length() - expected).abs() < 0.001);
+    }
+    assert!(signed_area(path.path_iter(), 0.01) > 0.0);
+}
+
+#[test]
+fn test_validating_builder() {
+    let mut builder = Path::builder().validated();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0));
+    builder.close();
+    builder.move_to(point(5.0, 5.0));
+    builder.arc(point(5.0, 0.0), vector(5.0, 5.0), Radians::new(1.0), Radians::new(0.0));
+    let path = builder.build().unwrap();
+    assert_eq!(path.verbs().len(), 6);
+
+    let mut builder = Path::builder().validated();
+    builder.line_to(point(1.0, 0.0));
+    assert_eq!(
+        builder.build().err(),
+        Some(ValidationError { event_index: 0, kind: ValidationErrorKind::MissingMoveTo })
+    );
+
+    let mut builder = Path::builder().validated();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.close();
+    builder.cubic_bezier_to(point(1.0, 1.0), point(2.0, 1.0), point(2.0, 0.0));
+    builder.line_to(point(::std::f32::NAN, 0.0));
+    assert_eq!(
+        builder.error(),
+        Some(ValidationError { event_index: 3, kind: ValidationErrorKind::EventAfterClose })
+    );
+
+    // The builder can be reused after build_and_reset.
+    assert!(builder.build_and_reset().is_err());
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(0.0, ::std::f32::INFINITY));
+    assert_eq!(
+        builder.build_and_reset().err(),
+        Some(ValidationError { event_index: 1, kind: ValidationErrorKind::InvalidNumber })
+    );
+
+    builder.move_to(point(0.0, 0.0));
+    builder.arc(point(1.0, 0.0), vector(1.0, ::std::f32::NAN), Radians::new(1.0), Radians::new(0.0));
+    assert_eq!(
+        builder.build_and_reset().err(),
+        Some(ValidationError { event_index: 1, kind: ValidationErrorKind::InvalidNumber })
+    );
+
+    builder.move_to(point(0.0, 0.0));
+    builder.close();
+    builder.move_to(point(1.0, 0.0));
+    builder.line_to(point(2.0, 0.0));
+    assert!(builder.build_and_reset().is_ok());
+}
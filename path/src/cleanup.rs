@@ -0,0 +1,331 @@
+//! # Path cleanup
+//!
+//! Remove degenerate geometry from paths before tessellating them.
+//!
+//! ## Overview
+//!
+//! Paths coming from user input or GIS data often contain zero-length segments,
+//! duplicated points, runs of collinear edges and curves that are actually straight.
+//! These don't change the shape of the path but they make the tessellators slower and
+//! more likely to run into precision issues.
+//!
+//! `cleanup` forwards the events of a path to a builder and, within a given tolerance:
+//!
+//! - removes segments that don't go anywhere, as well as the repeated `MoveTo` events of
+//!   empty sub-paths,
+//! - turns curves whose control points are on the chord into line segments,
+//! - merges consecutive line segments that are collinear into a single segment.
+//!
+//! Points are only removed if they are within the tolerance of the resulting edges, so
+//! the output doesn't deviate from the input by more than the tolerance.
+//!
+//! ## Examples
+//!
+//! ```
+//! # extern crate lyon_path;
+//! # fn main() {
+//! use lyon_path::default::Path;
+//! use lyon_path::math::point;
+//! use lyon_path::builder::*;
+//! use lyon_path::cleanup::cleanup;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(1.0, 0.0));
+//! builder.line_to(point(1.0, 0.0));
+//! builder.line_to(point(2.0, 0.0));
+//! builder.quadratic_bezier_to(point(2.0, 1.0), point(2.0, 2.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let mut output = Path::builder();
+//! cleanup(path.iter(), 0.01, &mut output);
+//! let clean = output.build();
+//!
+//! // The result is a triangle.
+//! assert_eq!(clean.points(), &[point(0.0, 0.0), point(2.0, 0.0), point(2.0, 2.0)]);
+//! # }
+//! ```
+
+use math::{Point, Vector};
+use geom::Arc;
+use geom::utils::vector_angle;
+use builder::PathBuilder;
+use PathEvent;
+
+/// Forwards the events of a path to a builder, removing degenerate geometry within the
+/// tolerance.
+///
+/// See the [cleanup module](index.html).
+pub fn cleanup<Iter, Builder>(path: Iter, tolerance: f32, builder: &mut Builder)
+where
+    Iter: IntoIterator<Item = PathEvent>,
+    Builder: PathBuilder,
+{
+    let mut cleaner = Cleaner {
+        builder: builder,
+        tolerance: tolerance,
+        first: Point::new(0.0, 0.0),
+        current: Point::new(0.0, 0.0),
+        pending_move: None,
+        line_start: None,
+        merged: Vec::new(),
+    };
+
+    for evt in path {
+        cleaner.path_event(evt);
+    }
+
+    cleaner.flush_line();
+}
+
+struct Cleaner<'l, Builder: 'l> {
+    builder: &'l mut Builder,
+    tolerance: f32,
+    // The first point of the current sub-path.
+    first: Point,
+    // The position after the last event received.
+    current: Point,
+    // A MoveTo that is only forwarded when something is added to the sub-path.
+    pending_move: Option<Point>,
+    // The start of a line segment ending at `current` that hasn't been forwarded yet,
+    // to be extended by the next collinear segments.
+    line_start: Option<Point>,
+    // The points that were removed by extending the pending line segment.
+    merged: Vec<Point>,
+}
+
+impl<'l, Builder: PathBuilder> Cleaner<'l, Builder> {
+    fn path_event(&mut self, evt: PathEvent) {
+        match evt {
+            PathEvent::MoveTo(to) => {
+                self.flush_line();
+                self.pending_move = Some(to);
+                self.first = to;
+                self.current = to;
+            }
+            PathEvent::LineTo(to) => {
+                self.line_to(to);
+            }
+            PathEvent::QuadraticTo(ctrl, to) => {
+                if self.is_flat(&[ctrl], to) {
+                    self.line_to(to);
+                    return;
+                }
+                self.begin_segment();
+                self.builder.quadratic_bezier_to(ctrl, to);
+                self.current = to;
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                if self.is_flat(&[ctrl1, ctrl2], to) {
+                    self.line_to(to);
+                    return;
+                }
+                self.begin_segment();
+                self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+                self.current = to;
+            }
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                let to = Arc {
+                    center: center,
+                    radii: radii,
+                    start_angle: vector_angle(self.current - center),
+                    sweep_angle: sweep_angle,
+                    x_rotation: x_rotation,
+                }.to();
+                let arc_length = radii.x.abs().max(radii.y.abs()) * sweep_angle.get().abs();
+                if arc_length <= self.tolerance {
+                    self.line_to(to);
+                    return;
+                }
+                self.begin_segment();
+                self.builder.arc(center, radii, sweep_angle, x_rotation);
+                self.current = to;
+            }
+            PathEvent::Close => {
+                let first = self.first;
+                if let Some(start) = self.line_start {
+                    // Let the closing segment replace the last line if they are collinear.
+                    if self.is_mergeable(start, first) {
+                        self.line_start = None;
+                        self.merged.clear();
+                    }
+                }
+                self.begin_segment();
+                self.builder.close();
+                self.current = first;
+            }
+        }
+    }
+
+    fn line_to(&mut self, to: Point) {
+        if self.is_same_point(self.current, to) {
+            return;
+        }
+
+        if let Some(start) = self.line_start {
+            if self.is_mergeable(start, to) {
+                let current = self.current;
+                self.merged.push(current);
+                self.current = to;
+                return;
+            }
+        }
+
+        self.begin_segment();
+        self.line_start = Some(self.current);
+        self.current = to;
+    }
+
+    // Whether the pending line can be extended up to `to`, removing the current point.
+    fn is_mergeable(&self, start: Point, to: Point) -> bool {
+        let tolerance = self.tolerance;
+        distance_to_segment(self.current, start, to) <= tolerance
+            && self.merged.iter().all(|p| distance_to_segment(*p, start, to) <= tolerance)
+    }
+
+    fn is_flat(&self, ctrls: &[Point], to: Point) -> bool {
+        let from = self.current;
+        ctrls.iter().all(|ctrl| distance_to_segment(*ctrl, from, to) <= self.tolerance)
+    }
+
+    fn is_same_point(&self, a: Point, b: Point) -> bool {
+        (a - b).square_length() <= self.tolerance * self.tolerance
+    }
+
+    // Forwards what needs to be before a segment that can't be merged with the pending line.
+    fn begin_segment(&mut self) {
+        self.flush_line();
+        if let Some(to) = self.pending_move.take() {
+            self.builder.move_to(to);
+        }
+    }
+
+    fn flush_line(&mut self) {
+        if self.line_start.take().is_some() {
+            let to = self.current;
+            self.builder.line_to(to);
+            self.merged.clear();
+        }
+    }
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f32 {
+    let ab: Vector = b - a;
+    let sqr_length = ab.square_length();
+    if sqr_length == 0.0 {
+        return (p - a).length();
+    }
+    let t = ((p - a).dot(ab) / sqr_length).max(0.0).min(1.0);
+
+    (p - (a + ab * t)).length()
+}
+
+#[cfg(test)]
+use math::{point, vector, Radians};
+#[cfg(test)]
+use default::Path;
+#[cfg(test)]
+use builder::FlatPathBuilder;
+
+#[cfg(test)]
+fn clean(path: &Path, tolerance: f32) -> Path {
+    let mut builder = Path::builder();
+    cleanup(path.iter(), tolerance, &mut builder);
+    builder.build()
+}
+
+#[test]
+fn test_degenerate_segments() {
+    let mut builder = Path::builder();
+    builder.move_to(point(5.0, 5.0));
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(1.001, 0.0), point(1.0, 0.001));
+    builder.cubic_bezier_to(point(1.0, 0.0), point(1.0, 0.0), point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.arc(point(2.0, 1.0), vector(1.0, 1.0), Radians::new(0.0), Radians::new(0.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    builder.move_to(point(10.0, 10.0));
+    let path = builder.build();
+
+    let result = clean(&path, 0.01);
+    let expected: Vec<PathEvent> = vec![
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(1.0, 0.0)),
+        PathEvent::LineTo(point(1.0, 1.0)),
+        PathEvent::LineTo(point(0.0, 1.0)),
+        PathEvent::Close,
+    ];
+    assert_eq!(result.iter().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_collinear_edges() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(2.0, 0.005));
+    builder.line_to(point(3.0, 0.0));
+    // Going back along the same line isn't merged.
+    builder.line_to(point(2.5, 0.0));
+    builder.line_to(point(2.5, 2.0));
+    builder.line_to(point(2.5, 3.0));
+    builder.cubic_bezier_to(point(2.0, 3.0), point(1.0, 3.0), point(0.0, 3.0));
+    builder.line_to(point(0.0, 2.0));
+    builder.line_to(point(0.0, 0.0));
+    builder.close();
+    let path = builder.build();
+
+    let result = clean(&path, 0.01);
+    let expected: Vec<PathEvent> = vec![
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(3.0, 0.0)),
+        PathEvent::LineTo(point(2.5, 0.0)),
+        PathEvent::LineTo(point(2.5, 3.0)),
+        PathEvent::LineTo(point(0.0, 3.0)),
+        PathEvent::Close,
+    ];
+    assert_eq!(result.iter().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_accumulated_deviation() {
+    // Each point is close to the line between its neighbors but not to the
+    // line between the ends.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    for i in 1..11 {
+        let x = i as f32;
+        builder.line_to(point(x, x * x * 0.001));
+    }
+    let path = builder.build();
+
+    let result = clean(&path, 0.01);
+    assert!(result.points().len() > 2);
+    assert!(result.points().len() < path.points().len());
+    for p in path.points() {
+        let mut min = ::std::f32::MAX;
+        let points = result.points();
+        for i in 1..points.len() {
+            min = min.min(distance_to_segment(*p, points[i - 1], points[i]));
+        }
+        assert!(min <= 0.01);
+    }
+}
+
+#[test]
+fn test_curves_are_kept() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(1.0, 1.0), point(2.0, 0.0));
+    builder.cubic_bezier_to(point(3.0, 1.0), point(4.0, -1.0), point(5.0, 0.0));
+    builder.arc(point(6.0, 0.0), vector(1.0, 1.0), Radians::new(1.0), Radians::new(0.0));
+    let path = builder.build();
+
+    let result = clean(&path, 0.01);
+    assert_eq!(result.verbs(), path.verbs());
+    assert_eq!(result.points(), path.points());
+}
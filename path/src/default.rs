@@ -1,24 +1,34 @@
 use builder::{FlatPathBuilder, PathBuilder, SvgPathBuilder, FlatteningBuilder};
-use iterator::PathIter;
+use iterator::{PathIter, PathIterator, Similarity, WithIds};
+use bounding_rect;
+use orientation;
+use geom::{QuadraticBezierSegment, CubicBezierSegment, Arc};
+use geom::utils::vector_angle;
 
-use PathEvent;
+use {PathEvent, PathState, Winding};
 use math::*;
 
 use std::iter::IntoIterator;
+use std::ops::Range;
 
 /// Enumeration corresponding to the [PathEvent](https://docs.rs/lyon_core/*/lyon_core/events/enum.PathEvent.html) enum
 /// without the parameters.
 ///
 /// This is used by the [Path](struct.Path.html) data structure to store path events a tad
 /// more efficiently.
+///
+/// The discriminant values are part of the [binary encoding](../binary/index.html) and
+/// must not change.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[repr(u8)]
 pub enum Verb {
-    MoveTo,
-    LineTo,
-    QuadraticTo,
-    CubicTo,
-    Arc,
-    Close,
+    MoveTo = 0,
+    LineTo = 1,
+    QuadraticTo = 2,
+    CubicTo = 3,
+    Arc = 4,
+    Close = 5,
 }
 
 /// A simple path data structure.
@@ -28,6 +38,7 @@ pub enum Verb {
 pub struct Path {
     points: Vec<Point>,
     verbs: Vec<Verb>,
+    cached_bounding_rect: Option<Rect>,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -44,6 +55,7 @@ impl Path {
         Path {
             points: Vec::new(),
             verbs: Vec::new(),
+            cached_bounding_rect: None,
         }
     }
 
@@ -51,6 +63,52 @@ impl Path {
         Path {
             points: Vec::with_capacity(cap),
             verbs: Vec::with_capacity(cap),
+            cached_bounding_rect: None,
+        }
+    }
+
+    // Appends an event without the bookkeeping of the builder.
+    #[cfg(feature = "serialization")]
+    fn push_event(&mut self, event: PathEvent) {
+        match event {
+            PathEvent::MoveTo(to) => {
+                self.points.push(to);
+                self.verbs.push(Verb::MoveTo);
+            }
+            PathEvent::LineTo(to) => {
+                self.points.push(to);
+                self.verbs.push(Verb::LineTo);
+            }
+            PathEvent::QuadraticTo(ctrl, to) => {
+                self.points.push(ctrl);
+                self.points.push(to);
+                self.verbs.push(Verb::QuadraticTo);
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                self.points.push(ctrl1);
+                self.points.push(ctrl2);
+                self.points.push(to);
+                self.verbs.push(Verb::CubicTo);
+            }
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                self.points.push(center);
+                self.points.push(radii.to_point());
+                self.points.push(point(sweep_angle.get(), x_rotation.get()));
+                self.verbs.push(Verb::Arc);
+            }
+            PathEvent::Close => {
+                self.verbs.push(Verb::Close);
+            }
+        }
+        self.cached_bounding_rect = None;
+    }
+
+    // The caller is responsible for providing the right amount of points for the verbs.
+    pub(crate) fn from_raw_parts(points: Vec<Point>, verbs: Vec<Verb>) -> Path {
+        Path {
+            points: points,
+            verbs: verbs,
+            cached_bounding_rect: None,
         }
     }
 
@@ -65,12 +123,42 @@ impl Path {
 
     pub fn path_iter(&self) -> PathIter<Iter> { PathIter::new(self.iter()) }
 
+    /// Iterates over the events of the path along with the identifiers of their endpoints
+    /// and sub-paths.
+    pub fn id_iter(&self) -> WithIds<PathIter<Iter>> { self.path_iter().with_ids() }
+
     pub fn points(&self) -> &[Point] { &self.points[..] }
 
-    pub fn mut_points(&mut self) -> &mut [Point] { &mut self.points[..] }
+    /// Mutable access to the points of the path.
+    ///
+    /// This invalidates the cached bounding rectangle.
+    pub fn mut_points(&mut self) -> &mut [Point] {
+        self.cached_bounding_rect = None;
+        &mut self.points[..]
+    }
 
     pub fn verbs(&self) -> &[Verb] { &self.verbs[..] }
 
+    /// Iterates over the sub-paths of the path.
+    pub fn subpaths(&self) -> SubPaths { self.as_slice().subpaths() }
+
+    /// Returns the sub-path at a given index, if any.
+    ///
+    /// This walks the verbs of the path up to the requested sub-path. Collect the
+    /// sub-paths from `subpaths` instead when accessing many of them.
+    pub fn subpath(&self, index: usize) -> Option<SubPath> { self.as_slice().subpath(index) }
+
+    /// Returns a cursor at the first event of the path, to edit it.
+    pub fn cursor(&mut self) -> PathCursor {
+        PathCursor {
+            path: self,
+            verb: 0,
+            point: 0,
+            current: point(0.0, 0.0),
+            first: point(0.0, 0.0),
+        }
+    }
+
     /// Consumes two paths and builds one that contains them.
     pub fn merge(mut self, other: Self) -> Self {
         if other.verbs.is_empty() {
@@ -84,9 +172,139 @@ impl Path {
 
         self.verbs.extend(other.verbs);
         self.points.extend(other.points);
+        self.cached_bounding_rect = None;
 
         self
     }
+
+    /// Computes a rectangle that contains the path, using its endpoints and control points.
+    ///
+    /// See the [bounding_rect module](../bounding_rect/index.html).
+    pub fn fast_bounding_rect(&self) -> Rect {
+        if let Some(rect) = self.cached_bounding_rect {
+            return rect;
+        }
+
+        bounding_rect::fast_bounding_rect(self.path_iter())
+    }
+
+    /// Computes the smallest rectangle that contains the path.
+    ///
+    /// See the [bounding_rect module](../bounding_rect/index.html).
+    pub fn bounding_rect(&self) -> Rect {
+        if let Some(rect) = self.cached_bounding_rect {
+            return rect;
+        }
+
+        bounding_rect::bounding_rect(self.path_iter())
+    }
+
+    /// Computes the bounding rectangle and stores it in the path so that the next calls
+    /// to `bounding_rect` and `fast_bounding_rect` don't have to iterate over the path.
+    ///
+    /// This is useful for paths that are tested many times against a view for culling.
+    /// The cached rectangle is discarded when the path is modified.
+    pub fn cache_bounding_rect(&mut self) -> Rect {
+        let rect = self.bounding_rect();
+        self.cached_bounding_rect = Some(rect);
+
+        rect
+    }
+
+    /// Returns the cached bounding rectangle if any.
+    pub fn cached_bounding_rect(&self) -> Option<Rect> { self.cached_bounding_rect }
+
+    /// Linearly interpolates between this path (`t = 0`) and another path (`t = 1`).
+    ///
+    /// Returns `None` if the paths don't have the same verbs. Paths with different
+    /// structures can be made compatible with `morph::normalize`.
+    pub fn lerp(&self, other: &Path, t: f32) -> Option<Path> {
+        if self.verbs != other.verbs {
+            return None;
+        }
+
+        let points = self.points.iter().zip(other.points.iter()).map(|(a, b)| {
+            a.lerp(*b, t)
+        }).collect();
+
+        Some(Path {
+            points: points,
+            verbs: self.verbs.clone(),
+            cached_bounding_rect: None,
+        })
+    }
+
+    /// Returns a path going through the same segments in the opposite direction.
+    ///
+    /// See `reverse_path`.
+    pub fn reversed(&self) -> Path {
+        let mut builder = Builder::with_capacity(self.points.len());
+        reverse_path(self.as_slice(), &mut builder);
+
+        builder.build()
+    }
+
+    /// Returns a copy of this path with a 2D transform applied to it.
+    ///
+    /// Arcs are converted into quadratic bézier curves if they can't be represented after
+    /// the transform, for example with non-uniform scales and skews.
+    pub fn transformed(&self, transform: &Transform2D) -> Path {
+        let mut path = self.clone();
+        path.apply_transform(transform);
+
+        path
+    }
+
+    /// Applies a 2D transform to this path in place.
+    ///
+    /// See `transformed`.
+    pub fn apply_transform(&mut self, transform: &Transform2D) {
+        self.cached_bounding_rect = None;
+
+        let similarity = Similarity::from_transform(transform);
+        let needs_curves = self.iter().any(|evt| match evt {
+            PathEvent::Arc(_, radii, sweep_angle, x_rotation) => {
+                similarity.and_then(|similarity| {
+                    similarity.transform_arc(radii, sweep_angle, x_rotation)
+                }).is_none()
+            }
+            _ => false,
+        });
+        if needs_curves {
+            let mut builder = Builder::with_capacity(self.verbs.len());
+            for evt in self.path_iter().transformed(transform) {
+                builder.path_event(evt);
+            }
+            *self = builder.build();
+            return;
+        }
+
+        let mut i = 0;
+        for verb in &self.verbs {
+            let n = match *verb {
+                Verb::MoveTo | Verb::LineTo => 1,
+                Verb::QuadraticTo => 2,
+                Verb::CubicTo => 3,
+                Verb::Arc => {
+                    let (radii, sweep_angle, x_rotation) = similarity.unwrap().transform_arc(
+                        self.points[i + 1].to_vector(),
+                        Radians::new(self.points[i + 2].x),
+                        Radians::new(self.points[i + 2].y),
+                    ).unwrap();
+                    self.points[i] = transform.transform_point(&self.points[i]);
+                    self.points[i + 1] = radii.to_point();
+                    self.points[i + 2] = point(sweep_angle.get(), x_rotation.get());
+                    i += 3;
+                    continue;
+                }
+                Verb::Close => 0,
+            };
+            for p in &mut self.points[i..i + n] {
+                *p = transform.transform_point(p);
+            }
+            i += n;
+        }
+    }
 }
 
 impl<'l> IntoIterator for &'l Path {
@@ -96,6 +314,51 @@ impl<'l> IntoIterator for &'l Path {
     fn into_iter(self) -> Iter<'l> { self.iter() }
 }
 
+/// Paths are serialized as a sequence of `PathEvent`s, which doesn't depend on the
+/// internal representation.
+#[cfg(feature = "serialization")]
+impl ::serde::Serialize for Path {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let mut seq = serializer.serialize_seq(Some(self.verbs.len()))?;
+        for evt in self.iter() {
+            seq.serialize_element(&evt)?;
+        }
+
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serialization")]
+impl<'de> ::serde::Deserialize<'de> for Path {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Path, D::Error> {
+        use serde::de::{Visitor, SeqAccess};
+        use std::fmt;
+
+        struct PathVisitor;
+
+        impl<'de> Visitor<'de> for PathVisitor {
+            type Value = Path;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sequence of path events")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Path, A::Error> {
+                let mut path = Path::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(evt) = seq.next_element::<PathEvent>()? {
+                    path.push_event(evt);
+                }
+
+                Ok(path)
+            }
+        }
+
+        deserializer.deserialize_seq(PathVisitor)
+    }
+}
+
 /// An immutable view over a Path.
 impl<'l> PathSlice<'l> {
     pub fn new(points: &'l [Point], verbs: &'l [Verb]) -> PathSlice<'l> {
@@ -109,9 +372,567 @@ impl<'l> PathSlice<'l> {
 
     pub fn path_iter(&self) -> PathIter<Iter> { PathIter::new(self.iter()) }
 
+    /// Iterates over the events of the path along with the identifiers of their endpoints
+    /// and sub-paths.
+    pub fn id_iter(&self) -> WithIds<PathIter<Iter>> { self.path_iter().with_ids() }
+
     pub fn points(&self) -> &[Point] { self.points }
 
     pub fn verbs(&self) -> &[Verb] { self.verbs }
+
+    /// Iterates over the sub-paths of the path.
+    pub fn subpaths(&self) -> SubPaths<'l> { SubPaths::new(*self) }
+
+    /// Returns the sub-path at a given index, if any.
+    pub fn subpath(&self, index: usize) -> Option<SubPath<'l>> { self.subpaths().nth(index) }
+
+    /// Computes a rectangle that contains the path, using its endpoints and control points.
+    pub fn fast_bounding_rect(&self) -> Rect {
+        bounding_rect::fast_bounding_rect(self.path_iter())
+    }
+
+    /// Computes the smallest rectangle that contains the path.
+    pub fn bounding_rect(&self) -> Rect {
+        bounding_rect::bounding_rect(self.path_iter())
+    }
+
+    /// Returns a copy of this path with a 2D transform applied to it.
+    ///
+    /// See `Path::transformed`.
+    pub fn transformed(&self, transform: &Transform2D) -> Path {
+        let mut path = Path {
+            points: self.points.to_vec(),
+            verbs: self.verbs.to_vec(),
+            cached_bounding_rect: None,
+        };
+        path.apply_transform(transform);
+
+        path
+    }
+}
+
+/// A sub-path of a `Path`.
+///
+/// A sub-path starts with a `MoveTo` event (except for events at the beginning of a path
+/// that doesn't start with `MoveTo`) and goes up to the next one. Events that follow a
+/// `Close` event without a `MoveTo` are part of the same sub-path since they start from
+/// its first point.
+///
+/// See `Path::subpaths`.
+#[derive(Clone, Debug)]
+pub struct SubPath<'l> {
+    path: PathSlice<'l>,
+    verbs: Range<usize>,
+    points: Range<usize>,
+}
+
+impl<'l> SubPath<'l> {
+    /// The events of the sub-path.
+    pub fn path(&self) -> PathSlice<'l> { self.path }
+
+    /// Whether the sub-path ends with a `Close` event.
+    pub fn is_closed(&self) -> bool { self.path.verbs.last() == Some(&Verb::Close) }
+
+    /// The range of the sub-path in the verbs of the path it belongs to.
+    pub fn verb_range(&self) -> Range<usize> { self.verbs.clone() }
+
+    /// The range of the sub-path in the points of the path it belongs to.
+    pub fn point_range(&self) -> Range<usize> { self.points.clone() }
+
+    /// Computes a rectangle that contains the sub-path, using its endpoints and control points.
+    pub fn fast_bounding_rect(&self) -> Rect { self.path.fast_bounding_rect() }
+
+    /// Computes the smallest rectangle that contains the sub-path.
+    pub fn bounding_rect(&self) -> Rect { self.path.bounding_rect() }
+
+    /// Computes the signed area of the sub-path, approximating curves within `tolerance`.
+    ///
+    /// The sub-path is treated as closed. See the [orientation module](../orientation/index.html).
+    pub fn signed_area(&self, tolerance: f32) -> f32 {
+        orientation::signed_area(self.path.path_iter(), tolerance)
+    }
+
+    /// Returns the winding of the sub-path, or `None` if its area is zero.
+    pub fn winding(&self, tolerance: f32) -> Option<Winding> {
+        orientation::winding_from_area(self.signed_area(tolerance))
+    }
+}
+
+/// An iterator over the sub-paths of a path.
+///
+/// See `Path::subpaths`.
+#[derive(Clone, Debug)]
+pub struct SubPaths<'l> {
+    path: PathSlice<'l>,
+    verb_idx: usize,
+    point_idx: usize,
+}
+
+impl<'l> SubPaths<'l> {
+    pub fn new(path: PathSlice<'l>) -> Self {
+        SubPaths {
+            path: path,
+            verb_idx: 0,
+            point_idx: 0,
+        }
+    }
+}
+
+impl<'l> Iterator for SubPaths<'l> {
+    type Item = SubPath<'l>;
+    fn next(&mut self) -> Option<SubPath<'l>> {
+        let verbs = self.path.verbs;
+        if self.verb_idx >= verbs.len() {
+            return None;
+        }
+
+        let verb_start = self.verb_idx;
+        let point_start = self.point_idx;
+        loop {
+            self.point_idx += num_points(verbs[self.verb_idx]);
+            self.verb_idx += 1;
+            if self.verb_idx >= verbs.len() || verbs[self.verb_idx] == Verb::MoveTo {
+                break;
+            }
+        }
+
+        let points = point_start..self.point_idx;
+        let verbs = verb_start..self.verb_idx;
+
+        Some(SubPath {
+            path: PathSlice {
+                points: &self.path.points[points.clone()],
+                verbs: &self.path.verbs[verbs.clone()],
+            },
+            verbs: verbs,
+            points: points,
+        })
+    }
+}
+
+pub(crate) fn num_points(verb: Verb) -> usize {
+    match verb {
+        Verb::MoveTo | Verb::LineTo => 1,
+        Verb::QuadraticTo => 2,
+        Verb::CubicTo | Verb::Arc => 3,
+        Verb::Close => 0,
+    }
+}
+
+/// A cursor over the segments of a `Path`, to edit it while keeping its points and
+/// verbs consistent.
+///
+/// The cursor is always at a verb of the path (or past the last one). Edits apply to the
+/// segment at the cursor, that is the event at the cursor along with the position it
+/// starts from.
+///
+/// See `Path::cursor`.
+pub struct PathCursor<'l> {
+    path: &'l mut Path,
+    verb: usize,
+    point: usize,
+    // Position before the event at the cursor, and first point of its sub-path, kept
+    // up to date as the cursor moves so that they don't have to be recomputed.
+    current: Point,
+    first: Point,
+}
+
+impl<'l> PathCursor<'l> {
+    /// Index of the verb at the cursor.
+    pub fn verb_index(&self) -> usize { self.verb }
+
+    /// Index of the first point of the event at the cursor.
+    pub fn point_index(&self) -> usize { self.point }
+
+    /// The verb at the cursor, or `None` if the cursor is past the end of the path.
+    pub fn verb(&self) -> Option<Verb> { self.path.verbs.get(self.verb).cloned() }
+
+    /// The event at the cursor, or `None` if the cursor is past the end of the path.
+    pub fn event(&self) -> Option<PathEvent> {
+        Iter::new(&self.path.points[self.point..], &self.path.verbs[self.verb..]).next()
+    }
+
+    /// The position the segment at the cursor starts from.
+    pub fn from(&self) -> Point { self.current }
+
+    /// The position the segment at the cursor ends at.
+    pub fn to(&self) -> Option<Point> {
+        let mut state = self.state_before();
+        let evt = match self.event() {
+            Some(evt) => evt,
+            None => { return None; }
+        };
+        state.path_event(evt);
+
+        Some(state.current)
+    }
+
+    /// Moves the cursor to the next verb.
+    ///
+    /// Returns false if there is no verb after the cursor.
+    pub fn move_to_next(&mut self) -> bool {
+        let evt = match self.event() {
+            Some(evt) => evt,
+            None => { return false; }
+        };
+        let mut state = self.state_before();
+        state.path_event(evt);
+        self.current = state.current;
+        self.first = state.first;
+        self.point += num_points(self.path.verbs[self.verb]);
+        self.verb += 1;
+
+        self.verb < self.path.verbs.len()
+    }
+
+    /// Moves the cursor to the previous verb.
+    ///
+    /// Returns false if the cursor is at the first verb.
+    pub fn move_to_previous(&mut self) -> bool {
+        if self.verb == 0 {
+            return false;
+        }
+        self.verb -= 1;
+        self.point -= num_points(self.path.verbs[self.verb]);
+        self.update_state();
+
+        true
+    }
+
+    /// Moves the cursor to a given verb.
+    pub fn seek(&mut self, verb_index: usize) {
+        debug_assert!(verb_index <= self.path.verbs.len());
+        if verb_index < self.verb {
+            self.verb = 0;
+            self.point = 0;
+            self.current = point(0.0, 0.0);
+            self.first = point(0.0, 0.0);
+        }
+        while self.verb < verb_index && self.move_to_next() {}
+    }
+
+    /// Splits the segment at the cursor into two segments of the same kind, the cursor
+    /// staying on the first one.
+    ///
+    /// Closing segments are split by inserting a line before the `Close` event.
+    /// Returns false if the cursor isn't on a segment.
+    pub fn split_at(&mut self, t: f32) -> bool {
+        let from = self.from();
+        let evt = match self.event() {
+            Some(evt) => evt,
+            None => { return false; }
+        };
+        let (verb, points) = match evt {
+            PathEvent::MoveTo(..) => { return false; }
+            PathEvent::LineTo(to) => {
+                (Verb::LineTo, vec![from.lerp(to, t)])
+            }
+            PathEvent::QuadraticTo(ctrl, to) => {
+                let (a, b) = QuadraticBezierSegment { from, ctrl, to }.split(t);
+                self.path.points[self.point] = b.ctrl;
+                (Verb::QuadraticTo, vec![a.ctrl, a.to])
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                let (a, b) = CubicBezierSegment { from, ctrl1, ctrl2, to }.split(t);
+                self.path.points[self.point] = b.ctrl1;
+                self.path.points[self.point + 1] = b.ctrl2;
+                (Verb::CubicTo, vec![a.ctrl1, a.ctrl2, a.to])
+            }
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                self.path.points[self.point + 2].x = sweep_angle.get() * (1.0 - t);
+                (Verb::Arc, vec![
+                    center,
+                    radii.to_point(),
+                    point(sweep_angle.get() * t, x_rotation.get()),
+                ])
+            }
+            PathEvent::Close => {
+                (Verb::LineTo, vec![from.lerp(self.first, t)])
+            }
+        };
+
+        self.insert(verb, &points);
+
+        true
+    }
+
+    /// Removes the endpoint of the segment at the cursor, the next segment starting from
+    /// the previous endpoint instead. The cursor is then on the next segment.
+    ///
+    /// Removing the endpoint of a `MoveTo` event makes the sub-path start at the endpoint of
+    /// its first segment (or removes the sub-path if it has no segment), and removing a
+    /// `Close` event opens the sub-path.
+    /// Returns false if the cursor is past the end of the path.
+    pub fn remove_endpoint(&mut self) -> bool {
+        let verb = match self.verb() {
+            Some(verb) => verb,
+            None => { return false; }
+        };
+
+        if verb == Verb::MoveTo {
+            let mut next = PathCursor {
+                path: self.path,
+                verb: self.verb,
+                point: self.point,
+                current: self.current,
+                first: self.first,
+            };
+            next.move_to_next();
+            match next.verb() {
+                Some(Verb::LineTo) | Some(Verb::QuadraticTo) |
+                Some(Verb::CubicTo) | Some(Verb::Arc) => {
+                    // The next segment becomes the start of the sub-path.
+                    let to = next.to().unwrap();
+                    let (verb, point) = (next.verb, next.point);
+                    next.remove_current();
+                    next.path.verbs.insert(verb, Verb::MoveTo);
+                    next.path.points.insert(point, to);
+                }
+                Some(Verb::Close) => {
+                    next.remove_current();
+                }
+                _ => {}
+            }
+        }
+
+        self.remove_current();
+
+        true
+    }
+
+    /// Converts the curve at the cursor into a line segment with the same endpoints.
+    ///
+    /// Returns false if the cursor isn't on a line or curve segment.
+    pub fn convert_to_line(&mut self) -> bool {
+        let to = match self.verb() {
+            Some(Verb::LineTo) => { return true; }
+            Some(Verb::QuadraticTo) | Some(Verb::CubicTo) | Some(Verb::Arc) => self.to().unwrap(),
+            _ => { return false; }
+        };
+        self.replace(Verb::LineTo, &[to]);
+
+        true
+    }
+
+    /// Converts the line segment at the cursor into a quadratic bézier curve with the
+    /// same endpoints and shape.
+    ///
+    /// Returns false if the cursor isn't on a line or quadratic bézier segment.
+    pub fn convert_to_quadratic(&mut self) -> bool {
+        match self.event() {
+            Some(PathEvent::QuadraticTo(..)) => true,
+            Some(PathEvent::LineTo(to)) => {
+                let from = self.from();
+                self.replace(Verb::QuadraticTo, &[from.lerp(to, 0.5), to]);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Converts the segment at the cursor into a cubic bézier curve with the same
+    /// endpoints and shape.
+    ///
+    /// Arcs are approximated with a sequence of cubic bézier curves, the cursor staying
+    /// on the first one.
+    /// Returns false if the cursor isn't on a line or curve segment.
+    pub fn convert_to_cubic(&mut self) -> bool {
+        let from = self.from();
+        match self.event() {
+            Some(PathEvent::CubicTo(..)) => {}
+            Some(PathEvent::LineTo(to)) => {
+                self.replace(Verb::CubicTo, &[from.lerp(to, 1.0 / 3.0), from.lerp(to, 2.0 / 3.0), to]);
+            }
+            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+                let cubic = QuadraticBezierSegment { from, ctrl, to }.to_cubic();
+                self.replace(Verb::CubicTo, &[cubic.ctrl1, cubic.ctrl2, cubic.to]);
+            }
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+                let arc = Arc {
+                    center, radii,
+                    start_angle: vector_angle(from - center),
+                    sweep_angle, x_rotation,
+                };
+                let mut curves = Vec::new();
+                let mut current = from;
+                arc.to_quadratic_beziers(&mut |ctrl, to| {
+                    curves.push(QuadraticBezierSegment { from: current, ctrl, to }.to_cubic());
+                    current = to;
+                });
+                self.remove_current();
+                for curve in curves.iter().rev() {
+                    self.insert(Verb::CubicTo, &[curve.ctrl1, curve.ctrl2, curve.to]);
+                }
+            }
+            _ => { return false; }
+        }
+
+        true
+    }
+
+    /// Closes the sub-path containing the cursor if it isn't closed already.
+    pub fn close_sub_path(&mut self) {
+        let end = self.sub_path_end();
+        if end > 0 && self.path.verbs[end - 1] != Verb::Close {
+            self.path.verbs.insert(end, Verb::Close);
+            self.path.cached_bounding_rect = None;
+        }
+    }
+
+    /// Removes the `Close` event at the end of the sub-path containing the cursor, if any.
+    pub fn open_sub_path(&mut self) {
+        let end = self.sub_path_end();
+        if end > 0 && self.path.verbs[end - 1] == Verb::Close {
+            self.path.verbs.remove(end - 1);
+            self.path.cached_bounding_rect = None;
+            if end == self.verb {
+                // The cursor was right after the removed event.
+                self.verb -= 1;
+                self.update_state();
+            }
+        }
+    }
+
+    fn state_before(&self) -> PathState {
+        PathState { current: self.current, first: self.first, last_ctrl: self.current }
+    }
+
+    // Recomputes the position before the cursor by going through the events from the
+    // start of the sub-path containing the cursor.
+    fn update_state(&mut self) {
+        let mut verb = self.verb;
+        let mut point = self.point;
+        while verb > 0 {
+            verb -= 1;
+            point -= num_points(self.path.verbs[verb]);
+            if self.path.verbs[verb] == Verb::MoveTo {
+                break;
+            }
+        }
+
+        let mut state = PathState::new();
+        for evt in Iter::new(&self.path.points[point..self.point], &self.path.verbs[verb..self.verb]) {
+            state.path_event(evt);
+        }
+        self.current = state.current;
+        self.first = state.first;
+    }
+
+    // Index of the verb after the last one of the sub-path containing the cursor.
+    fn sub_path_end(&self) -> usize {
+        let verbs = &self.path.verbs;
+        let mut end = self.verb;
+        if end < verbs.len() && verbs[end] == Verb::MoveTo {
+            end += 1;
+        }
+        while end < verbs.len() && verbs[end] != Verb::MoveTo {
+            end += 1;
+        }
+
+        end
+    }
+
+    // Inserts an event before the cursor, which then points to the new event.
+    fn insert(&mut self, verb: Verb, points: &[Point]) {
+        debug_assert_eq!(points.len(), num_points(verb));
+        self.path.verbs.insert(self.verb, verb);
+        for (i, p) in points.iter().enumerate() {
+            self.path.points.insert(self.point + i, *p);
+        }
+        self.path.cached_bounding_rect = None;
+    }
+
+    fn remove_current(&mut self) {
+        let n = num_points(self.path.verbs[self.verb]);
+        self.path.verbs.remove(self.verb);
+        self.path.points.drain(self.point..self.point + n);
+        self.path.cached_bounding_rect = None;
+    }
+
+    fn replace(&mut self, verb: Verb, points: &[Point]) {
+        self.remove_current();
+        self.insert(verb, points);
+    }
+}
+
+/// Writes the reverse of a path into a builder.
+///
+/// The sub-paths are written in reverse order and each of them goes through the same
+/// segments in the opposite direction. A closed sub-path starts at its last point and is
+/// closed as well, so that its closing segment stays implicit. Arcs are reversed by
+/// negating their sweep angle.
+///
+/// Events that follow a `Close` event without a `MoveTo` belong to the same sub-path (see
+/// `SubPath`) but start from its first point, which the reversed closed part doesn't end at.
+/// They are written first, as a sub-path of their own, and each closed part is then
+/// written starting with a `MoveTo`.
+///
+/// This doesn't call `build` on the builder.
+pub fn reverse_path<Output: PathBuilder>(path: PathSlice, builder: &mut Output) {
+    // Gather the events along with the positions they start from and end at.
+    let mut events = Vec::with_capacity(path.verbs.len());
+    let mut state = PathState::new();
+    for evt in path.iter() {
+        let from = state.current;
+        state.path_event(evt);
+        events.push((from, state.current, evt));
+    }
+
+    let mut end = events.len();
+    while end > 0 {
+        // Find the beginning of the last part of the sub-path, which is either a MoveTo
+        // or the event following a Close.
+        let mut start = end - 1;
+        while start > 0 {
+            if let PathEvent::MoveTo(..) = events[start].2 {
+                break;
+            }
+            if events[start - 1].2 == PathEvent::Close {
+                break;
+            }
+            start -= 1;
+        }
+
+        let sub_path = &events[start..end];
+        end = start;
+
+        let (segments, closed) = match sub_path.last() {
+            Some(&(_, _, PathEvent::Close)) => (&sub_path[..sub_path.len() - 1], true),
+            _ => (sub_path, false),
+        };
+        let segments = match segments.first() {
+            Some(&(_, _, PathEvent::MoveTo(..))) => &segments[1..],
+            _ => segments,
+        };
+
+        let last = match segments.last() {
+            Some(&(_, to, _)) => to,
+            None => sub_path[0].1,
+        };
+
+        builder.move_to(last);
+        for &(from, _, evt) in segments.iter().rev() {
+            match evt {
+                PathEvent::LineTo(_) => {
+                    builder.line_to(from);
+                }
+                PathEvent::QuadraticTo(ctrl, _) => {
+                    builder.quadratic_bezier_to(ctrl, from);
+                }
+                PathEvent::CubicTo(ctrl1, ctrl2, _) => {
+                    builder.cubic_bezier_to(ctrl2, ctrl1, from);
+                }
+                PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                    builder.arc(center, radii, Radians::new(-sweep_angle.get()), x_rotation);
+                }
+                PathEvent::MoveTo(..) | PathEvent::Close => {}
+            }
+        }
+
+        if closed {
+            builder.close();
+        }
+    }
 }
 
 //impl<'l> IntoIterator for PathSlice<'l> {
@@ -632,3 +1453,464 @@ fn test_path_builder_close_empty() {
     let _ = builder.build();
 }
 */
+
+#[test]
+fn test_cached_bounding_rect() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(1.0, 2.0), point(2.0, 0.0));
+    let mut path = builder.build();
+
+    assert_eq!(path.cached_bounding_rect(), None);
+    let r = path.cache_bounding_rect();
+    assert_eq!(r, rect(0.0, 0.0, 2.0, 1.0));
+    assert_eq!(path.cached_bounding_rect(), Some(r));
+    assert_eq!(path.bounding_rect(), r);
+
+    path.mut_points()[1] = point(1.0, 4.0);
+    assert_eq!(path.cached_bounding_rect(), None);
+    assert_eq!(path.bounding_rect(), rect(0.0, 0.0, 2.0, 2.0));
+}
+
+#[test]
+fn test_transformed_path() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.cubic_bezier_to(point(2.0, 0.0), point(2.0, 1.0), point(1.0, 1.0));
+    builder.arc(point(1.0, 2.0), vector(1.0, 1.0), Radians::new(1.0), Radians::new(0.0));
+    builder.close();
+    let mut path = builder.build();
+    path.cache_bounding_rect();
+
+    let transform = Transform2D::create_rotation(Radians::new(0.5)).post_translate(vector(2.0, 3.0));
+    let transformed = path.transformed(&transform);
+    assert_eq!(transformed.verbs(), path.verbs());
+    assert_eq!(transformed.cached_bounding_rect(), None);
+    let expected: Vec<PathEvent> = path.path_iter().transformed(&transform).collect();
+    let events: Vec<PathEvent> = transformed.iter().collect();
+    assert_eq!(events, expected);
+    assert_eq!(path.as_slice().transformed(&transform).iter().collect::<Vec<_>>(), expected);
+
+    // The arc is converted into curves.
+    let transform = Transform2D::create_scale(1.0, 2.0);
+    let expected: Vec<PathEvent> = path.path_iter().transformed(&transform).collect();
+    path.apply_transform(&transform);
+    assert!(!path.verbs().contains(&Verb::Arc));
+    assert_eq!(path.iter().collect::<Vec<_>>(), expected);
+}
+
+#[cfg(test)]
+use std::f32::consts::PI;
+
+#[test]
+fn test_reversed_path() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0));
+    builder.cubic_bezier_to(point(2.0, 2.0), point(1.0, 3.0), point(0.0, 2.0));
+    builder.close();
+    builder.move_to(point(10.0, 0.0));
+    builder.line_to(point(11.0, 0.0));
+    builder.arc(point(11.0, 1.0), vector(1.0, 1.0), Radians::new(PI * 0.5), Radians::new(0.0));
+    builder.move_to(point(20.0, 0.0));
+    let path = builder.build();
+
+    let reversed = path.reversed();
+    let events: Vec<PathEvent> = reversed.iter().collect();
+    assert_eq!(events[0], PathEvent::MoveTo(point(20.0, 0.0)));
+    match events[1] {
+        PathEvent::MoveTo(p) => { assert!((p - point(12.0, 1.0)).length() < 0.0001); }
+        evt => { panic!("{:?}", evt); }
+    }
+    assert_eq!(
+        &events[2..],
+        &[
+            PathEvent::Arc(point(11.0, 1.0), vector(1.0, 1.0), Radians::new(-PI * 0.5), Radians::new(0.0)),
+            PathEvent::LineTo(point(10.0, 0.0)),
+            PathEvent::MoveTo(point(0.0, 2.0)),
+            PathEvent::CubicTo(point(1.0, 3.0), point(2.0, 2.0), point(2.0, 1.0)),
+            PathEvent::QuadraticTo(point(2.0, 0.0), point(1.0, 0.0)),
+            PathEvent::LineTo(point(0.0, 0.0)),
+            PathEvent::Close,
+        ][..]
+    );
+
+    // The reversed arc ends where the original one started.
+    let mut state = PathState::new();
+    for evt in &events[..3] {
+        state.path_event(*evt);
+    }
+    assert!((state.current - point(11.0, 0.0)).length() < 0.0001);
+
+    // Reversing twice gives back the original path.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.cubic_bezier_to(point(2.0, 2.0), point(1.0, 3.0), point(0.0, 2.0));
+    builder.close();
+    builder.move_to(point(5.0, 5.0));
+    builder.quadratic_bezier_to(point(6.0, 5.0), point(6.0, 6.0));
+    let path = builder.build();
+    let twice = path.reversed().reversed();
+    assert_eq!(twice.verbs(), path.verbs());
+    assert_eq!(twice.points(), path.points());
+}
+
+#[test]
+fn test_lerp() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(10.0, 0.0), point(10.0, 10.0));
+    builder.close();
+    let a = builder.build();
+
+    let mut builder = Path::builder();
+    builder.move_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(20.0, 10.0), point(0.0, 20.0));
+    builder.close();
+    let b = builder.build();
+
+    let mid = a.lerp(&b, 0.5).unwrap();
+    assert_eq!(mid.verbs(), a.verbs());
+    assert_eq!(mid.points(), &[point(5.0, 0.0), point(15.0, 5.0), point(5.0, 15.0)]);
+    assert_eq!(a.lerp(&b, 0.0).unwrap().points(), a.points());
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    let c = builder.build();
+
+    assert!(a.lerp(&c, 0.5).is_none());
+}
+
+#[test]
+fn test_reversed_path_after_close() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.close();
+    builder.line_to(point(0.0, 5.0));
+    let path = builder.build();
+
+    let reversed = path.reversed();
+    assert_eq!(
+        reversed.iter().collect::<Vec<PathEvent>>(),
+        vec![
+            PathEvent::MoveTo(point(0.0, 5.0)),
+            PathEvent::LineTo(point(0.0, 0.0)),
+            PathEvent::MoveTo(point(1.0, 1.0)),
+            PathEvent::LineTo(point(1.0, 0.0)),
+            PathEvent::LineTo(point(0.0, 0.0)),
+            PathEvent::Close,
+        ]
+    );
+}
+
+#[test]
+fn test_subpaths() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(10.0, 10.0), point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(20.0, 0.0));
+    builder.cubic_bezier_to(point(30.0, 0.0), point(30.0, 10.0), point(20.0, 10.0));
+    builder.move_to(point(50.0, 50.0));
+    let path = builder.build();
+
+    let subpaths: Vec<SubPath> = path.subpaths().collect();
+    assert_eq!(subpaths.len(), 3);
+
+    assert!(subpaths[0].is_closed());
+    assert_eq!(subpaths[0].verb_range(), 0..4);
+    assert_eq!(subpaths[0].point_range(), 0..4);
+    assert_eq!(subpaths[0].bounding_rect(), Rect::new(point(0.0, 0.0), size(10.0, 10.0)));
+
+    assert!(!subpaths[1].is_closed());
+    assert_eq!(subpaths[1].verb_range(), 4..6);
+    assert_eq!(subpaths[1].point_range(), 4..8);
+    assert_eq!(
+        subpaths[1].path().iter().collect::<Vec<PathEvent>>(),
+        vec![
+            PathEvent::MoveTo(point(20.0, 0.0)),
+            PathEvent::CubicTo(point(30.0, 0.0), point(30.0, 10.0), point(20.0, 10.0)),
+        ]
+    );
+
+    assert_eq!(subpaths[2].verb_range(), 6..7);
+    assert_eq!(subpaths[2].point_range(), 8..9);
+
+    assert_eq!(path.subpath(1).unwrap().point_range(), 4..8);
+    assert!(path.subpath(3).is_none());
+    assert!(Path::new().subpaths().next().is_none());
+}
+
+#[test]
+fn test_subpath_ids() {
+    let mut builder = Path::builder();
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.close();
+    builder.line_to(point(0.0, 20.0));
+    builder.close();
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    builder.close();
+    builder.line_to(point(20.0, 10.0));
+    builder.move_to(point(50.0, 50.0));
+    let path = builder.build();
+
+    assert_eq!(path.subpaths().count(), 3);
+
+    // The sub-path identifiers of the events are the indices of the sub-paths
+    // they belong to.
+    for (verb, evt) in path.id_iter().enumerate() {
+        let range = path.subpath(evt.sub_path.to_usize()).unwrap().verb_range();
+        assert!(range.start <= verb && verb < range.end, "{:?} {:?}", evt, range);
+    }
+}
+
+#[test]
+fn test_cursor_split() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(10.0, 10.0), point(0.0, 10.0));
+    builder.close();
+    let mut path = builder.build();
+
+    {
+        let mut cursor = path.cursor();
+        assert_eq!(cursor.verb(), Some(Verb::MoveTo));
+        assert!(!cursor.split_at(0.5));
+        assert!(cursor.move_to_next());
+        assert!(cursor.split_at(0.5));
+        assert_eq!(cursor.event(), Some(PathEvent::LineTo(point(5.0, 0.0))));
+        assert!(cursor.move_to_next());
+        assert_eq!(cursor.from(), point(5.0, 0.0));
+        assert_eq!(cursor.event(), Some(PathEvent::LineTo(point(10.0, 0.0))));
+        assert!(cursor.move_to_next());
+        assert!(cursor.split_at(0.5));
+        assert_eq!(cursor.to(), Some(point(7.5, 7.5)));
+        assert!(cursor.move_to_next());
+        assert_eq!(cursor.to(), Some(point(0.0, 10.0)));
+        assert!(cursor.move_to_next());
+        assert!(cursor.split_at(0.5));
+        assert_eq!(cursor.event(), Some(PathEvent::LineTo(point(0.0, 5.0))));
+        assert!(cursor.move_to_next());
+        assert_eq!(cursor.event(), Some(PathEvent::Close));
+        assert!(!cursor.move_to_next());
+        assert!(!cursor.split_at(0.5));
+    }
+
+    assert_eq!(path.verbs.len(), 7);
+    assert_eq!(path.points.len(), 8);
+    let rect = path.bounding_rect();
+    assert_eq!(rect.origin, point(0.0, 0.0));
+}
+
+#[test]
+fn test_cursor_split_arc() {
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 0.0));
+    builder.arc(point(0.0, 0.0), vector(1.0, 1.0), Radians::new(PI), Radians::new(0.0));
+    let mut path = builder.build();
+
+    let mut cursor = path.cursor();
+    cursor.move_to_next();
+    assert!(cursor.split_at(0.5));
+    let mid = cursor.to().unwrap();
+    assert!((mid - point(0.0, 1.0)).length() < 0.001);
+    cursor.move_to_next();
+    let end = cursor.to().unwrap();
+    assert!((end - point(-1.0, 0.0)).length() < 0.001);
+}
+
+#[test]
+fn test_cursor_remove_endpoint() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.cubic_bezier_to(point(2.0, 0.0), point(2.0, 1.0), point(1.0, 1.0));
+    builder.close();
+    builder.move_to(point(5.0, 5.0));
+    builder.close();
+    let mut path = builder.build();
+
+    {
+        let mut cursor = path.cursor();
+        assert!(cursor.remove_endpoint());
+        assert_eq!(cursor.event(), Some(PathEvent::MoveTo(point(1.0, 0.0))));
+        cursor.move_to_next();
+        assert!(cursor.remove_endpoint());
+        assert_eq!(cursor.event(), Some(PathEvent::Close));
+        assert_eq!(cursor.from(), point(1.0, 0.0));
+        assert!(cursor.remove_endpoint());
+        assert_eq!(cursor.event(), Some(PathEvent::MoveTo(point(5.0, 5.0))));
+        assert!(cursor.remove_endpoint());
+        assert_eq!(cursor.event(), None);
+        assert!(!cursor.remove_endpoint());
+    }
+
+    assert_eq!(path.verbs, vec![Verb::MoveTo]);
+    assert_eq!(path.points, vec![point(1.0, 0.0)]);
+}
+
+#[test]
+fn test_cursor_conversions() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(3.0, 0.0));
+    builder.arc(point(3.0, 1.0), vector(1.0, 1.0), Radians::new(PI), Radians::new(0.0));
+    let mut path = builder.build();
+
+    let mut cursor = path.cursor();
+    assert!(!cursor.convert_to_cubic());
+    cursor.move_to_next();
+    assert!(cursor.convert_to_cubic());
+    assert_eq!(
+        cursor.event(),
+        Some(PathEvent::CubicTo(point(1.0, 0.0), point(2.0, 0.0), point(3.0, 0.0)))
+    );
+    assert!(!cursor.convert_to_quadratic());
+    assert!(cursor.convert_to_line());
+    assert!(cursor.convert_to_quadratic());
+    assert_eq!(cursor.event(), Some(PathEvent::QuadraticTo(point(1.5, 0.0), point(3.0, 0.0))));
+    assert!(cursor.convert_to_cubic());
+    assert_eq!(
+        cursor.event(),
+        Some(PathEvent::CubicTo(point(1.0, 0.0), point(2.0, 0.0), point(3.0, 0.0)))
+    );
+
+    cursor.move_to_next();
+    assert_eq!(cursor.verb(), Some(Verb::Arc));
+    let end = cursor.to().unwrap();
+    assert!(cursor.convert_to_cubic());
+    assert_eq!(cursor.verb(), Some(Verb::CubicTo));
+    assert_eq!(cursor.from(), point(3.0, 0.0));
+    while cursor.move_to_next() {
+        assert_eq!(cursor.verb(), Some(Verb::CubicTo));
+    }
+    cursor.move_to_previous();
+    assert!((cursor.to().unwrap() - end).length() < 0.001);
+
+    assert!(cursor.convert_to_line());
+    assert!((cursor.to().unwrap() - end).length() < 0.001);
+}
+
+#[test]
+fn test_cursor_positions() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.arc(point(1.0, 1.0), vector(1.0, 1.0), Radians::new(PI), Radians::new(0.0));
+    builder.close();
+    builder.move_to(point(5.0, 0.0));
+    builder.quadratic_bezier_to(point(6.0, 0.0), point(6.0, 1.0));
+    builder.close();
+    let mut path = builder.build();
+
+    // The positions kept by the cursor match the ones found by iterating the path.
+    let mut expected = Vec::new();
+    let mut state = PathState::new();
+    for evt in path.iter() {
+        let from = state.current;
+        state.path_event(evt);
+        expected.push((from, state.current));
+    }
+
+    let mut cursor = path.cursor();
+    for (i, &(from, to)) in expected.iter().enumerate() {
+        assert_eq!(cursor.verb_index(), i);
+        assert_eq!(cursor.from(), from);
+        assert_eq!(cursor.to(), Some(to));
+        cursor.move_to_next();
+    }
+    assert_eq!(cursor.to(), None);
+    for i in (0..expected.len()).rev() {
+        assert!(cursor.move_to_previous());
+        assert_eq!(cursor.verb_index(), i);
+        assert_eq!(cursor.from(), expected[i].0);
+        assert_eq!(cursor.to(), Some(expected[i].1));
+    }
+    cursor.seek(5);
+    assert_eq!(cursor.from(), expected[5].0);
+    cursor.seek(2);
+    assert_eq!(cursor.from(), expected[2].0);
+
+    cursor.seek(expected.len());
+    cursor.open_sub_path();
+    assert!(cursor.move_to_previous());
+    assert_eq!(cursor.verb(), Some(Verb::QuadraticTo));
+    assert_eq!(cursor.from(), point(5.0, 0.0));
+}
+
+#[test]
+fn test_cursor_open_close() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.move_to(point(5.0, 0.0));
+    builder.line_to(point(6.0, 0.0));
+    builder.close();
+    let mut path = builder.build();
+
+    {
+        let mut cursor = path.cursor();
+        cursor.close_sub_path();
+        cursor.close_sub_path();
+        cursor.seek(4);
+        assert_eq!(cursor.verb(), Some(Verb::LineTo));
+        cursor.open_sub_path();
+        cursor.open_sub_path();
+    }
+
+    assert_eq!(
+        path.verbs,
+        vec![Verb::MoveTo, Verb::LineTo, Verb::Close, Verb::MoveTo, Verb::LineTo]
+    );
+    assert!(path.subpath(0).unwrap().is_closed());
+    assert!(!path.subpath(1).unwrap().is_closed());
+}
+
+#[cfg(feature = "serialization")]
+#[test]
+fn test_serialization() {
+    use serde_json;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0));
+    builder.cubic_bezier_to(point(2.0, 2.0), point(1.0, 2.0), point(0.0, 2.0));
+    builder.close();
+    builder.move_to(point(5.0, 0.0));
+    builder.arc(point(5.0, 1.0), vector(1.0, 1.0), Radians::new(PI), Radians::new(0.0));
+    let path = builder.build();
+
+    for evt in path.iter() {
+        let json = serde_json::to_string(&evt).unwrap();
+        assert_eq!(serde_json::from_str::<PathEvent>(&json).unwrap(), evt);
+    }
+
+    let json = serde_json::to_string(&path).unwrap();
+    let deserialized: Path = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.points, path.points);
+    assert_eq!(deserialized.verbs, path.verbs);
+
+    // Paths are written as a readable sequence of events, independently of the way
+    // points and verbs are stored.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0));
+    builder.close();
+    assert_eq!(
+        serde_json::to_string(&builder.build()).unwrap(),
+        r#"[{"MoveTo":[0.0,0.0]},{"LineTo":[1.0,0.0]},{"QuadraticTo":[[2.0,0.0],[2.0,1.0]]},"Close"]"#
+    );
+}
+
@@ -1,7 +1,10 @@
 use math::{Point, Vector, Radians};
 use ArcFlags;
 
+use std::u32;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum SvgEvent {
     MoveTo(Point),
     RelativeMoveTo(Vector),
@@ -25,6 +28,7 @@ pub enum SvgEvent {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum PathEvent {
     MoveTo(Point),
     LineTo(Point),
@@ -35,6 +39,7 @@ pub enum PathEvent {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum QuadraticPathEvent {
     MoveTo,
     LineTo,
@@ -43,12 +48,29 @@ pub enum QuadraticPathEvent {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum FlattenedEvent {
     MoveTo(Point),
     LineTo(Point),
     Close,
 }
 
+/// A `FlattenedEvent` along with the location of its endpoint in the original path.
+///
+/// See `PathIterator::flattened_with_t`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct FlattenedEventWithT {
+    pub event: FlattenedEvent,
+    /// Index of the `PathEvent` that produced this event in the original sequence of events.
+    pub segment_index: usize,
+    /// Curve parameter of the event's endpoint on the original segment.
+    ///
+    /// It is always `1.0` for line segments and `close` events, and `0.0` for
+    /// `move_to` events.
+    pub t: f32,
+}
+
 impl FlattenedEvent {
     pub fn to_svg_event(self) -> SvgEvent {
         return match self {
@@ -74,3 +96,54 @@ impl Into<PathEvent> for FlattenedEvent {
 impl Into<SvgEvent> for FlattenedEvent {
     fn into(self) -> SvgEvent { self.to_svg_event() }
 }
+
+/// Identifies an endpoint of a path.
+///
+/// Endpoints are numbered in the order in which they appear in the path's events,
+/// each event except `Close` adding one endpoint.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct EndpointId(pub u32);
+
+impl EndpointId {
+    /// An identifier that doesn't refer to any endpoint.
+    pub const INVALID: EndpointId = EndpointId(u32::MAX);
+
+    pub fn to_usize(&self) -> usize { self.0 as usize }
+
+    pub fn is_valid(&self) -> bool { *self != EndpointId::INVALID }
+}
+
+/// Identifies a sub-path of a path.
+///
+/// Sub-paths are numbered in order, starting at zero. A new sub-path starts with each
+/// `MoveTo` event, so that identifiers match the indices of `Path::subpath`. Events that
+/// follow a `Close` event without a `MoveTo` are part of the closed sub-path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct SubPathId(pub u32);
+
+impl SubPathId {
+    /// An identifier that doesn't refer to any sub-path.
+    pub const INVALID: SubPathId = SubPathId(u32::MAX);
+
+    pub fn to_usize(&self) -> usize { self.0 as usize }
+
+    pub fn is_valid(&self) -> bool { *self != SubPathId::INVALID }
+}
+
+/// A `PathEvent` along with the identifiers of the endpoints of its segment and of its
+/// sub-path.
+///
+/// For `MoveTo` events `from` and `to` are the same endpoint, and for `Close` events
+/// `to` is the first endpoint of the sub-path.
+///
+/// See `PathIterator::with_ids`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct IdEvent {
+    pub event: PathEvent,
+    pub from: EndpointId,
+    pub to: EndpointId,
+    pub sub_path: SubPathId,
+}
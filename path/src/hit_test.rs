@@ -0,0 +1,369 @@
+//! # Hit testing
+//!
+//! Determine whether a point is inside of a path without tessellating it.
+//!
+//! The winding number of the path around the point is computed by counting the crossings
+//! between the path and a horizontal ray going from the point towards positive x. Curves
+//! are split into y-monotonic pieces, and the crossing of each piece with the ray is
+//! located by bisection, which only relies on evaluating the curve. This works the same way
+//! for lines, quadratic and cubic bézier curves and arcs. Each piece is treated as
+//! including its lower endpoint and excluding its upper one, so that a ray going through
+//! a vertex of the path is counted exactly once.
+//!
+//! Sub-paths that are not explicitly closed are treated as if they were.
+//! Points that are exactly on the boundary of the path may be considered either inside or
+//! outside.
+//!
+//! When testing many points against the same path, a
+//! [SegmentIndex](../spatial_index/struct.SegmentIndex.html) avoids going through all of
+//! the segments for each point.
+//!
+//! ## Examples
+//!
+//! ```
+//! # extern crate lyon_path;
+//! # fn main() {
+//! use lyon_path::default::Path;
+//! use lyon_path::math::point;
+//! use lyon_path::builder::*;
+//! use lyon_path::hit_test::contains_point;
+//! use lyon_path::FillRule;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.quadratic_bezier_to(point(1.0, 2.0), point(2.0, 0.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! assert!(contains_point(path.path_iter(), point(1.0, 0.5), FillRule::EvenOdd, 0.01));
+//! assert!(!contains_point(path.path_iter(), point(1.0, 1.5), FillRule::EvenOdd, 0.01));
+//! # }
+//! ```
+
+use math::Point;
+use geom::{QuadraticBezierSegment, CubicBezierSegment, Arc};
+use geom::predicates::orient2d;
+use geom::traits::{Segment, BoundingRect};
+use iterator::PathIterator;
+use {PathEvent, PathSegment, FillRule};
+
+use std::f32::consts::PI;
+
+/// Returns whether a point is inside of a path, according to a fill rule.
+///
+/// The crossings between the path's curves and the horizontal ray going through
+/// the point are located with a precision of `tolerance`.
+pub fn contains_point<Iter>(path: Iter, point: Point, fill_rule: FillRule, tolerance: f32) -> bool
+where
+    Iter: PathIterator,
+{
+    fill_rule.is_in(winding_number(path, point, tolerance))
+}
+
+/// Computes the winding number of a path around a point.
+///
+/// The winding number is positive if the path goes around the point in counter-clockwise
+/// order (assuming the y axis points upwards).
+///
+/// The crossings between the path's curves and the horizontal ray going through
+/// the point are located with a precision of `tolerance`.
+pub fn winding_number<Iter>(path: Iter, point: Point, tolerance: f32) -> i32
+where
+    Iter: PathIterator,
+{
+    let mut winding = 0;
+    let mut first = Point::new(0.0, 0.0);
+    let mut current = first;
+    for evt in path {
+        match evt {
+            PathEvent::MoveTo(to) => {
+                winding += line_winding(current, first, point);
+                first = to;
+                current = to;
+            }
+            PathEvent::Close => {
+                winding += line_winding(current, first, point);
+                current = first;
+            }
+            _ => {
+                let segment = PathSegment::from_event(current, evt).unwrap();
+                winding += segment_winding(&segment, point, tolerance);
+                current = segment.to();
+            }
+        }
+    }
+    winding += line_winding(current, first, point);
+
+    winding
+}
+
+// Computes the contribution of a segment to the winding number around a point.
+pub(crate) fn segment_winding(segment: &PathSegment, p: Point, tolerance: f32) -> i32 {
+    match *segment {
+        PathSegment::Line(ref line) => line_winding(line.from, line.to, p),
+        PathSegment::Quadratic(ref curve) => quadratic_winding(curve, p, tolerance),
+        PathSegment::Cubic(ref curve) => cubic_winding(curve, p, tolerance),
+        PathSegment::Arc(ref arc) => arc_winding(arc, p, tolerance),
+    }
+}
+
+pub(crate) fn line_winding(from: Point, to: Point, p: Point) -> i32 {
+    if from.y <= p.y {
+        if to.y > p.y && orient2d(from, to, p) > 0.0 {
+            return 1;
+        }
+    } else if to.y <= p.y && orient2d(from, to, p) < 0.0 {
+        return -1;
+    }
+
+    0
+}
+
+fn quadratic_winding(curve: &QuadraticBezierSegment, p: Point, tolerance: f32) -> i32 {
+    if let Some(winding) = quick_winding(curve, p) {
+        return winding;
+    }
+
+    match curve.find_local_y_extremum() {
+        Some(t) => {
+            monotonic_winding(curve, 0.0, t, p, tolerance)
+                + monotonic_winding(curve, t, 1.0, p, tolerance)
+        }
+        None => monotonic_winding(curve, 0.0, 1.0, p, tolerance),
+    }
+}
+
+fn cubic_winding(curve: &CubicBezierSegment, p: Point, tolerance: f32) -> i32 {
+    if let Some(winding) = quick_winding(curve, p) {
+        return winding;
+    }
+
+    let mut splits = [1.0; 3];
+    for (i, t) in curve.find_local_y_extrema().iter().enumerate() {
+        splits[i] = *t;
+    }
+    if splits[0] > splits[1] {
+        splits.swap(0, 1);
+    }
+
+    let mut winding = 0;
+    let mut t0 = 0.0;
+    for &t1 in &splits {
+        if t1 > t0 {
+            winding += monotonic_winding(curve, t0, t1, p, tolerance);
+            t0 = t1;
+        }
+    }
+
+    winding
+}
+
+fn arc_winding(arc: &Arc, p: Point, tolerance: f32) -> i32 {
+    let sweep = arc.sweep_angle.get();
+    if sweep == 0.0 || !sweep.is_finite() {
+        return 0;
+    }
+
+    if let Some(winding) = quick_winding(arc, p) {
+        return winding;
+    }
+
+    // The y extrema of the ellipse are at the angles a0 + k * PI.
+    let x_rotation = arc.x_rotation.get();
+    let a0 = (arc.radii.y * x_rotation.cos()).atan2(arc.radii.x * x_rotation.sin());
+    let start = arc.start_angle.get();
+    let direction = sweep.signum();
+
+    let mut winding = 0;
+    let mut t0 = 0.0;
+    let mut k = ((start - a0) / PI).floor();
+    loop {
+        let t1 = (a0 + k * PI - start) / sweep;
+        if t1 >= 1.0 {
+            break;
+        }
+        if t1 > t0 {
+            winding += monotonic_winding(arc, t0, t1, p, tolerance);
+            t0 = t1;
+        }
+        k += direction;
+    }
+    winding += monotonic_winding(arc, t0, 1.0, p, tolerance);
+
+    winding
+}
+
+// Handles the common cases where the curve is entirely above, below, on the left or
+// on the right of the point.
+fn quick_winding<S: Segment + BoundingRect>(curve: &S, p: Point) -> Option<i32> {
+    let (min_y, max_y) = curve.fast_bounding_range_y();
+    if p.y < min_y || p.y > max_y {
+        return Some(0);
+    }
+
+    let (min_x, max_x) = curve.fast_bounding_range_x();
+    if p.x > max_x {
+        return Some(0);
+    }
+    if p.x < min_x {
+        // The ray crosses the whole curve, only the endpoints matter.
+        return Some(line_winding_y(curve.from(), curve.to(), p));
+    }
+
+    None
+}
+
+// Same as line_winding, for a line that is known to be on the right of the point.
+fn line_winding_y(from: Point, to: Point, p: Point) -> i32 {
+    if from.y <= p.y && to.y > p.y {
+        return 1;
+    }
+    if to.y <= p.y && from.y > p.y {
+        return -1;
+    }
+
+    0
+}
+
+// Computes the contribution to the winding number of the y-monotonic part of a curve
+// between t0 and t1.
+fn monotonic_winding<S: Segment>(curve: &S, t0: f32, t1: f32, p: Point, tolerance: f32) -> i32 {
+    let mut from = if t0 == 0.0 { curve.from() } else { curve.sample(t0) };
+    let mut to = if t1 == 1.0 { curve.to() } else { curve.sample(t1) };
+
+    let winding = line_winding_y(from, to, p);
+    if winding == 0 {
+        return 0;
+    }
+
+    // Narrow down the part of the curve that crosses the ray, until it is
+    // small enough to be treated as a line segment.
+    let from_is_below = from.y <= p.y;
+    let square_tolerance = tolerance * tolerance;
+    let mut t_from = t0;
+    let mut t_to = t1;
+    for _ in 0..32 {
+        if (to - from).square_length() <= square_tolerance {
+            break;
+        }
+
+        let t = (t_from + t_to) * 0.5;
+        let mid = curve.sample(t);
+        if (mid.y <= p.y) == from_is_below {
+            from = mid;
+            t_from = t;
+        } else {
+            to = mid;
+            t_to = t;
+        }
+    }
+
+    if line_winding(from, to, p) != 0 {
+        return winding;
+    }
+
+    0
+}
+
+#[cfg(test)]
+use default::Path;
+#[cfg(test)]
+use builder::{FlatPathBuilder, PathBuilder, PolygonBuilder};
+#[cfg(test)]
+use math::{point, vector, Radians};
+
+#[cfg(test)]
+fn contains(path: &Path, p: Point, fill_rule: FillRule) -> bool {
+    contains_point(path.path_iter(), p, fill_rule, 0.0001)
+}
+
+#[test]
+fn hit_test_polygons() {
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, 0.0), point(4.0, 0.0), point(4.0, 4.0), point(0.0, 4.0)]);
+    builder.polygon(&[point(1.0, 1.0), point(3.0, 1.0), point(3.0, 3.0), point(1.0, 3.0)]);
+    let path = builder.build();
+
+    assert!(contains(&path, point(0.5, 0.5), FillRule::EvenOdd));
+    assert!(contains(&path, point(0.5, 0.5), FillRule::NonZero));
+    assert!(!contains(&path, point(2.0, 2.0), FillRule::EvenOdd));
+    assert!(contains(&path, point(2.0, 2.0), FillRule::NonZero));
+    assert!(!contains(&path, point(5.0, 2.0), FillRule::NonZero));
+    assert!(!contains(&path, point(-1.0, 2.0), FillRule::NonZero));
+    assert_eq!(winding_number(path.path_iter(), point(2.0, 2.0), 0.01), 2);
+
+    // The ray goes through a vertex of the diamond.
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, -1.0), point(1.0, 0.0), point(0.0, 1.0), point(-1.0, 0.0)]);
+    let path = builder.build();
+    assert!(contains(&path, point(-0.5, 0.0), FillRule::EvenOdd));
+    assert!(contains(&path, point(0.5, 0.0), FillRule::EvenOdd));
+    assert!(!contains(&path, point(-1.5, 0.0), FillRule::EvenOdd));
+    assert!(!contains(&path, point(1.5, 0.0), FillRule::EvenOdd));
+
+    // Open sub-paths are implicitly closed.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    let path = builder.build();
+    assert!(contains(&path, point(0.9, 0.5), FillRule::EvenOdd));
+    assert!(!contains(&path, point(0.1, 0.5), FillRule::EvenOdd));
+}
+
+#[test]
+fn hit_test_curves() {
+    // A circle of radius 1 made of four cubic bézier curves.
+    let k = 0.5522848;
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 0.0));
+    builder.cubic_bezier_to(point(1.0, k), point(k, 1.0), point(0.0, 1.0));
+    builder.cubic_bezier_to(point(-k, 1.0), point(-1.0, k), point(-1.0, 0.0));
+    builder.cubic_bezier_to(point(-1.0, -k), point(-k, -1.0), point(0.0, -1.0));
+    builder.cubic_bezier_to(point(k, -1.0), point(1.0, -k), point(1.0, 0.0));
+    builder.close();
+    let circle = builder.build();
+
+    let s = 0.5f32.sqrt();
+    for &(radius, expected) in &[(0.99, true), (1.01, false)] {
+        for &v in &[vector(1.0, 0.0), vector(s, s), vector(-s, s), vector(0.0, -1.0), vector(s, -s)] {
+            let p = point(0.0, 0.0) + v * radius;
+            assert_eq!(contains(&circle, p, FillRule::NonZero), expected);
+        }
+    }
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(1.0, 2.0), point(2.0, 0.0));
+    builder.close();
+    let path = builder.build();
+    // The top of the curve is at y = 1.
+    assert!(contains(&path, point(1.0, 0.99), FillRule::EvenOdd));
+    assert!(!contains(&path, point(1.0, 1.01), FillRule::EvenOdd));
+    assert!(contains(&path, point(0.2, 0.3), FillRule::EvenOdd));
+    assert!(!contains(&path, point(0.2, 0.4), FillRule::EvenOdd));
+}
+
+#[test]
+fn hit_test_arcs() {
+    for &sweep in &[2.0 * PI, -2.0 * PI] {
+        // An ellipse rotated by 0.5 radians.
+        let (sin, cos) = 0.5f32.sin_cos();
+        let u = vector(cos, sin);
+        let v = vector(-sin, cos);
+        let center = point(0.0, 0.0);
+
+        let mut builder = Path::builder();
+        builder.move_to(center + u * 2.0);
+        builder.arc(center, vector(2.0, 1.0), Radians::new(sweep), Radians::new(0.5));
+        builder.close();
+        let path = builder.build();
+
+        assert!(contains(&path, center + u * 1.9, FillRule::EvenOdd));
+        assert!(!contains(&path, center + u * 2.1, FillRule::EvenOdd));
+        assert!(contains(&path, center - v * 0.9, FillRule::EvenOdd));
+        assert!(!contains(&path, center - v * 1.1, FillRule::EvenOdd));
+        assert!(!contains(&path, point(1.5, -1.5), FillRule::EvenOdd));
+        assert_eq!(winding_number(path.path_iter(), center, 0.01), if sweep > 0.0 { 1 } else { -1 });
+    }
+}
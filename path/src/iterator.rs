@@ -101,10 +101,11 @@
 use std::iter;
 
 use math::*;
-use {PathEvent, SvgEvent, FlattenedEvent, PathState};
+use {PathEvent, SvgEvent, FlattenedEvent, FlattenedEventWithT, PathState, IdState, IdEvent};
 use geom::{QuadraticBezierSegment, CubicBezierSegment, quadratic_bezier, cubic_bezier};
 use geom::utils::vector_angle;
-use geom::arc;
+use geom::{arc, Arc};
+use geom::traits::FlatteningIterator;
 use walk;
 use builder::FlatPathBuilder;
 
@@ -119,6 +120,26 @@ pub trait PathIterator: Iterator<Item = PathEvent> + Sized {
     fn flattened(self, tolerance: f32) -> Flattened<Self> {
         Flattened::new(tolerance, self)
     }
+
+    /// Returns an iterator that turns curves into line segments and reports, for each
+    /// event, the index of the original segment and the curve parameter of the endpoint.
+    fn flattened_with_t(self, tolerance: f32) -> FlattenedWithT<Self> {
+        FlattenedWithT::new(tolerance, self)
+    }
+
+    /// Returns an iterator that reports, for each event, the identifiers of the endpoints
+    /// of its segment and of its sub-path.
+    fn with_ids(self) -> WithIds<Self> {
+        WithIds::new(self)
+    }
+
+    /// Returns an iterator applying a 2D transform to all of its events.
+    ///
+    /// Arcs are converted into quadratic bézier curves if they can't be represented after
+    /// the transform, for example with non-uniform scales and skews.
+    fn transformed(self, transform: &Transform2D) -> Transformed<Self> {
+        Transformed::new(transform, self)
+    }
 }
 
 /// An extension to the common Iterator interface, that adds information which is useful when
@@ -135,6 +156,14 @@ pub trait SvgIterator: Iterator<Item = SvgEvent> + Sized {
 
     /// Returns an iterator of path events.
     fn path_events(self) -> PathEvents<Self> { PathEvents::new(self) }
+
+    /// Returns an iterator of path events, applying a 2D transform to all of them.
+    ///
+    /// Relative, horizontal and vertical events don't survive arbitrary transforms so
+    /// the events are converted into absolute `PathEvent`s.
+    fn transformed(self, transform: &Transform2D) -> Transformed<PathEvents<Self>> {
+        self.path_events().transformed(transform)
+    }
 }
 
 /// An extension to the common Iterator interface, that adds information which is useful when
@@ -154,6 +183,11 @@ pub trait FlattenedIterator: Iterator<Item = FlattenedEvent> + Sized {
         self.map(flattened_to_svg_event)
     }
 
+    /// Returns an iterator applying a 2D transform to all of its events.
+    fn transformed(self, transform: &Transform2D) -> Transformed<Self> {
+        Transformed::new(transform, self)
+    }
+
     /// Walks along the path staring from `start` and applies a `Pattern`.
     fn walk(self, start: f32, pattern: &mut walk::Pattern) {
         let mut walker = walk::PathWalker::new(start, pattern);
@@ -196,6 +230,8 @@ pub struct Flattened<Iter> {
     it: Iter,
     current_curve: TmpFlatteningIter,
     tolerance: f32,
+    // Number of events consumed from the underlying iterator.
+    segment_count: usize,
 }
 
 enum TmpFlatteningIter {
@@ -212,48 +248,40 @@ impl<Iter: PathIterator> Flattened<Iter> {
             it: it,
             current_curve: TmpFlatteningIter::None,
             tolerance: tolerance,
+            segment_count: 0,
         }
     }
-}
 
-impl<Iter> FlattenedIterator for Flattened<Iter>
-where
-    Iter: PathIterator,
-{
-    fn get_state(&self) -> &PathState { self.it.get_state() }
-}
-
-impl<Iter> Iterator for Flattened<Iter>
-where
-    Iter: PathIterator,
-{
-    type Item = FlattenedEvent;
-    fn next(&mut self) -> Option<FlattenedEvent> {
-        match self.current_curve {
-            TmpFlatteningIter::Quadratic(ref mut it) => {
-                if let Some(point) = it.next() {
-                    return Some(FlattenedEvent::LineTo(point));
-                }
-            }
-            TmpFlatteningIter::Cubic(ref mut it) => {
-                if let Some(point) = it.next() {
-                    return Some(FlattenedEvent::LineTo(point));
-                }
-            }
-            TmpFlatteningIter::Arc(ref mut it) => {
-                if let Some(point) = it.next() {
-                    return Some(FlattenedEvent::LineTo(point));
-                }
-            }
-            _ => {}
+    /// Returns the next event along with the index of the original segment and
+    /// the curve parameter of the event's endpoint.
+    pub fn next_with_t(&mut self) -> Option<FlattenedEventWithT> {
+        let next_point = match self.current_curve {
+            TmpFlatteningIter::Quadratic(ref mut it) => it.next_with_t(),
+            TmpFlatteningIter::Cubic(ref mut it) => it.next_with_t(),
+            TmpFlatteningIter::Arc(ref mut it) => it.next_with_t(),
+            TmpFlatteningIter::None => None,
+        };
+        if let Some((point, t)) = next_point {
+            return Some(FlattenedEventWithT {
+                event: FlattenedEvent::LineTo(point),
+                segment_index: self.segment_count - 1,
+                t: t,
+            });
         }
+
         self.current_curve = TmpFlatteningIter::None;
         let current = self.get_state().current;
-        return match self.it.next() {
-            Some(PathEvent::MoveTo(to)) => Some(FlattenedEvent::MoveTo(to)),
-            Some(PathEvent::LineTo(to)) => Some(FlattenedEvent::LineTo(to)),
-            Some(PathEvent::Close) => Some(FlattenedEvent::Close),
-            Some(PathEvent::QuadraticTo(ctrl, to)) => {
+        let evt = match self.it.next() {
+            Some(evt) => evt,
+            None => { return None; }
+        };
+        self.segment_count += 1;
+
+        let (event, t) = match evt {
+            PathEvent::MoveTo(to) => (FlattenedEvent::MoveTo(to), 0.0),
+            PathEvent::LineTo(to) => (FlattenedEvent::LineTo(to), 1.0),
+            PathEvent::Close => (FlattenedEvent::Close, 1.0),
+            PathEvent::QuadraticTo(ctrl, to) => {
                 self.current_curve = TmpFlatteningIter::Quadratic(
                     QuadraticBezierSegment {
                             from: current,
@@ -261,9 +289,9 @@ where
                             to: to,
                     }.flattened(self.tolerance)
                 );
-                return self.next();
+                return self.next_with_t();
             }
-            Some(PathEvent::CubicTo(ctrl1, ctrl2, to)) => {
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
                 self.current_curve = TmpFlatteningIter::Cubic(
                     CubicBezierSegment {
                         from: current,
@@ -272,9 +300,9 @@ where
                         to: to,
                     }.flattened(self.tolerance)
                 );
-                return self.next();
+                return self.next_with_t();
             }
-            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => {
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
                 let start_angle = vector_angle(current - center);
                 self.current_curve = TmpFlatteningIter::Arc(
                     arc::Arc {
@@ -283,10 +311,94 @@ where
                         x_rotation
                     }.flattened(self.tolerance)
                 );
-                return self.next();
+                return self.next_with_t();
             }
-            None => None,
         };
+
+        return Some(FlattenedEventWithT {
+            event: event,
+            segment_index: self.segment_count - 1,
+            t: t,
+        });
+    }
+}
+
+impl<Iter> FlattenedIterator for Flattened<Iter>
+where
+    Iter: PathIterator,
+{
+    fn get_state(&self) -> &PathState { self.it.get_state() }
+}
+
+impl<Iter> Iterator for Flattened<Iter>
+where
+    Iter: PathIterator,
+{
+    type Item = FlattenedEvent;
+    fn next(&mut self) -> Option<FlattenedEvent> {
+        self.next_with_t().map(|evt| evt.event)
+    }
+}
+
+/// An iterator that consumes a PathIterator and yields FlattenedEvents along with the
+/// index of the segment that produced them and the curve parameter of their endpoint.
+///
+/// See `PathIterator::flattened_with_t`.
+pub struct FlattenedWithT<Iter> {
+    it: Flattened<Iter>,
+}
+
+impl<Iter: PathIterator> FlattenedWithT<Iter> {
+    /// Create the iterator.
+    pub fn new(tolerance: f32, it: Iter) -> Self {
+        FlattenedWithT { it: Flattened::new(tolerance, it) }
+    }
+
+    /// The returned structure exposes the current position, the first position in the current
+    /// sub-path, and the position of the last control point.
+    pub fn get_state(&self) -> &PathState { self.it.get_state() }
+}
+
+impl<Iter> Iterator for FlattenedWithT<Iter>
+where
+    Iter: PathIterator,
+{
+    type Item = FlattenedEventWithT;
+    fn next(&mut self) -> Option<FlattenedEventWithT> {
+        self.it.next_with_t()
+    }
+}
+
+/// An iterator that consumes a PathIterator and yields the events along with the
+/// identifiers of their endpoints and sub-path.
+///
+/// See `PathIterator::with_ids`.
+pub struct WithIds<Iter> {
+    it: Iter,
+    ids: IdState,
+}
+
+impl<Iter: PathIterator> WithIds<Iter> {
+    /// Create the iterator.
+    pub fn new(it: Iter) -> Self {
+        WithIds {
+            it: it,
+            ids: IdState::new(),
+        }
+    }
+
+    /// The returned structure exposes the current position, the first position in the current
+    /// sub-path, and the position of the last control point.
+    pub fn get_state(&self) -> &PathState { self.it.get_state() }
+}
+
+impl<Iter> Iterator for WithIds<Iter>
+where
+    Iter: PathIterator,
+{
+    type Item = IdEvent;
+    fn next(&mut self) -> Option<IdEvent> {
+        self.it.next().map(|evt| self.ids.path_event(evt))
     }
 }
 
@@ -381,6 +493,260 @@ where
     }
 }
 
+/// An iterator adapter that applies a 2D transform to the events of a path.
+///
+/// It can be created with the `transformed` method of `PathIterator`, `SvgIterator`
+/// and `FlattenedIterator`.
+pub struct Transformed<Iter> {
+    it: Iter,
+    transform: Transform2D,
+    similarity: Option<Similarity>,
+    // State of the untransformed path.
+    src_state: PathState,
+    // State of the transformed path.
+    state: PathState,
+    // Events that are produced when converting an arc into curves.
+    pending: Vec<PathEvent>,
+    pending_idx: usize,
+}
+
+impl<Iter> Transformed<Iter> {
+    /// Create the iterator.
+    pub fn new(transform: &Transform2D, it: Iter) -> Self {
+        Transformed {
+            it: it,
+            transform: *transform,
+            similarity: Similarity::from_transform(transform),
+            src_state: PathState::new(),
+            state: PathState::new(),
+            pending: Vec::new(),
+            pending_idx: 0,
+        }
+    }
+}
+
+impl<Iter> PathIterator for Transformed<Iter>
+where
+    Iter: Iterator<Item = PathEvent>
+{
+    fn get_state(&self) -> &PathState { &self.state }
+}
+
+impl<Iter> FlattenedIterator for Transformed<Iter>
+where
+    Iter: Iterator<Item = FlattenedEvent>
+{
+    fn get_state(&self) -> &PathState { &self.state }
+}
+
+impl<E, Iter> Iterator for Transformed<Iter>
+where
+    E: TransformEvent,
+    Iter: Iterator<Item = E>
+{
+    type Item = E;
+    fn next(&mut self) -> Option<E> {
+        if let Some(evt) = E::next_pending(&self.pending, &mut self.pending_idx) {
+            evt.update_state(&mut self.state);
+            return Some(evt);
+        }
+
+        let evt = match self.it.next() {
+            Some(evt) => evt,
+            None => { return None; }
+        };
+
+        self.pending.clear();
+        self.pending_idx = 0;
+        let transformed = evt.transform_event(
+            &self.transform,
+            self.similarity.as_ref(),
+            &self.src_state,
+            &mut self.pending,
+        );
+        evt.update_state(&mut self.src_state);
+        transformed.update_state(&mut self.state);
+
+        return Some(transformed);
+    }
+}
+
+/// Path events that can be transformed by the [Transformed](struct.Transformed.html)
+/// iterator adapter.
+pub trait TransformEvent: Copy {
+    /// Applies the transform to this event.
+    ///
+    /// `state` is the state of the untransformed path before this event. If the event can't
+    /// be represented after the transform, the first event of its replacement is returned
+    /// and the other ones are pushed into `pending`.
+    fn transform_event(
+        &self,
+        transform: &Transform2D,
+        similarity: Option<&Similarity>,
+        state: &PathState,
+        pending: &mut Vec<PathEvent>,
+    ) -> Self;
+
+    /// Returns the next pending event, if any.
+    fn next_pending(pending: &[PathEvent], idx: &mut usize) -> Option<Self>;
+
+    /// Updates a path state with this event.
+    fn update_state(&self, state: &mut PathState);
+}
+
+impl TransformEvent for PathEvent {
+    fn transform_event(
+        &self,
+        transform: &Transform2D,
+        similarity: Option<&Similarity>,
+        state: &PathState,
+        pending: &mut Vec<PathEvent>,
+    ) -> Self {
+        match *self {
+            PathEvent::MoveTo(to) => PathEvent::MoveTo(transform.transform_point(&to)),
+            PathEvent::LineTo(to) => PathEvent::LineTo(transform.transform_point(&to)),
+            PathEvent::QuadraticTo(ctrl, to) => PathEvent::QuadraticTo(
+                transform.transform_point(&ctrl),
+                transform.transform_point(&to),
+            ),
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => PathEvent::CubicTo(
+                transform.transform_point(&ctrl1),
+                transform.transform_point(&ctrl2),
+                transform.transform_point(&to),
+            ),
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                let transformed_arc = similarity.and_then(|similarity| {
+                    similarity.transform_arc(radii, sweep_angle, x_rotation)
+                });
+                if let Some((radii, sweep_angle, x_rotation)) = transformed_arc {
+                    return PathEvent::Arc(
+                        transform.transform_point(&center),
+                        radii, sweep_angle, x_rotation,
+                    );
+                }
+
+                // The arc can't be represented after the transform (for example with a
+                // non-uniform scale or a skew), so it is converted into quadratic bézier
+                // curves which can be transformed exactly.
+                Arc {
+                    center: center,
+                    radii: radii,
+                    start_angle: vector_angle(state.current - center),
+                    sweep_angle: sweep_angle,
+                    x_rotation: x_rotation,
+                }.to_quadratic_beziers(&mut |ctrl, to| {
+                    pending.push(PathEvent::QuadraticTo(
+                        transform.transform_point(&ctrl),
+                        transform.transform_point(&to),
+                    ));
+                });
+
+                if pending.is_empty() {
+                    return PathEvent::LineTo(transform.transform_point(&state.current));
+                }
+
+                return pending.remove(0);
+            }
+            PathEvent::Close => PathEvent::Close,
+        }
+    }
+
+    fn next_pending(pending: &[PathEvent], idx: &mut usize) -> Option<Self> {
+        let evt = pending.get(*idx).cloned();
+        if evt.is_some() {
+            *idx += 1;
+        }
+
+        evt
+    }
+
+    fn update_state(&self, state: &mut PathState) { state.path_event(*self); }
+}
+
+impl TransformEvent for FlattenedEvent {
+    fn transform_event(
+        &self,
+        transform: &Transform2D,
+        _: Option<&Similarity>,
+        _: &PathState,
+        _: &mut Vec<PathEvent>,
+    ) -> Self {
+        match *self {
+            FlattenedEvent::MoveTo(to) => FlattenedEvent::MoveTo(transform.transform_point(&to)),
+            FlattenedEvent::LineTo(to) => FlattenedEvent::LineTo(transform.transform_point(&to)),
+            FlattenedEvent::Close => FlattenedEvent::Close,
+        }
+    }
+
+    fn next_pending(_: &[PathEvent], _: &mut usize) -> Option<Self> { None }
+
+    fn update_state(&self, state: &mut PathState) { state.flattened_event(*self); }
+}
+
+/// The decomposition of a transform that preserves angles into a uniform scale,
+/// a rotation and an optional reflection, followed by a translation.
+///
+/// Arcs can often be transformed by similarities without being converted into curves.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Similarity {
+    pub scale: f32,
+    pub rotation: Radians,
+    pub reflection: bool,
+}
+
+impl Similarity {
+    /// Returns the decomposition of the transform, or `None` if it is not a similarity.
+    pub fn from_transform(transform: &Transform2D) -> Option<Self> {
+        // Images of the x and y axes.
+        let x_axis: Vector = vector(transform.m11, transform.m12);
+        let y_axis: Vector = vector(transform.m21, transform.m22);
+        let sx = x_axis.length();
+        let sy = y_axis.length();
+        let epsilon = 0.00001 * sx.max(sy);
+        if (sx - sy).abs() > epsilon || x_axis.dot(y_axis).abs() > epsilon * sx.max(sy) {
+            return None;
+        }
+
+        Some(Similarity {
+            scale: sx,
+            rotation: Radians::new(x_axis.y.atan2(x_axis.x)),
+            reflection: x_axis.cross(y_axis) < 0.0,
+        })
+    }
+
+    /// Transforms the radii, sweep angle and x-rotation of an arc event.
+    ///
+    /// The center of the arc is transformed like any other point.
+    ///
+    /// The start angle of an arc event is the angle of the current position relative to
+    /// the center, so it follows the rotation on its own. This only describes the same
+    /// curve if the arc is circular or the rotation is a multiple of a half turn,
+    /// otherwise `None` is returned and the arc must be converted into curves.
+    pub fn transform_arc(
+        &self,
+        radii: Vector,
+        sweep_angle: Radians,
+        x_rotation: Radians,
+    ) -> Option<(Vector, Radians, Radians)> {
+        let epsilon = 0.00001;
+        if (radii.x - radii.y).abs() > epsilon * radii.x.abs().max(radii.y.abs())
+            && self.rotation.get().sin().abs() > epsilon {
+            return None;
+        }
+
+        let radii = radii * self.scale;
+        if self.reflection {
+            return Some((
+                radii,
+                Radians::new(-sweep_angle.get()),
+                Radians::new(-x_rotation.get()),
+            ));
+        }
+
+        Some((radii, sweep_angle, x_rotation))
+    }
+}
+
 fn flattened_to_path_event(evt: FlattenedEvent) -> PathEvent { evt.to_path_event() }
 fn flattened_to_svg_event(evt: FlattenedEvent) -> SvgEvent { evt.to_svg_event() }
 
@@ -492,3 +858,219 @@ fn test_from_polyline_closed() {
     assert_eq!(evts.next(), Some(FlattenedEvent::LineTo(point(5.0, 2.0))));
     assert_eq!(evts.next(), Some(FlattenedEvent::Close));
 }
+
+#[test]
+fn test_flattened_with_t() {
+    let events = [
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(1.0, 0.0)),
+        PathEvent::QuadraticTo(point(2.0, 0.0), point(2.0, 1.0)),
+        PathEvent::CubicTo(point(2.0, 2.0), point(0.0, 2.0), point(0.0, 1.0)),
+        PathEvent::Close,
+    ];
+
+    let flattened: Vec<FlattenedEvent> = PathIter::new(events.iter().cloned())
+        .flattened(0.01)
+        .collect();
+    let with_t: Vec<FlattenedEventWithT> = PathIter::new(events.iter().cloned())
+        .flattened_with_t(0.01)
+        .collect();
+
+    assert_eq!(flattened.len(), with_t.len());
+    for (evt, evt_with_t) in flattened.iter().zip(with_t.iter()) {
+        assert_eq!(*evt, evt_with_t.event);
+    }
+
+    assert_eq!(with_t[0].segment_index, 0);
+    assert_eq!(with_t[0].t, 0.0);
+    assert_eq!(with_t[1].segment_index, 1);
+    assert_eq!(with_t[1].t, 1.0);
+    assert_eq!(with_t.last().unwrap().segment_index, 4);
+
+    let quadratic = QuadraticBezierSegment {
+        from: point(1.0, 0.0),
+        ctrl: point(2.0, 0.0),
+        to: point(2.0, 1.0),
+    };
+    let cubic = CubicBezierSegment {
+        from: point(2.0, 1.0),
+        ctrl1: point(2.0, 2.0),
+        ctrl2: point(0.0, 2.0),
+        to: point(0.0, 1.0),
+    };
+
+    let mut prev_t = 1.0;
+    let mut prev_index = 1;
+    for evt in &with_t[2..with_t.len() - 1] {
+        if evt.segment_index != prev_index {
+            assert_eq!(prev_t, 1.0);
+            prev_t = 0.0;
+            prev_index = evt.segment_index;
+        }
+        assert!(evt.t > prev_t);
+        prev_t = evt.t;
+
+        let expected = match evt.segment_index {
+            2 => quadratic.sample(evt.t),
+            3 => cubic.sample(evt.t),
+            _ => panic!(),
+        };
+        match evt.event {
+            FlattenedEvent::LineTo(p) => { assert!((p - expected).length() < 0.001); }
+            _ => panic!(),
+        }
+    }
+    assert_eq!(prev_index, 3);
+    assert_eq!(prev_t, 1.0);
+}
+
+#[cfg(test)]
+fn arc_events(sweep_angle: f32) -> [PathEvent; 2] {
+    [
+        PathEvent::MoveTo(point(2.0, 1.0)),
+        PathEvent::Arc(point(1.0, 1.0), vector(1.0, 1.0), Radians::new(sweep_angle), Radians::new(0.0)),
+    ]
+}
+
+#[test]
+fn test_transformed_lines_and_curves() {
+    let events = [
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(1.0, 0.0)),
+        PathEvent::QuadraticTo(point(2.0, 0.0), point(2.0, 1.0)),
+        PathEvent::CubicTo(point(2.0, 2.0), point(0.0, 2.0), point(0.0, 1.0)),
+        PathEvent::Close,
+    ];
+    let transform = Transform2D::create_scale(2.0, 3.0).post_translate(vector(1.0, 1.0));
+
+    let mut it = PathIter::new(events.iter().cloned()).transformed(&transform);
+    assert_eq!(it.next(), Some(PathEvent::MoveTo(point(1.0, 1.0))));
+    assert_eq!(it.next(), Some(PathEvent::LineTo(point(3.0, 1.0))));
+    assert_eq!(it.get_state().current, point(3.0, 1.0));
+    assert_eq!(it.next(), Some(PathEvent::QuadraticTo(point(5.0, 1.0), point(5.0, 4.0))));
+    assert_eq!(it.next(), Some(PathEvent::CubicTo(point(5.0, 7.0), point(1.0, 7.0), point(1.0, 4.0))));
+    assert_eq!(it.next(), Some(PathEvent::Close));
+    assert_eq!(it.get_state().current, point(1.0, 1.0));
+    assert_eq!(it.next(), None);
+
+    let mut flattened = PathIter::new(events[0..2].iter().cloned())
+        .flattened(0.01)
+        .transformed(&transform);
+    assert_eq!(flattened.next(), Some(FlattenedEvent::MoveTo(point(1.0, 1.0))));
+    assert_eq!(flattened.next(), Some(FlattenedEvent::LineTo(point(3.0, 1.0))));
+    assert_eq!(flattened.get_state().current, point(3.0, 1.0));
+    assert_eq!(flattened.next(), None);
+}
+
+#[test]
+fn test_transformed_arc_similarity() {
+    let original = Arc {
+        center: point(1.0, 1.0),
+        radii: vector(1.0, 1.0),
+        start_angle: Radians::new(0.0),
+        sweep_angle: Radians::new(1.5),
+        x_rotation: Radians::new(0.0),
+    };
+
+    let transforms = [
+        Transform2D::create_rotation(Radians::new(0.7)).post_translate(vector(3.0, -2.0)),
+        // With a reflection.
+        Transform2D::create_scale(2.0, -2.0).post_rotate(Radians::new(0.3)),
+    ];
+
+    for transform in &transforms {
+        let events = arc_events(1.5);
+        let mut it = PathIter::new(events.iter().cloned()).transformed(transform);
+        it.next();
+        let current = it.get_state().current;
+        let arc = match it.next() {
+            Some(PathEvent::Arc(center, radii, sweep_angle, x_rotation)) => Arc {
+                center: center,
+                radii: radii,
+                start_angle: vector_angle(current - center),
+                sweep_angle: sweep_angle,
+                x_rotation: x_rotation,
+            },
+            evt => panic!("{:?}", evt),
+        };
+        assert_eq!(it.next(), None);
+
+        for &t in &[0.0, 0.3, 0.5, 1.0] {
+            let expected = transform.transform_point(&original.sample(t));
+            assert!((arc.sample(t) - expected).length() < 0.001);
+        }
+    }
+}
+
+#[test]
+fn test_transformed_arc_to_curves() {
+    let transform = Transform2D::create_scale(2.0, 1.0);
+    let events = arc_events(-1.5);
+    let transformed: Vec<PathEvent> = PathIter::new(events.iter().cloned())
+        .transformed(&transform)
+        .collect();
+
+    assert_eq!(transformed[0], PathEvent::MoveTo(point(4.0, 1.0)));
+    assert!(transformed.len() > 2);
+    for evt in &transformed[1..] {
+        match *evt {
+            PathEvent::QuadraticTo(_, to) => {
+                // The endpoints are on the transformed ellipse.
+                let v = to - point(2.0, 1.0);
+                assert!(((v.x * 0.5) * (v.x * 0.5) + v.y * v.y - 1.0).abs() < 0.0001);
+                assert!(to.y <= 1.0);
+            }
+            _ => panic!("{:?}", evt),
+        }
+    }
+
+    let end = transform.transform_point(&point(1.0 + 1.5f32.cos(), 1.0 - 1.5f32.sin()));
+    match *transformed.last().unwrap() {
+        PathEvent::QuadraticTo(_, to) => { assert!((to - end).length() < 0.0001); }
+        _ => panic!(),
+    }
+
+    // Rotated elliptic arcs are converted into curves as well.
+    let events = [
+        PathEvent::MoveTo(point(2.0, 0.0)),
+        PathEvent::Arc(point(0.0, 0.0), vector(2.0, 1.0), Radians::new(1.0), Radians::new(0.0)),
+    ];
+    let transform = Transform2D::create_rotation(Radians::new(0.5));
+    for evt in PathIter::new(events.iter().cloned()).transformed(&transform) {
+        if let PathEvent::Arc(..) = evt {
+            panic!();
+        }
+    }
+}
+
+#[test]
+fn test_with_ids() {
+    let events = [
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(1.0, 0.0)),
+        PathEvent::QuadraticTo(point(2.0, 0.0), point(2.0, 1.0)),
+        PathEvent::Close,
+        PathEvent::LineTo(point(0.0, 5.0)),
+        PathEvent::MoveTo(point(10.0, 0.0)),
+        PathEvent::LineTo(point(11.0, 0.0)),
+    ];
+
+    let ids: Vec<(u32, u32, u32)> = PathIter::new(events.iter().cloned())
+        .with_ids()
+        .map(|evt| (evt.from.0, evt.to.0, evt.sub_path.0))
+        .collect();
+
+    assert_eq!(
+        ids,
+        vec![
+            (0, 0, 0),
+            (0, 1, 0),
+            (1, 2, 0),
+            (2, 0, 0),
+            // Continues the closed sub-path from its first endpoint.
+            (0, 3, 0),
+            (4, 4, 1),
+            (4, 5, 1),
+        ]
+    );
+}
@@ -38,14 +38,76 @@
 
 pub extern crate lyon_geom as geom;
 
+#[cfg(feature = "serialization")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "serialization")]
+pub extern crate serde;
+#[cfg(all(test, feature = "serialization"))]
+extern crate serde_json;
+
 mod events;
 mod path_state;
+mod path_segment;
 pub mod default;
 pub mod iterator;
 pub mod builder;
 pub mod walk;
+pub mod boolean;
+pub mod hit_test;
+pub mod bounding_rect;
+pub mod measure;
+pub mod attributes;
+pub mod morph;
+pub mod binary;
+pub mod cleanup;
+pub mod orientation;
+pub mod spatial_index;
+
+#[cfg(test)]
+mod test_utils;
 
 pub use events::*;
 pub use path_state::*;
+pub use path_segment::*;
 pub use geom::ArcFlags;
 pub use geom::math as math;
+
+/// The fill rule defines how to determine what is inside and what is outside of the shape.
+///
+/// See the SVG specification.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum FillRule {
+    EvenOdd,
+    NonZero,
+}
+
+impl FillRule {
+    /// Returns whether a point with the provided winding number is inside of the shape.
+    #[inline]
+    pub fn is_in(&self, winding_number: i32) -> bool {
+        match *self {
+            FillRule::EvenOdd => winding_number % 2 != 0,
+            FillRule::NonZero => winding_number != 0,
+        }
+    }
+
+    /// Returns whether a point with the provided winding number is outside of the shape.
+    #[inline]
+    pub fn is_out(&self, winding_number: i32) -> bool {
+        !self.is_in(winding_number)
+    }
+}
+
+/// The direction in which a closed shape is traversed.
+///
+/// Shapes with a positive winding have a positive signed area, which means they are
+/// counter-clockwise when the y axis points upwards, and clockwise when it points
+/// downwards (the usual convention for screen coordinates).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum Winding {
+    Positive,
+    Negative,
+}
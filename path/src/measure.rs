@@ -0,0 +1,493 @@
+//! # Path measurement
+//!
+//! Measure the length of a path, sample positions at a given distance along it and extract
+//! portions of it.
+//!
+//! ## Overview
+//!
+//! Unlike the [walk module](../walk/index.html) which advances along flattened events,
+//! a `PathMeasure` keeps the original segments of the path and only uses a flattened
+//! approximation to map distances to positions on the curves. As a result the portions
+//! of the path extracted with `PathMeasure::segment` are made of the same kind of
+//! curves as the original path, which is useful for "trim path" animations.
+//!
+//! Distances are measured along the whole path, the sub-paths being placed one after the
+//! other. The implicit segment of closed sub-paths back to their first point is included.
+//!
+//! ## Examples
+//!
+//! ```
+//! # extern crate lyon_path;
+//! # fn main() {
+//! use lyon_path::default::Path;
+//! use lyon_path::math::point;
+//! use lyon_path::builder::*;
+//! use lyon_path::measure::PathMeasure;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.quadratic_bezier_to(point(20.0, 0.0), point(20.0, 10.0));
+//! let path = builder.build();
+//!
+//! let measure = PathMeasure::new(path.path_iter(), 0.01);
+//!
+//! let (position, tangent) = measure.sample_at(5.0).unwrap();
+//! assert_eq!(position, point(5.0, 0.0));
+//!
+//! // Extract the second half of the path.
+//! let mut trimmed = Path::builder();
+//! measure.segment(measure.length() * 0.5, measure.length(), &mut trimmed);
+//! let trimmed = trimmed.build();
+//! # }
+//! ```
+
+use math::{Point, Vector};
+use geom::LineSegment;
+use builder::PathBuilder;
+use iterator::PathIterator;
+use {PathEvent, PathSegment};
+
+use std::cmp::Ordering;
+
+/// Computes distances along a path.
+///
+/// See the [measure module](index.html).
+pub struct PathMeasure {
+    events: Vec<MeasuredEvent>,
+    samples: Vec<Sample>,
+    sub_paths: Vec<SubPath>,
+}
+
+struct MeasuredEvent {
+    event: PathEvent,
+    from: Point,
+    // The segment drawn by the event, including the closing segment of Close events.
+    curve: Option<PathSegment>,
+    // Distances along the path at the start and at the end of the event.
+    start: f32,
+    end: f32,
+}
+
+// A point of the flattened approximation of a segment.
+struct Sample {
+    position: Point,
+    distance: f32,
+    event: usize,
+    t: f32,
+}
+
+struct SubPath {
+    first_event: usize,
+    end_event: usize,
+    start: f32,
+    length: f32,
+    closed: bool,
+}
+
+impl PathMeasure {
+    /// Measures a path, approximating its curves with the provided tolerance.
+    pub fn new<Iter: PathIterator>(path: Iter, tolerance: f32) -> Self {
+        let mut measure = PathMeasure {
+            events: Vec::new(),
+            samples: Vec::new(),
+            sub_paths: Vec::new(),
+        };
+
+        let mut current = Point::new(0.0, 0.0);
+        let mut first = current;
+        let mut distance = 0.0;
+        for evt in path {
+            let idx = measure.events.len();
+            // Events that follow a Close without a MoveTo continue the closed sub-path,
+            // as in `Path::subpaths`.
+            let starts_sub_path = match evt {
+                PathEvent::MoveTo(..) => true,
+                _ => measure.sub_paths.is_empty(),
+            };
+            if starts_sub_path {
+                measure.end_sub_path(distance);
+                measure.sub_paths.push(SubPath {
+                    first_event: idx,
+                    end_event: idx,
+                    start: distance,
+                    length: 0.0,
+                    closed: false,
+                });
+            }
+
+            let from = current;
+            let curve = match evt {
+                PathEvent::MoveTo(to) => {
+                    first = to;
+                    current = to;
+                    None
+                }
+                PathEvent::Close => {
+                    measure.sub_paths.last_mut().unwrap().closed = true;
+                    current = first;
+                    Some(PathSegment::Line(LineSegment { from: from, to: first }))
+                }
+                _ => {
+                    let curve = PathSegment::from_event(from, evt).unwrap();
+                    current = curve.to();
+                    Some(curve)
+                }
+            };
+
+            let start = distance;
+            if let Some(curve) = curve {
+                let mut prev = from;
+                let mut add_sample = |position: Point, t: f32| {
+                    distance += (position - prev).length();
+                    prev = position;
+                    measure.samples.push(Sample {
+                        position: position,
+                        distance: distance,
+                        event: idx,
+                        t: t,
+                    });
+                };
+                curve.flattened_with_t_for_each(tolerance, &mut add_sample);
+            }
+
+            measure.events.push(MeasuredEvent {
+                event: evt,
+                from: from,
+                curve: curve,
+                start: start,
+                end: distance,
+            });
+        }
+        measure.end_sub_path(distance);
+
+        measure
+    }
+
+    fn end_sub_path(&mut self, distance: f32) {
+        let end_event = self.events.len();
+        if let Some(sub_path) = self.sub_paths.last_mut() {
+            sub_path.end_event = end_event;
+            sub_path.length = distance - sub_path.start;
+        }
+    }
+
+    /// Returns the length of the whole path.
+    pub fn length(&self) -> f32 {
+        match self.samples.last() {
+            Some(sample) => sample.distance,
+            None => 0.0,
+        }
+    }
+
+    /// Returns the number of sub-paths.
+    pub fn sub_path_count(&self) -> usize { self.sub_paths.len() }
+
+    /// Returns the length of a sub-path.
+    pub fn sub_path_length(&self, index: usize) -> f32 { self.sub_paths[index].length }
+
+    /// Returns the distance along the path at which a sub-path starts.
+    pub fn sub_path_start(&self, index: usize) -> f32 { self.sub_paths[index].start }
+
+    /// Returns the position and the unit tangent at a given distance along the path.
+    ///
+    /// The distance is clamped to the length of the path. Returns `None` if the path
+    /// doesn't have any segment.
+    pub fn sample_at(&self, distance: f32) -> Option<(Point, Vector)> {
+        let (idx, t) = match self.locate(distance) {
+            Some(location) => location,
+            None => { return None; }
+        };
+
+        let sample = &self.samples[idx];
+        let evt = &self.events[sample.event];
+        let curve = evt.curve.unwrap();
+        let mut tangent = curve.derivative(t);
+        if tangent.square_length() < 0.000001 {
+            // Degenerate derivative, fall back to the direction of the approximation.
+            let prev = if idx > 0 && self.samples[idx - 1].event == sample.event {
+                self.samples[idx - 1].position
+            } else {
+                evt.from
+            };
+            tangent = sample.position - prev;
+        }
+        if tangent.square_length() > 0.0 {
+            tangent = tangent.normalize();
+        }
+
+        Some((curve.sample(t), tangent))
+    }
+
+    /// Writes the portion of the path between two distances into a builder.
+    ///
+    /// The extracted portion keeps the curves of the original path. A new sub-path is
+    /// started for each of the sub-paths that the portion overlaps, and closed sub-paths
+    /// that are fully included stay closed.
+    ///
+    /// This doesn't call `build` on the builder.
+    pub fn segment<Builder: PathBuilder>(&self, start: f32, end: f32, output: &mut Builder) {
+        let start = start.max(0.0);
+        let end = end.min(self.length());
+        if start >= end {
+            return;
+        }
+
+        for sub_path in &self.sub_paths {
+            let sub_path_end = sub_path.start + sub_path.length;
+            if sub_path.length == 0.0 || sub_path_end <= start || sub_path.start >= end {
+                continue;
+            }
+
+            let events = &self.events[sub_path.first_event..sub_path.end_event];
+            if sub_path.closed && start <= sub_path.start && end >= sub_path_end {
+                // The path can start without a MoveTo.
+                if let PathEvent::MoveTo(..) = events[0].event {} else {
+                    output.move_to(events[0].from);
+                }
+                for evt in events {
+                    output.path_event(evt.event);
+                }
+                continue;
+            }
+
+            let mut first = true;
+            for (i, evt) in events.iter().enumerate() {
+                if evt.end <= start || evt.start >= end || evt.start == evt.end {
+                    continue;
+                }
+                let curve = match evt.curve {
+                    Some(curve) => curve,
+                    None => { continue; }
+                };
+
+                let idx = sub_path.first_event + i;
+                let t0 = self.t_in_event(idx, start);
+                let t1 = self.t_in_event(idx, end);
+                let curve = curve.split_range(t0, t1);
+                if first {
+                    output.move_to(curve.from());
+                    first = false;
+                }
+                curve.add_to(output);
+            }
+        }
+    }
+
+    // Returns the index of the sample after the position at a given distance, and the
+    // curve parameter of that position on its event.
+    fn locate(&self, distance: f32) -> Option<(usize, f32)> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let distance = distance.max(0.0).min(self.length());
+        // Find the first sample at or after the distance.
+        let idx = match self.samples.binary_search_by(|sample| {
+            sample.distance.partial_cmp(&distance).unwrap_or(Ordering::Equal)
+        }) {
+            Ok(mut idx) => {
+                // Several samples can have the same distance.
+                while idx > 0 && self.samples[idx - 1].distance == distance {
+                    idx -= 1;
+                }
+                idx
+            }
+            Err(idx) => idx.min(self.samples.len() - 1),
+        };
+
+        let sample = &self.samples[idx];
+        let (t0, d0) = if idx > 0 && self.samples[idx - 1].event == sample.event {
+            (self.samples[idx - 1].t, self.samples[idx - 1].distance)
+        } else {
+            (0.0, self.events[sample.event].start)
+        };
+
+        let t = if sample.distance > d0 {
+            t0 + (sample.t - t0) * (distance - d0) / (sample.distance - d0)
+        } else {
+            sample.t
+        };
+
+        Some((idx, t))
+    }
+
+    fn t_in_event(&self, event: usize, distance: f32) -> f32 {
+        let evt = &self.events[event];
+        if distance <= evt.start {
+            return 0.0;
+        }
+        if distance >= evt.end {
+            return 1.0;
+        }
+
+        match self.locate(distance) {
+            Some((idx, t)) if self.samples[idx].event == event => t,
+            _ => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+use default::Path;
+#[cfg(test)]
+use builder::{FlatPathBuilder, PolygonBuilder};
+#[cfg(test)]
+use math::{point, vector, Radians};
+#[cfg(test)]
+use std::f32::consts::PI;
+
+#[cfg(test)]
+fn assert_approx_eq(a: Point, b: Point) {
+    if (a - b).length() > 0.001 {
+        panic!("{:?} != {:?}", a, b);
+    }
+}
+
+#[cfg(test)]
+fn square_and_line() -> Path {
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)]);
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(25.0, 0.0));
+    builder.build()
+}
+
+#[test]
+fn measure_lengths() {
+    let path = square_and_line();
+    let measure = PathMeasure::new(path.path_iter(), 0.01);
+
+    assert_eq!(measure.length(), 45.0);
+    assert_eq!(measure.sub_path_count(), 2);
+    assert_eq!(measure.sub_path_length(0), 40.0);
+    assert_eq!(measure.sub_path_length(1), 5.0);
+    assert_eq!(measure.sub_path_start(1), 40.0);
+
+    let empty = PathMeasure::new(Path::new().path_iter(), 0.01);
+    assert_eq!(empty.length(), 0.0);
+    assert_eq!(empty.sub_path_count(), 0);
+    assert!(empty.sample_at(0.0).is_none());
+}
+
+#[test]
+fn measure_sample_at() {
+    let path = square_and_line();
+    let measure = PathMeasure::new(path.path_iter(), 0.01);
+
+    assert_eq!(measure.sample_at(0.0), Some((point(0.0, 0.0), vector(1.0, 0.0))));
+    assert_eq!(measure.sample_at(15.0), Some((point(10.0, 5.0), vector(0.0, 1.0))));
+    // On the closing segment.
+    assert_eq!(measure.sample_at(35.0), Some((point(0.0, 5.0), vector(0.0, -1.0))));
+    assert_eq!(measure.sample_at(42.0), Some((point(22.0, 0.0), vector(1.0, 0.0))));
+    // The distance is clamped.
+    assert_eq!(measure.sample_at(100.0), Some((point(25.0, 0.0), vector(1.0, 0.0))));
+
+    // A quarter of circle.
+    let mut builder = Path::builder();
+    builder.move_to(point(10.0, 0.0));
+    builder.arc(point(0.0, 0.0), vector(10.0, 10.0), Radians::new(PI * 0.5), Radians::new(0.0));
+    let path = builder.build();
+    let measure = PathMeasure::new(path.path_iter(), 0.001);
+    assert!((measure.length() - 5.0 * PI).abs() < 0.01);
+    let (position, tangent) = measure.sample_at(measure.length() * 0.5).unwrap();
+    let diagonal = vector(1.0, 1.0).normalize();
+    assert_approx_eq(position, (diagonal * 10.0).to_point());
+    assert!((tangent - vector(-1.0, 1.0).normalize()).length() < 0.001);
+}
+
+#[test]
+fn measure_segment() {
+    let path = square_and_line();
+    let measure = PathMeasure::new(path.path_iter(), 0.01);
+
+    let mut builder = Path::builder();
+    measure.segment(5.0, 25.0, &mut builder);
+    let trimmed: Vec<PathEvent> = builder.build().iter().collect();
+    assert_eq!(
+        trimmed,
+        vec![
+            PathEvent::MoveTo(point(5.0, 0.0)),
+            PathEvent::LineTo(point(10.0, 0.0)),
+            PathEvent::LineTo(point(10.0, 10.0)),
+            PathEvent::LineTo(point(5.0, 10.0)),
+        ]
+    );
+
+    // Across the closing segment and into the next sub-path.
+    let mut builder = Path::builder();
+    measure.segment(35.0, 42.0, &mut builder);
+    let trimmed: Vec<PathEvent> = builder.build().iter().collect();
+    assert_eq!(
+        trimmed,
+        vec![
+            PathEvent::MoveTo(point(0.0, 5.0)),
+            PathEvent::LineTo(point(0.0, 0.0)),
+            PathEvent::MoveTo(point(20.0, 0.0)),
+            PathEvent::LineTo(point(22.0, 0.0)),
+        ]
+    );
+
+    // The whole path.
+    let mut builder = Path::builder();
+    measure.segment(0.0, measure.length(), &mut builder);
+    let whole: Vec<PathEvent> = builder.build().iter().collect();
+    assert_eq!(whole, path.iter().collect::<Vec<PathEvent>>());
+
+    // Curves are preserved.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0));
+    let path = builder.build();
+    let measure = PathMeasure::new(path.path_iter(), 0.001);
+    let mut builder = Path::builder();
+    measure.segment(measure.length() * 0.25, measure.length() * 0.75, &mut builder);
+    let trimmed = builder.build();
+    let events: Vec<PathEvent> = trimmed.iter().collect();
+    assert_eq!(events.len(), 2);
+    match (events[0], events[1]) {
+        (PathEvent::MoveTo(from), PathEvent::CubicTo(_, _, to)) => {
+            assert_approx_eq(from, measure.sample_at(measure.length() * 0.25).unwrap().0);
+            assert_approx_eq(to, measure.sample_at(measure.length() * 0.75).unwrap().0);
+        }
+        _ => panic!("{:?}", events),
+    }
+    let trimmed_length = PathMeasure::new(trimmed.path_iter(), 0.001).length();
+    assert!((trimmed_length - measure.length() * 0.5).abs() < 0.01);
+}
+
+#[test]
+fn measure_nan_coordinates() {
+    use iterator::PathIter;
+    use std::f32;
+
+    let events = [
+        PathEvent::MoveTo(point(0.0, 0.0)),
+        PathEvent::LineTo(point(10.0, 0.0)),
+        PathEvent::LineTo(point(f32::NAN, 5.0)),
+        PathEvent::LineTo(point(10.0, 10.0)),
+    ];
+    let measure = PathMeasure::new(PathIter::new(events.iter().cloned()), 0.01);
+    for i in 0..10 {
+        measure.sample_at(i as f32 * 3.0);
+    }
+    let mut builder = Path::builder();
+    measure.segment(1.0, 5.0, &mut builder);
+}
+
+#[test]
+fn measure_events_after_close() {
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)]);
+    // Continues the closed sub-path from its first point.
+    builder.line_to(point(0.0, -5.0));
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(25.0, 0.0));
+    let path = builder.build();
+
+    let measure = PathMeasure::new(path.path_iter(), 0.01);
+    assert_eq!(measure.sub_path_count(), path.subpaths().count());
+    assert_eq!(measure.sub_path_length(0), 45.0);
+    assert_eq!(measure.sub_path_start(1), 45.0);
+    assert_eq!(measure.sample_at(42.0).map(|(p, _)| p), Some(point(0.0, -2.0)));
+}
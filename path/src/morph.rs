@@ -0,0 +1,399 @@
+//! # Path morphing
+//!
+//! Interpolate between paths that don't have the same structure.
+//!
+//! ## Overview
+//!
+//! `Path::lerp` interpolates the points of two paths that have exactly the same verbs.
+//! Arbitrary paths first need to be resampled into compatible paths, which is what
+//! `normalize` does:
+//!
+//! - Every segment is converted into cubic bézier curves.
+//! - Sub-paths are paired in order. When a path has more sub-paths than the other, its
+//!   extra sub-paths are paired with empty sub-paths placed at their center, so that they
+//!   grow out of (or shrink into) a point.
+//! - Paired sub-paths are split into the same number of curves by subdividing their
+//!   longest curves.
+//! - When both sub-paths of a pair are closed, the second one is made to go in the same
+//!   direction as the first one and its start point is moved to the endpoint that minimizes
+//!   the distance between corresponding endpoints.
+//!
+//! The resulting paths only contain `MoveTo`, `CubicTo` and `Close` events, in the same order.
+//! If only one of the sub-paths of a pair is closed, the closing segment is kept as a curve
+//! and neither of them is closed.
+//!
+//! ## Examples
+//!
+//! ```
+//! # extern crate lyon_path;
+//! # fn main() {
+//! use lyon_path::default::Path;
+//! use lyon_path::math::point;
+//! use lyon_path::builder::*;
+//! use lyon_path::morph::Morph;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.line_to(point(0.0, 10.0));
+//! builder.close();
+//! let square = builder.build();
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(5.0, 0.0));
+//! builder.line_to(point(10.0, 10.0));
+//! builder.line_to(point(0.0, 10.0));
+//! builder.close();
+//! let triangle = builder.build();
+//!
+//! let morph = Morph::new(square.as_slice(), triangle.as_slice(), 0.01);
+//!
+//! // A shape halfway between the square and the triangle.
+//! let halfway = morph.sample(0.5);
+//! # }
+//! ```
+
+use math::{Point, point};
+use geom::{QuadraticBezierSegment, CubicBezierSegment, Arc};
+use geom::utils::vector_angle;
+use builder::{FlatPathBuilder, PathBuilder};
+use default::{Path, PathSlice, Builder};
+use iterator::PathIter;
+use orientation;
+use PathEvent;
+
+/// Interpolates between two arbitrary paths.
+///
+/// See the [morph module](index.html).
+#[derive(Clone, Debug)]
+pub struct Morph {
+    from: Path,
+    to: Path,
+}
+
+impl Morph {
+    /// Creates the morph, resampling the two paths with `normalize`.
+    pub fn new(from: PathSlice, to: PathSlice, tolerance: f32) -> Self {
+        let (from, to) = normalize(from, to, tolerance);
+
+        Morph { from: from, to: to }
+    }
+
+    /// The normalized version of the first path.
+    pub fn from(&self) -> &Path { &self.from }
+
+    /// The normalized version of the second path.
+    pub fn to(&self) -> &Path { &self.to }
+
+    /// Returns the path at `t` between the first (`t = 0`) and the second (`t = 1`) path.
+    pub fn sample(&self, t: f32) -> Path {
+        self.from.lerp(&self.to, t).unwrap()
+    }
+}
+
+/// Resamples two paths into paths that have the same structure, so that they can be
+/// interpolated with `Path::lerp`.
+///
+/// The tolerance is used to approximate the length of the curves when deciding which
+/// ones to split.
+///
+/// See the [morph module](index.html).
+pub fn normalize(a: PathSlice, b: PathSlice, tolerance: f32) -> (Path, Path) {
+    let mut sub_paths_a = sub_paths(a);
+    let mut sub_paths_b = sub_paths(b);
+
+    while sub_paths_a.len() < sub_paths_b.len() {
+        let idx = sub_paths_a.len();
+        let empty = sub_paths_b[idx].empty_at_center();
+        sub_paths_a.push(empty);
+    }
+    while sub_paths_b.len() < sub_paths_a.len() {
+        let idx = sub_paths_b.len();
+        let empty = sub_paths_a[idx].empty_at_center();
+        sub_paths_b.push(empty);
+    }
+
+    let mut builder_a = Path::builder();
+    let mut builder_b = Path::builder();
+    for (mut sp_a, mut sp_b) in sub_paths_a.into_iter().zip(sub_paths_b) {
+        let n = ::std::cmp::max(sp_a.curves.len(), sp_b.curves.len());
+        sp_a.subdivide(n, tolerance);
+        sp_b.subdivide(n, tolerance);
+
+        if sp_a.closed && sp_b.closed {
+            if (sp_a.signed_area(tolerance) < 0.0) != (sp_b.signed_area(tolerance) < 0.0) {
+                sp_b.reverse();
+            }
+            sp_b.align_start_with(&sp_a);
+        } else {
+            sp_a.closed = false;
+            sp_b.closed = false;
+        }
+
+        sp_a.build(&mut builder_a);
+        sp_b.build(&mut builder_b);
+    }
+
+    return (builder_a.build(), builder_b.build());
+}
+
+struct SubPath {
+    start: Point,
+    curves: Vec<CubicBezierSegment>,
+    closed: bool,
+}
+
+impl SubPath {
+    fn new(start: Point) -> Self {
+        SubPath { start: start, curves: Vec::new(), closed: false }
+    }
+
+    // An empty sub-path located at the center of this one's endpoints.
+    fn empty_at_center(&self) -> SubPath {
+        let mut center = self.start.to_vector();
+        for curve in &self.curves {
+            center += curve.to.to_vector();
+        }
+        center /= (self.curves.len() + 1) as f32;
+
+        SubPath { start: center.to_point(), curves: Vec::new(), closed: self.closed }
+    }
+
+    // Splits the longest curves until there are n of them.
+    fn subdivide(&mut self, n: usize, tolerance: f32) {
+        if self.curves.is_empty() {
+            let p = self.start;
+            let curve = CubicBezierSegment { from: p, ctrl1: p, ctrl2: p, to: p };
+            self.curves.resize(n, curve);
+            return;
+        }
+
+        let mut lengths: Vec<f32> = self.curves.iter().map(|curve| {
+            curve.approximate_length(tolerance)
+        }).collect();
+
+        while self.curves.len() < n {
+            let mut longest = 0;
+            for i in 1..lengths.len() {
+                if lengths[i] > lengths[longest] {
+                    longest = i;
+                }
+            }
+
+            let (first, second) = self.curves[longest].split(0.5);
+            self.curves[longest] = first;
+            self.curves.insert(longest + 1, second);
+            lengths[longest] = first.approximate_length(tolerance);
+            lengths.insert(longest + 1, second.approximate_length(tolerance));
+        }
+    }
+
+    fn signed_area(&self, tolerance: f32) -> f32 {
+        let mut events = Vec::with_capacity(self.curves.len() + 1);
+        events.push(PathEvent::MoveTo(self.start));
+        for curve in &self.curves {
+            events.push(PathEvent::CubicTo(curve.ctrl1, curve.ctrl2, curve.to));
+        }
+
+        orientation::signed_area(PathIter::new(events.into_iter()), tolerance)
+    }
+
+    fn reverse(&mut self) {
+        self.curves.reverse();
+        for curve in &mut self.curves {
+            *curve = curve.flip();
+        }
+        if let Some(first) = self.curves.first() {
+            self.start = first.from;
+        }
+    }
+
+    // Rotates the curves of a closed sub-path so that its endpoints are as close as
+    // possible to the ones of the other sub-path.
+    fn align_start_with(&mut self, other: &SubPath) {
+        let n = self.curves.len();
+        debug_assert_eq!(n, other.curves.len());
+        if n == 0 {
+            return;
+        }
+
+        let mut best = 0;
+        let mut best_distance = ::std::f32::MAX;
+        for offset in 0..n {
+            let mut distance = 0.0;
+            for i in 0..n {
+                let a = other.curves[i].from;
+                let b = self.curves[(i + offset) % n].from;
+                distance += (a - b).square_length();
+            }
+            if distance < best_distance {
+                best = offset;
+                best_distance = distance;
+            }
+        }
+
+        self.curves.rotate_left(best);
+        self.start = self.curves[0].from;
+    }
+
+    fn build(&self, builder: &mut Builder) {
+        builder.move_to(self.start);
+        for curve in &self.curves {
+            builder.cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to);
+        }
+        if self.closed {
+            builder.close();
+        }
+    }
+}
+
+fn line_curve(from: Point, to: Point) -> CubicBezierSegment {
+    CubicBezierSegment {
+        from: from,
+        ctrl1: from.lerp(to, 1.0 / 3.0),
+        ctrl2: from.lerp(to, 2.0 / 3.0),
+        to: to,
+    }
+}
+
+// Splits the path into sub-paths made of cubic bézier curves.
+fn sub_paths(path: PathSlice) -> Vec<SubPath> {
+    let mut sub_paths = Vec::new();
+    let mut current_sub_path: Option<SubPath> = None;
+    let mut first = point(0.0, 0.0);
+    let mut current = first;
+
+    for evt in path.path_iter() {
+        if let PathEvent::MoveTo(to) = evt {
+            if let Some(sp) = current_sub_path.take() {
+                sub_paths.push(sp);
+            }
+            current_sub_path = Some(SubPath::new(to));
+            first = to;
+            current = to;
+            continue;
+        }
+
+        let sp = current_sub_path.get_or_insert_with(|| SubPath::new(first));
+        match evt {
+            PathEvent::LineTo(to) => {
+                sp.curves.push(line_curve(current, to));
+                current = to;
+            }
+            PathEvent::QuadraticTo(ctrl, to) => {
+                sp.curves.push(QuadraticBezierSegment { from: current, ctrl: ctrl, to: to }.to_cubic());
+                current = to;
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                sp.curves.push(CubicBezierSegment { from: current, ctrl1: ctrl1, ctrl2: ctrl2, to: to });
+                current = to;
+            }
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                let arc = Arc {
+                    center, radii,
+                    start_angle: vector_angle(current - center),
+                    sweep_angle,
+                    x_rotation,
+                };
+                let mut from = current;
+                arc.to_quadratic_beziers(&mut |ctrl, to| {
+                    sp.curves.push(QuadraticBezierSegment { from: from, ctrl: ctrl, to: to }.to_cubic());
+                    from = to;
+                });
+                current = from;
+            }
+            PathEvent::Close => {
+                if current != first {
+                    sp.curves.push(line_curve(current, first));
+                }
+                sp.closed = true;
+                current = first;
+            }
+            PathEvent::MoveTo(..) => { unreachable!(); }
+        }
+
+        if sp.closed {
+            if let Some(sp) = current_sub_path.take() {
+                sub_paths.push(sp);
+            }
+        }
+    }
+
+    if let Some(sp) = current_sub_path.take() {
+        sub_paths.push(sp);
+    }
+
+    return sub_paths;
+}
+
+#[cfg(test)]
+use default::Verb;
+#[cfg(test)]
+use test_utils::square;
+
+#[test]
+fn normalize_compatible_structure() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(10.0, 0.0), point(10.0, 10.0));
+    builder.line_to(point(0.0, 5.0));
+    builder.close();
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    let a = builder.build();
+    let b = square(0.0, 0.0, 10.0);
+
+    let (na, nb) = normalize(a.as_slice(), b.as_slice(), 0.01);
+    assert_eq!(na.verbs(), nb.verbs());
+    for verb in na.verbs() {
+        assert!(*verb == Verb::MoveTo || *verb == Verb::CubicTo || *verb == Verb::Close);
+    }
+
+    // The extra sub-path of a grows out of a point at its center.
+    let last_b = &nb.points()[nb.points().len() - 4..];
+    for p in last_b {
+        assert_eq!(*p, point(25.0, 0.0));
+    }
+
+    // The endpoints are preserved.
+    assert!(na.lerp(&nb, 0.0).is_some());
+    assert_eq!(Morph::new(a.as_slice(), b.as_slice(), 0.01).sample(1.0).points(), nb.points());
+}
+
+#[test]
+fn normalize_aligns_start_points() {
+    let a = square(0.0, 0.0, 10.0);
+
+    // The same square, starting at another corner and going in the other direction.
+    let mut builder = Path::builder();
+    builder.move_to(point(10.0, 10.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(0.0, 0.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let b = builder.build();
+
+    let (na, nb) = normalize(a.as_slice(), b.as_slice(), 0.01);
+    assert_eq!(na.verbs(), nb.verbs());
+    for (pa, pb) in na.points().iter().zip(nb.points().iter()) {
+        assert!((*pa - *pb).length() < 0.0001, "{:?} {:?}", pa, pb);
+    }
+}
+
+#[test]
+fn normalize_subdivides() {
+    let a = square(0.0, 0.0, 10.0);
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.close();
+    let b = builder.build();
+
+    let (na, nb) = normalize(a.as_slice(), b.as_slice(), 0.01);
+    assert_eq!(na.verbs(), nb.verbs());
+    assert_eq!(nb.verbs().len(), 6);
+
+    let halfway = Morph::new(a.as_slice(), b.as_slice(), 0.01).sample(0.5);
+    assert_eq!(halfway.verbs(), na.verbs());
+}
@@ -0,0 +1,244 @@
+//! # Orientation
+//!
+//! Detect the winding of sub-paths and make it follow a convention.
+//!
+//! ## Overview
+//!
+//! The winding of a closed shape is given by the sign of its area: shapes with a positive
+//! area have a positive winding (see [Winding](../enum.Winding.html)). Sub-paths that are
+//! not explicitly closed are treated as if they were.
+//!
+//! Some formats and algorithms expect the sub-paths of a shape to follow a convention,
+//! for example GeoJSON requires outer contours to be counter-clockwise and holes to be
+//! clockwise (with the y axis pointing upwards). `normalize_orientation` reverses the
+//! sub-paths that don't follow such a convention, depending on how many other sub-paths
+//! contain them.
+//!
+//! ## Examples
+//!
+//! ```
+//! # extern crate lyon_path;
+//! # fn main() {
+//! use lyon_path::default::Path;
+//! use lyon_path::math::rect;
+//! use lyon_path::builder::*;
+//! use lyon_path::orientation::normalize_orientation;
+//! use lyon_path::Winding;
+//!
+//! let mut builder = Path::builder();
+//! builder.add_rectangle(&rect(0.0, 0.0, 10.0, 10.0), Winding::Negative);
+//! builder.add_rectangle(&rect(2.0, 2.0, 6.0, 6.0), Winding::Negative);
+//! let path = builder.build();
+//!
+//! let mut output = Path::builder();
+//! normalize_orientation(path.as_slice(), Winding::Positive, 0.01, &mut output);
+//! let path = output.build();
+//!
+//! let windings: Vec<Option<Winding>> = path.subpaths().map(|sp| sp.winding(0.01)).collect();
+//! assert_eq!(windings, vec![Some(Winding::Positive), Some(Winding::Negative)]);
+//! # }
+//! ```
+
+use math::{Point, Rect};
+use builder::PathBuilder;
+use iterator::PathIterator;
+use default::{PathSlice, SubPath, reverse_path};
+use hit_test::winding_number;
+use events::FlattenedEvent;
+use Winding;
+
+/// Computes the signed area of a path, approximating curves within `tolerance`.
+///
+/// The areas of the sub-paths are added up, each of them being treated as closed.
+pub fn signed_area<Iter: PathIterator>(path: Iter, tolerance: f32) -> f32 {
+    let mut area = 0.0;
+    let mut first = Point::new(0.0, 0.0);
+    let mut current = first;
+    for evt in path.flattened(tolerance) {
+        match evt {
+            FlattenedEvent::MoveTo(to) => {
+                first = to;
+                current = to;
+            }
+            FlattenedEvent::LineTo(to) => {
+                // Relative to the first point of the sub-path to preserve precision
+                // far from the origin. The closing segment doesn't contribute.
+                area += (current - first).cross(to - first);
+                current = to;
+            }
+            FlattenedEvent::Close => {
+                current = first;
+            }
+        }
+    }
+
+    area * 0.5
+}
+
+/// Returns the winding of a path from the sign of its area, or `None` if its area is zero.
+pub fn winding<Iter: PathIterator>(path: Iter, tolerance: f32) -> Option<Winding> {
+    winding_from_area(signed_area(path, tolerance))
+}
+
+/// Forwards the sub-paths of a path to a builder, reversing the ones that don't follow
+/// an orientation convention.
+///
+/// Sub-paths that are contained in an even number of other sub-paths (the outer contours)
+/// get the `outer` winding, the other ones (the holes) get the opposite winding. Whether a
+/// sub-path is contained in another one is determined using its first point. Sub-paths with
+/// a zero area are left unchanged.
+pub fn normalize_orientation<Output: PathBuilder>(
+    path: PathSlice,
+    outer: Winding,
+    tolerance: f32,
+    output: &mut Output,
+) {
+    let sub_paths: Vec<SubPath> = path.subpaths().collect();
+    let windings: Vec<Option<Winding>> = sub_paths.iter().map(|sp| sp.winding(tolerance)).collect();
+    // The control points of a sub-path contain it, so a point outside of this rectangle
+    // can't be inside of the sub-path and the (more expensive) winding test can be skipped.
+    let rects: Vec<Rect> = sub_paths.iter().map(|sp| sp.fast_bounding_rect()).collect();
+
+    for (i, sp) in sub_paths.iter().enumerate() {
+        let reverse = match (windings[i], sp.path().points().first()) {
+            (Some(winding), Some(&position)) => {
+                let mut depth = 0;
+                for (j, other) in sub_paths.iter().enumerate() {
+                    if j != i
+                        && windings[j].is_some()
+                        && rect_contains(&rects[j], position)
+                        && winding_number(other.path().path_iter(), position, tolerance) != 0 {
+                        depth += 1;
+                    }
+                }
+
+                let expected = if depth % 2 == 0 { outer } else { opposite(outer) };
+
+                winding != expected
+            }
+            _ => false,
+        };
+
+        if reverse {
+            reverse_path(sp.path(), output);
+        } else {
+            for evt in sp.path().iter() {
+                output.path_event(evt);
+            }
+        }
+    }
+}
+
+// Unlike `Rect::contains`, includes the right and bottom edges.
+fn rect_contains(rect: &Rect, p: Point) -> bool {
+    p.x >= rect.min_x() && p.x <= rect.max_x() && p.y >= rect.min_y() && p.y <= rect.max_y()
+}
+
+pub(crate) fn winding_from_area(area: f32) -> Option<Winding> {
+    if area > 0.0 {
+        Some(Winding::Positive)
+    } else if area < 0.0 {
+        Some(Winding::Negative)
+    } else {
+        None
+    }
+}
+
+fn opposite(winding: Winding) -> Winding {
+    match winding {
+        Winding::Positive => Winding::Negative,
+        Winding::Negative => Winding::Positive,
+    }
+}
+
+#[cfg(test)]
+use math::{point, rect};
+#[cfg(test)]
+use default::Path;
+#[cfg(test)]
+use builder::{FlatPathBuilder, ShapeBuilder};
+
+#[test]
+fn test_signed_area() {
+    let mut builder = Path::builder();
+    builder.add_rectangle(&rect(100.0, 100.0, 2.0, 3.0), Winding::Positive);
+    let path = builder.build();
+    assert_eq!(signed_area(path.path_iter(), 0.01), 6.0);
+    assert_eq!(winding(path.path_iter(), 0.01), Some(Winding::Positive));
+
+    // Sub-paths are implicitly closed.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(0.0, 2.0));
+    builder.line_to(point(2.0, 2.0));
+    let path = builder.build();
+    assert_eq!(signed_area(path.path_iter(), 0.01), -2.0);
+    assert_eq!(path.subpath(0).unwrap().winding(0.01), Some(Winding::Negative));
+
+    let mut builder = Path::builder();
+    builder.add_circle(point(0.0, 0.0), 1.0, Winding::Negative);
+    builder.move_to(point(5.0, 5.0));
+    builder.line_to(point(6.0, 5.0));
+    let path = builder.build();
+    let area = path.subpath(0).unwrap().signed_area(0.001);
+    assert!((area + ::std::f32::consts::PI).abs() < 0.01);
+    assert_eq!(path.subpath(1).unwrap().winding(0.01), None);
+}
+
+#[test]
+fn test_normalize_orientation() {
+    let mut builder = Path::builder();
+    // An outer contour with a hole containing an island.
+    builder.add_rectangle(&rect(0.0, 0.0, 10.0, 10.0), Winding::Negative);
+    builder.add_rectangle(&rect(1.0, 1.0, 8.0, 8.0), Winding::Positive);
+    builder.add_circle(point(5.0, 5.0), 2.0, Winding::Negative);
+    // A separate contour.
+    builder.add_rectangle(&rect(20.0, 0.0, 10.0, 10.0), Winding::Positive);
+    // A degenerate one inside of the outer contour.
+    builder.move_to(point(3.0, 3.0));
+    builder.line_to(point(4.0, 4.0));
+    let path = builder.build();
+
+    for &outer in &[Winding::Positive, Winding::Negative] {
+        let mut output = Path::builder();
+        normalize_orientation(path.as_slice(), outer, 0.01, &mut output);
+        let result = output.build();
+
+        let windings: Vec<Option<Winding>> = result.subpaths().map(|sp| sp.winding(0.01)).collect();
+        let inner = opposite(outer);
+        assert_eq!(windings, vec![Some(outer), Some(inner), Some(outer), Some(outer), None]);
+
+        assert_eq!(result.verbs(), path.verbs());
+        for (a, b) in result.subpaths().zip(path.subpaths()) {
+            assert_eq!(a.bounding_rect(), b.bounding_rect());
+        }
+    }
+}
+
+#[test]
+fn test_normalize_orientation_many_sub_paths() {
+    let mut builder = Path::builder();
+    // A large contour containing a grid of squares that each contain a hole.
+    builder.add_rectangle(&rect(0.0, 0.0, 100.0, 100.0), Winding::Positive);
+    for i in 0..5 {
+        for j in 0..5 {
+            let x = 5.0 + i as f32 * 20.0;
+            let y = 5.0 + j as f32 * 20.0;
+            builder.add_rectangle(&rect(x, y, 10.0, 10.0), Winding::Negative);
+            builder.add_rectangle(&rect(x + 2.0, y + 2.0, 6.0, 6.0), Winding::Negative);
+        }
+    }
+    let path = builder.build();
+
+    let mut output = Path::builder();
+    normalize_orientation(path.as_slice(), Winding::Positive, 0.01, &mut output);
+    let result = output.build();
+
+    let windings: Vec<Option<Winding>> = result.subpaths().map(|sp| sp.winding(0.01)).collect();
+    assert_eq!(windings.len(), 51);
+    assert_eq!(windings[0], Some(Winding::Positive));
+    for i in 0..25 {
+        assert_eq!(windings[1 + i * 2], Some(Winding::Negative));
+        assert_eq!(windings[2 + i * 2], Some(Winding::Positive));
+    }
+}
@@ -0,0 +1,188 @@
+use math::{Point, Vector, Rect};
+use geom::{LineSegment, QuadraticBezierSegment, CubicBezierSegment, Arc};
+use geom::traits::Segment;
+use geom::utils::vector_angle;
+use builder::PathBuilder;
+use events::PathEvent;
+
+/// A segment of a path: a line segment, a bézier curve or an arc, with its start point.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum PathSegment {
+    Line(LineSegment),
+    Quadratic(QuadraticBezierSegment),
+    Cubic(CubicBezierSegment),
+    Arc(Arc),
+}
+
+impl PathSegment {
+    /// Creates the segment drawn by an event, given the position before the event.
+    ///
+    /// Returns `None` for `MoveTo` and `Close` events.
+    pub fn from_event(from: Point, event: PathEvent) -> Option<PathSegment> {
+        match event {
+            PathEvent::MoveTo(..) | PathEvent::Close => None,
+            PathEvent::LineTo(to) => Some(PathSegment::Line(LineSegment { from: from, to: to })),
+            PathEvent::QuadraticTo(ctrl, to) => Some(PathSegment::Quadratic(
+                QuadraticBezierSegment { from: from, ctrl: ctrl, to: to }
+            )),
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => Some(PathSegment::Cubic(
+                CubicBezierSegment { from: from, ctrl1: ctrl1, ctrl2: ctrl2, to: to }
+            )),
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => Some(PathSegment::Arc(Arc {
+                center: center,
+                radii: radii,
+                start_angle: vector_angle(from - center),
+                sweep_angle: sweep_angle,
+                x_rotation: x_rotation,
+            })),
+        }
+    }
+
+    /// Start of the segment.
+    pub fn from(&self) -> Point {
+        match *self {
+            PathSegment::Line(ref segment) => segment.from,
+            PathSegment::Quadratic(ref segment) => segment.from,
+            PathSegment::Cubic(ref segment) => segment.from,
+            PathSegment::Arc(ref segment) => segment.from(),
+        }
+    }
+
+    /// End of the segment.
+    pub fn to(&self) -> Point {
+        match *self {
+            PathSegment::Line(ref segment) => segment.to,
+            PathSegment::Quadratic(ref segment) => segment.to,
+            PathSegment::Cubic(ref segment) => segment.to,
+            PathSegment::Arc(ref segment) => segment.to(),
+        }
+    }
+
+    /// Sample the segment at t (expecting t between 0 and 1).
+    pub fn sample(&self, t: f32) -> Point {
+        match *self {
+            PathSegment::Line(ref segment) => segment.sample(t),
+            PathSegment::Quadratic(ref segment) => segment.sample(t),
+            PathSegment::Cubic(ref segment) => segment.sample(t),
+            PathSegment::Arc(ref segment) => segment.sample(t),
+        }
+    }
+
+    /// Sample the segment's derivative at t (expecting t between 0 and 1).
+    pub fn derivative(&self, t: f32) -> Vector {
+        match *self {
+            PathSegment::Line(ref segment) => segment.to_vector(),
+            PathSegment::Quadratic(ref segment) => segment.derivative(t),
+            PathSegment::Cubic(ref segment) => segment.derivative(t),
+            PathSegment::Arc(ref segment) => segment.sample_tangent(t) * segment.sweep_angle.get(),
+        }
+    }
+
+    /// Returns the smallest rectangle that contains the segment.
+    pub fn bounding_rect(&self) -> Rect {
+        match *self {
+            PathSegment::Line(ref segment) => segment.bounding_rect(),
+            PathSegment::Quadratic(ref segment) => segment.bounding_rect(),
+            PathSegment::Cubic(ref segment) => segment.bounding_rect(),
+            PathSegment::Arc(ref segment) => segment.bounding_rect(),
+        }
+    }
+
+    /// Returns the part of the segment between t0 and t1.
+    ///
+    /// If t0 is greater than t1, the returned segment goes in the opposite direction.
+    pub fn split_range(&self, t0: f32, t1: f32) -> PathSegment {
+        match *self {
+            PathSegment::Line(ref segment) => PathSegment::Line(split_range(segment, t0, t1)),
+            PathSegment::Quadratic(ref segment) => PathSegment::Quadratic(split_range(segment, t0, t1)),
+            PathSegment::Cubic(ref segment) => PathSegment::Cubic(split_range(segment, t0, t1)),
+            PathSegment::Arc(ref segment) => PathSegment::Arc(split_range(segment, t0, t1)),
+        }
+    }
+
+    /// Adds the segment to a builder which current position is the start of the segment.
+    pub fn add_to<Builder: PathBuilder>(&self, builder: &mut Builder) {
+        match *self {
+            PathSegment::Line(ref segment) => {
+                builder.line_to(segment.to);
+            }
+            PathSegment::Quadratic(ref segment) => {
+                builder.quadratic_bezier_to(segment.ctrl, segment.to);
+            }
+            PathSegment::Cubic(ref segment) => {
+                builder.cubic_bezier_to(segment.ctrl1, segment.ctrl2, segment.to);
+            }
+            PathSegment::Arc(ref segment) => {
+                builder.arc(segment.center, segment.radii, segment.sweep_angle, segment.x_rotation);
+            }
+        }
+    }
+
+    /// Iterates through the points of an approximation of the segment with line segments
+    /// and their parameter on the segment, starting after the first point.
+    pub fn flattened_with_t_for_each<F: FnMut(Point, f32)>(&self, tolerance: f32, callback: &mut F) {
+        match *self {
+            PathSegment::Line(ref segment) => {
+                callback(segment.to, 1.0);
+            }
+            PathSegment::Quadratic(ref segment) => {
+                for (p, t) in segment.flattened_with_t(tolerance) { callback(p, t); }
+            }
+            PathSegment::Cubic(ref segment) => {
+                for (p, t) in segment.flattened_with_t(tolerance) { callback(p, t); }
+            }
+            PathSegment::Arc(ref segment) => {
+                for (p, t) in segment.flattened_with_t(tolerance) { callback(p, t); }
+            }
+        }
+    }
+}
+
+fn split_range<S: Segment>(segment: &S, t0: f32, t1: f32) -> S {
+    if t0 > t1 {
+        return split_range(segment, t1, t0).flip();
+    }
+
+    let after = if t0 > 0.0 { segment.after_split(t0) } else { *segment };
+    if t1 < 1.0 {
+        after.before_split((t1 - t0) / (1.0 - t0))
+    } else {
+        after
+    }
+}
+
+#[cfg(test)]
+use math::{point, vector, Radians};
+
+#[test]
+fn path_segment_split_range() {
+    let segments = [
+        PathSegment::Line(LineSegment { from: point(0.0, 0.0), to: point(4.0, 0.0) }),
+        PathSegment::Cubic(CubicBezierSegment {
+            from: point(0.0, 0.0),
+            ctrl1: point(1.0, 2.0),
+            ctrl2: point(3.0, 2.0),
+            to: point(4.0, 0.0),
+        }),
+        PathSegment::from_event(
+            point(1.0, 0.0),
+            PathEvent::Arc(point(0.0, 0.0), vector(1.0, 1.0), Radians::new(-2.0), Radians::new(0.0)),
+        ).unwrap(),
+    ];
+
+    for segment in &segments {
+        let sub = segment.split_range(0.25, 0.75);
+        assert!((sub.from() - segment.sample(0.25)).length() < 0.0001);
+        assert!((sub.to() - segment.sample(0.75)).length() < 0.0001);
+
+        let reversed = segment.split_range(0.75, 0.25);
+        assert!((reversed.from() - segment.sample(0.75)).length() < 0.0001);
+        assert!((reversed.to() - segment.sample(0.25)).length() < 0.0001);
+
+        // The derivative points towards the next samples.
+        let d = segment.derivative(0.5);
+        let step = segment.sample(0.51) - segment.sample(0.5);
+        assert!((d * 0.01 - step).length() < 0.001);
+    }
+}
@@ -2,7 +2,7 @@
 use math::{Point, Vector, point, vector};
 use geom::utils::vector_angle;
 use geom::{Arc, SvgArc};
-use events::{PathEvent, SvgEvent, FlattenedEvent};
+use events::{PathEvent, SvgEvent, FlattenedEvent, IdEvent, EndpointId, SubPathId};
 
 /// Represents the current state of a path while it is being built.
 pub struct PathState {
@@ -256,3 +256,71 @@ impl PathState {
         }
     }
 }
+
+/// Assigns endpoint and sub-path identifiers to path events while a path is being built
+/// or iterated over.
+///
+/// See `EndpointId` and `SubPathId` for how the identifiers are numbered.
+#[derive(Clone, Debug)]
+pub struct IdState {
+    next_endpoint: u32,
+    next_sub_path: u32,
+    first: EndpointId,
+    current: EndpointId,
+}
+
+impl IdState {
+    pub fn new() -> Self {
+        IdState {
+            next_endpoint: 0,
+            next_sub_path: 0,
+            first: EndpointId::INVALID,
+            current: EndpointId::INVALID,
+        }
+    }
+
+    /// The endpoint at the current position.
+    pub fn current_endpoint(&self) -> EndpointId { self.current }
+
+    /// The first endpoint of the current sub-path.
+    pub fn first_endpoint(&self) -> EndpointId { self.first }
+
+    /// Returns the identifiers of the next event in the sequence.
+    pub fn path_event(&mut self, event: PathEvent) -> IdEvent {
+        let starts_sub_path = match event {
+            PathEvent::MoveTo(..) => true,
+            PathEvent::Close => false,
+            // A path that doesn't start with MoveTo.
+            _ => self.next_sub_path == 0,
+        };
+        if starts_sub_path {
+            self.next_sub_path += 1;
+        }
+        let sub_path = SubPathId(self.next_sub_path.wrapping_sub(1));
+
+        if let PathEvent::Close = event {
+            let from = self.current;
+            self.current = self.first;
+            return IdEvent { event: event, from: from, to: self.first, sub_path: sub_path };
+        }
+
+        let to = EndpointId(self.next_endpoint);
+        self.next_endpoint += 1;
+        let from = match event {
+            PathEvent::MoveTo(..) => to,
+            // Segments at the very beginning of the path have no previous endpoint.
+            _ if !self.current.is_valid() => to,
+            _ => self.current,
+        };
+        if starts_sub_path {
+            self.first = from;
+        }
+        self.current = to;
+
+        return IdEvent { event: event, from: from, to: to, sub_path: sub_path };
+    }
+}
+
+impl Default for IdState {
+    fn default() -> Self { IdState::new() }
+}
@@ -0,0 +1,672 @@
+//! # Spatial index
+//!
+//! Find the segments of a path near a position without going through all of them.
+//!
+//! ## Overview
+//!
+//! A `SegmentIndex` stores the segments of a path in a bounding volume hierarchy (`Bvh`)
+//! built from their bounding rectangles. It speeds up picking queries on paths with many
+//! segments:
+//!
+//! - the segments which bounding rectangle overlaps a rectangle,
+//! - the nearest segment to a point,
+//! - the intersections between a ray and the path,
+//! - the winding number of the path around a point, to test whether it is inside
+//!   (see the [hit_test module](../hit_test/index.html)).
+//!
+//! Each segment keeps the index of the event it comes from. Closing segments are indexed
+//! as well, with the index of the `Close` event.
+//!
+//! The `Bvh` itself only deals with rectangles and indices, and can be used to index other
+//! kinds of primitives.
+//!
+//! ## Examples
+//!
+//! ```
+//! # extern crate lyon_path;
+//! # fn main() {
+//! use lyon_path::default::Path;
+//! use lyon_path::math::{point, vector};
+//! use lyon_path::builder::*;
+//! use lyon_path::spatial_index::SegmentIndex;
+//! use lyon_path::FillRule;
+//!
+//! let mut builder = Path::builder();
+//! builder.move_to(point(0.0, 0.0));
+//! builder.line_to(point(10.0, 0.0));
+//! builder.quadratic_bezier_to(point(10.0, 10.0), point(0.0, 10.0));
+//! builder.close();
+//! let path = builder.build();
+//!
+//! let index = SegmentIndex::new(path.path_iter(), 0.01);
+//!
+//! let nearest = index.nearest(point(5.0, -1.0)).unwrap();
+//! assert_eq!(nearest.segment.event_index, 1);
+//! assert_eq!(nearest.position, point(5.0, 0.0));
+//!
+//! let hit = index.first_ray_intersection(point(-5.0, 5.0), vector(1.0, 0.0)).unwrap();
+//! assert_eq!(hit.position, point(0.0, 5.0));
+//!
+//! assert!(index.contains_point(point(5.0, 5.0), FillRule::NonZero));
+//! # }
+//! ```
+
+use math::{Point, Vector, Rect, point};
+use geom::LineSegment;
+use iterator::PathIterator;
+use hit_test::{line_winding, segment_winding};
+use {PathEvent, PathSegment, FillRule};
+
+use std::cmp::Ordering;
+use std::f32;
+
+const MAX_ITEMS_PER_LEAF: usize = 4;
+
+// An axis-aligned box including its boundaries, so that horizontal and vertical
+// segments (which bounding rectangles have a zero area) are not considered empty.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Aabb {
+    min: Point,
+    max: Point,
+}
+
+impl Aabb {
+    fn from_rect(rect: &Rect) -> Self {
+        Aabb {
+            min: rect.origin,
+            max: point(rect.origin.x + rect.size.width, rect.origin.y + rect.size.height),
+        }
+    }
+
+    fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: point(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: point(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    fn center(&self) -> Point { self.min.lerp(self.max, 0.5) }
+
+    fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x
+            && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+
+    fn square_distance_to(&self, p: Point) -> f32 {
+        let dx = (self.min.x - p.x).max(0.0).max(p.x - self.max.x);
+        let dy = (self.min.y - p.y).max(0.0).max(p.y - self.max.y);
+
+        dx * dx + dy * dy
+    }
+
+    // Returns the range of the ray's parameter in the box, if any.
+    fn ray_range(&self, origin: Point, direction: Vector) -> Option<(f32, f32)> {
+        let mut t_min = 0.0;
+        let mut t_max = f32::INFINITY;
+        let axes = [
+            (origin.x, direction.x, self.min.x, self.max.x),
+            (origin.y, direction.y, self.min.y, self.max.y),
+        ];
+        for &(o, d, min, max) in &axes {
+            if d == 0.0 {
+                if o < min || o > max {
+                    return None;
+                }
+                continue;
+            }
+            let t0 = (min - o) / d;
+            let t1 = (max - o) / d;
+            let (t0, t1) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+            t_min = t0.max(t_min);
+            t_max = t1.min(t_max);
+            if t_min > t_max {
+                return None;
+            }
+        }
+
+        Some((t_min, t_max))
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct BvhNode {
+    aabb: Aabb,
+    // For leaves, the range of the node's items in `Bvh::items`. For other nodes,
+    // `count` is zero and the two children are at `start` and `start + 1`.
+    start: usize,
+    count: usize,
+}
+
+/// A bounding volume hierarchy over a set of rectangles.
+///
+/// The rectangles are identified by their index in the slice the hierarchy is built from.
+/// Rectangles are considered to include their boundaries, so rectangles with a zero width
+/// or height are supported.
+#[derive(Clone, Debug)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    items: Vec<(usize, Aabb)>,
+}
+
+impl Bvh {
+    /// Builds a hierarchy over the given rectangles.
+    pub fn new(rects: &[Rect]) -> Self {
+        let mut bvh = Bvh {
+            nodes: Vec::with_capacity(rects.len() * 2 / MAX_ITEMS_PER_LEAF + 1),
+            items: Vec::with_capacity(rects.len()),
+        };
+
+        if rects.is_empty() {
+            return bvh;
+        }
+
+        let mut entries: Vec<(usize, Aabb)> = rects.iter()
+            .enumerate()
+            .map(|(i, rect)| (i, Aabb::from_rect(rect)))
+            .collect();
+
+        bvh.nodes.push(BvhNode {
+            aabb: entries[0].1,
+            start: 0,
+            count: 0,
+        });
+        bvh.build_node(0, &mut entries);
+
+        bvh
+    }
+
+    fn build_node(&mut self, node: usize, entries: &mut [(usize, Aabb)]) {
+        let mut aabb = entries[0].1;
+        let mut centers = Aabb { min: aabb.center(), max: aabb.center() };
+        for entry in entries.iter() {
+            let entry = &entry.1;
+            aabb = aabb.union(entry);
+            let c = entry.center();
+            centers = centers.union(&Aabb { min: c, max: c });
+        }
+        self.nodes[node].aabb = aabb;
+
+        if entries.len() <= MAX_ITEMS_PER_LEAF {
+            self.nodes[node].start = self.items.len();
+            self.nodes[node].count = entries.len();
+            self.items.extend_from_slice(entries);
+            return;
+        }
+
+        // Split at the median along the axis in which the centers are the most spread.
+        let split_x = centers.max.x - centers.min.x > centers.max.y - centers.min.y;
+        entries.sort_by(|a, b| {
+            let (a, b) = (a.1.center(), b.1.center());
+            let (a, b) = if split_x { (a.x, b.x) } else { (a.y, b.y) };
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        });
+
+        let children = self.nodes.len();
+        for _ in 0..2 {
+            self.nodes.push(BvhNode { aabb: aabb, start: 0, count: 0 });
+        }
+        self.nodes[node].start = children;
+
+        let (left, right) = entries.split_at_mut(entries.len() / 2);
+        self.build_node(children, left);
+        self.build_node(children + 1, right);
+    }
+
+    /// Calls the callback with the index of each rectangle that overlaps `rect`.
+    pub fn for_each_in_rect<F: FnMut(usize)>(&self, rect: &Rect, mut callback: F) {
+        let query = Aabb::from_rect(rect);
+        self.traverse(|aabb| aabb.overlaps(&query), &mut callback);
+    }
+
+    /// Calls the callback with the index of each rectangle that intersects the ray starting at
+    /// `origin` and going towards `direction`.
+    pub fn for_each_on_ray<F: FnMut(usize)>(&self, origin: Point, direction: Vector, mut callback: F) {
+        self.traverse(|aabb| aabb.ray_range(origin, direction).is_some(), &mut callback);
+    }
+
+    /// Finds the item with the smallest distance to a point.
+    ///
+    /// The distance between `point` and an item is given by the `distance` callback, and
+    /// must be greater than or equal to the distance between `point` and the item's rectangle.
+    /// Returns the index of the nearest item and its distance.
+    pub fn nearest<F: FnMut(usize) -> f32>(&self, point: Point, mut distance: F) -> Option<(usize, f32)> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let mut best: Option<(usize, f32)> = None;
+        let mut stack = vec![(0, self.nodes[0].aabb.square_distance_to(point))];
+        while let Some((node_idx, square_distance)) = stack.pop() {
+            if let Some((_, best_distance)) = best {
+                if square_distance > best_distance * best_distance {
+                    continue;
+                }
+            }
+
+            let node = &self.nodes[node_idx];
+            if node.count > 0 {
+                for &(item, ref aabb) in &self.items[node.start..node.start + node.count] {
+                    if let Some((_, best_distance)) = best {
+                        if aabb.square_distance_to(point) > best_distance * best_distance {
+                            continue;
+                        }
+                    }
+                    let d = distance(item);
+                    if best.map(|(_, best_distance)| d < best_distance).unwrap_or(true) {
+                        best = Some((item, d));
+                    }
+                }
+                continue;
+            }
+
+            // Push the farthest child first so that the nearest one is visited first.
+            let a = (node.start, self.nodes[node.start].aabb.square_distance_to(point));
+            let b = (node.start + 1, self.nodes[node.start + 1].aabb.square_distance_to(point));
+            if a.1 < b.1 {
+                stack.push(b);
+                stack.push(a);
+            } else {
+                stack.push(a);
+                stack.push(b);
+            }
+        }
+
+        best
+    }
+
+    fn traverse<Filter, F>(&self, filter: Filter, callback: &mut F)
+    where
+        Filter: Fn(&Aabb) -> bool,
+        F: FnMut(usize),
+    {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        let mut stack = vec![0];
+        while let Some(node_idx) = stack.pop() {
+            let node = &self.nodes[node_idx];
+            if !filter(&node.aabb) {
+                continue;
+            }
+            if node.count > 0 {
+                for &(item, ref aabb) in &self.items[node.start..node.start + node.count] {
+                    if filter(aabb) {
+                        callback(item);
+                    }
+                }
+            } else {
+                stack.push(node.start + 1);
+                stack.push(node.start);
+            }
+        }
+    }
+}
+
+/// A segment stored in a `SegmentIndex`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IndexedSegment {
+    pub segment: PathSegment,
+    /// The index of the event the segment comes from.
+    pub event_index: usize,
+}
+
+/// The result of `SegmentIndex::nearest`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NearestSegment {
+    pub segment: IndexedSegment,
+    /// The nearest position on the segment.
+    pub position: Point,
+    /// The parameter of `position` on the segment, approximated for curves.
+    pub t: f32,
+    pub distance: f32,
+}
+
+/// An intersection between a ray and a segment.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RayHit {
+    pub segment: IndexedSegment,
+    pub position: Point,
+    /// The parameter of `position` on the segment, approximated for curves.
+    pub t: f32,
+    /// The parameter of `position` on the ray, in units of the ray's direction.
+    pub ray_t: f32,
+}
+
+/// A spatial index over the segments of a path.
+///
+/// See the [spatial_index module](index.html).
+#[derive(Clone, Debug)]
+pub struct SegmentIndex {
+    segments: Vec<IndexedSegment>,
+    bvh: Bvh,
+    // The segments that implicitly close sub-paths without a Close event, which are
+    // only taken into account by winding number queries.
+    implicit_closing_segments: Vec<LineSegment>,
+    tolerance: f32,
+}
+
+impl SegmentIndex {
+    /// Builds the index of a path.
+    ///
+    /// The queries approximate curves with line segments within `tolerance`.
+    pub fn new<Iter: PathIterator>(path: Iter, tolerance: f32) -> Self {
+        let mut segments = Vec::new();
+        let mut implicit_closing_segments = Vec::new();
+        let mut first = point(0.0, 0.0);
+        let mut current = first;
+        for (event_index, evt) in path.enumerate() {
+            let segment = match evt {
+                PathEvent::MoveTo(to) => {
+                    if current != first {
+                        implicit_closing_segments.push(LineSegment { from: current, to: first });
+                    }
+                    first = to;
+                    current = to;
+                    None
+                }
+                PathEvent::Close => {
+                    if current != first {
+                        Some(PathSegment::Line(LineSegment { from: current, to: first }))
+                    } else {
+                        None
+                    }
+                }
+                _ => PathSegment::from_event(current, evt),
+            };
+
+            if let Some(segment) = segment {
+                current = segment.to();
+                segments.push(IndexedSegment {
+                    segment: segment,
+                    event_index: event_index,
+                });
+            }
+        }
+        if current != first {
+            implicit_closing_segments.push(LineSegment { from: current, to: first });
+        }
+
+        let rects: Vec<Rect> = segments.iter().map(|s| s.segment.bounding_rect()).collect();
+
+        SegmentIndex {
+            bvh: Bvh::new(&rects),
+            segments: segments,
+            implicit_closing_segments: implicit_closing_segments,
+            tolerance: tolerance,
+        }
+    }
+
+    /// The indexed segments, in the order of the path.
+    pub fn segments(&self) -> &[IndexedSegment] { &self.segments }
+
+    /// The bounding volume hierarchy over the segments.
+    pub fn bvh(&self) -> &Bvh { &self.bvh }
+
+    /// Calls the callback with each segment which bounding rectangle overlaps `rect`.
+    pub fn for_each_in_rect<F: FnMut(&IndexedSegment)>(&self, rect: &Rect, mut callback: F) {
+        let segments = &self.segments;
+        self.bvh.for_each_in_rect(rect, |idx| callback(&segments[idx]));
+    }
+
+    /// Finds the segment that is the nearest to a point.
+    pub fn nearest(&self, p: Point) -> Option<NearestSegment> {
+        let tolerance = self.tolerance;
+        let segments = &self.segments;
+        let (idx, _) = self.bvh.nearest(p, |idx| nearest_on_segment(&segments[idx].segment, p, tolerance).2)?;
+
+        let (position, t, distance) = nearest_on_segment(&segments[idx].segment, p, tolerance);
+
+        Some(NearestSegment {
+            segment: segments[idx],
+            position: position,
+            t: t,
+            distance: distance,
+        })
+    }
+
+    /// Computes the intersections between the path and a ray, sorted by distance to the
+    /// origin of the ray.
+    ///
+    /// A ray going through the point shared by two consecutive segments intersects both.
+    pub fn ray_intersections(&self, origin: Point, direction: Vector) -> Vec<RayHit> {
+        let mut hits = Vec::new();
+        let tolerance = self.tolerance;
+        let segments = &self.segments;
+        self.bvh.for_each_on_ray(origin, direction, |idx| {
+            let segment = &segments[idx];
+            for_each_flattened(&segment.segment, tolerance, &mut |line, t0, t1| {
+                if let Some((ray_t, line_t)) = ray_line_intersection(origin, direction, &line) {
+                    // Don't count the shared endpoint of consecutive pieces twice.
+                    if line_t == 1.0 && t1 != 1.0 {
+                        return;
+                    }
+                    hits.push(RayHit {
+                        segment: *segment,
+                        position: line.sample(line_t),
+                        t: t0 + (t1 - t0) * line_t,
+                        ray_t: ray_t,
+                    });
+                }
+            });
+        });
+
+        hits.sort_by(|a, b| a.ray_t.partial_cmp(&b.ray_t).unwrap_or(Ordering::Equal));
+
+        hits
+    }
+
+    /// Returns the intersection between the path and a ray that is the nearest to the
+    /// origin of the ray.
+    pub fn first_ray_intersection(&self, origin: Point, direction: Vector) -> Option<RayHit> {
+        self.ray_intersections(origin, direction).first().cloned()
+    }
+
+    /// Computes the winding number of the path around a point.
+    ///
+    /// This gives the same result as `hit_test::winding_number` while only looking at the
+    /// segments that cross the horizontal ray going from the point towards positive x.
+    pub fn winding_number(&self, p: Point) -> i32 {
+        let tolerance = self.tolerance;
+        let ray = Rect::new(p, ::math::size(f32::INFINITY, 0.0));
+        let mut winding = 0;
+        self.for_each_in_rect(&ray, |segment| {
+            winding += segment_winding(&segment.segment, p, tolerance);
+        });
+        for line in &self.implicit_closing_segments {
+            winding += line_winding(line.from, line.to, p);
+        }
+
+        winding
+    }
+
+    /// Returns whether a point is inside of the path, according to a fill rule.
+    pub fn contains_point(&self, p: Point, fill_rule: FillRule) -> bool {
+        fill_rule.is_in(self.winding_number(p))
+    }
+}
+
+// Calls the callback with the line segments approximating a segment and the range of t
+// they correspond to.
+fn for_each_flattened<F>(segment: &PathSegment, tolerance: f32, callback: &mut F)
+where
+    F: FnMut(LineSegment, f32, f32),
+{
+    let mut from = (segment.from(), 0.0);
+    segment.flattened_with_t_for_each(tolerance, &mut |p, t| {
+        callback(LineSegment { from: from.0, to: p }, from.1, t);
+        from = (p, t);
+    });
+}
+
+// Returns the nearest position, its parameter on the segment and its distance to the point.
+fn nearest_on_segment(segment: &PathSegment, p: Point, tolerance: f32) -> (Point, f32, f32) {
+    let mut best = (segment.from(), 0.0, (segment.from() - p).length());
+    for_each_flattened(segment, tolerance, &mut |line, t0, t1| {
+        let v = line.to - line.from;
+        let square_length = v.square_length();
+        let s = if square_length > 0.0 {
+            ((p - line.from).dot(v) / square_length).max(0.0).min(1.0)
+        } else {
+            0.0
+        };
+        let position = line.sample(s);
+        let distance = (position - p).length();
+        if distance < best.2 {
+            best = (position, t0 + (t1 - t0) * s, distance);
+        }
+    });
+
+    best
+}
+
+// Returns the parameters of the intersection on the ray and on the line segment.
+fn ray_line_intersection(origin: Point, direction: Vector, line: &LineSegment) -> Option<(f32, f32)> {
+    let v = line.to - line.from;
+    let denom = direction.cross(v);
+    if denom == 0.0 {
+        return None;
+    }
+
+    let w = line.from - origin;
+    let ray_t = w.cross(v) / denom;
+    let line_t = w.cross(direction) / denom;
+    if ray_t < 0.0 || line_t < 0.0 || line_t > 1.0 {
+        return None;
+    }
+
+    Some((ray_t, line_t))
+}
+
+#[cfg(test)]
+use math::{vector, rect, Radians};
+#[cfg(test)]
+use default::Path;
+#[cfg(test)]
+use builder::{FlatPathBuilder, PathBuilder};
+#[cfg(test)]
+use hit_test;
+
+#[test]
+fn test_bvh() {
+    let mut rects = Vec::new();
+    for i in 0..100 {
+        let x = (i % 10) as f32;
+        let y = (i / 10) as f32;
+        // Include zero-sized rectangles.
+        rects.push(rect(x * 10.0, y * 10.0, (i % 3) as f32, (i % 2) as f32));
+    }
+    let bvh = Bvh::new(&rects);
+
+    let query = rect(15.0, 15.0, 20.0, 10.0);
+    let mut found = Vec::new();
+    bvh.for_each_in_rect(&query, |i| found.push(i));
+    found.sort();
+    let expected: Vec<usize> = (0..100).filter(|&i| {
+        Aabb::from_rect(&rects[i]).overlaps(&Aabb::from_rect(&query))
+    }).collect();
+    assert_eq!(found, expected);
+    assert!(!found.is_empty());
+
+    let p = point(42.0, 57.0);
+    let (nearest, distance) = bvh.nearest(p, |i| Aabb::from_rect(&rects[i]).square_distance_to(p).sqrt()).unwrap();
+    let mut expected = (0, f32::MAX);
+    for (i, r) in rects.iter().enumerate() {
+        let d = Aabb::from_rect(r).square_distance_to(p).sqrt();
+        if d < expected.1 {
+            expected = (i, d);
+        }
+    }
+    assert_eq!((nearest, distance), expected);
+
+    let mut found = Vec::new();
+    bvh.for_each_on_ray(point(-5.0, 30.0), vector(1.0, 0.0), |i| found.push(i));
+    found.sort();
+    assert_eq!(found, (30..40).collect::<Vec<usize>>());
+
+    assert_eq!(Bvh::new(&[]).nearest(p, |_| 0.0), None);
+}
+
+#[cfg(test)]
+fn test_path() -> Path {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    for i in 1..50 {
+        builder.line_to(point(i as f32, (i % 2) as f32));
+    }
+    builder.quadratic_bezier_to(point(60.0, 10.0), point(50.0, 20.0));
+    builder.cubic_bezier_to(point(40.0, 30.0), point(20.0, 10.0), point(10.0, 20.0));
+    builder.arc(point(5.0, 20.0), vector(5.0, 5.0), Radians::new(3.14), Radians::new(0.0));
+    builder.close();
+    builder.move_to(point(20.0, 5.0));
+    builder.line_to(point(30.0, 5.0));
+    builder.line_to(point(30.0, 12.0));
+    builder.build()
+}
+
+#[test]
+fn test_segment_index() {
+    let path = test_path();
+    let index = SegmentIndex::new(path.path_iter(), 0.01);
+
+    // 49 lines, 3 curves, the closing segment and 2 lines.
+    assert_eq!(index.segments().len(), 55);
+    assert_eq!(index.segments()[52].event_index, 53);
+
+    let mut found = Vec::new();
+    index.for_each_in_rect(&rect(10.2, -1.0, 0.5, 1.5), |s| found.push(s.event_index));
+    assert_eq!(found, vec![11]);
+
+    let nearest = index.nearest(point(25.0, 4.0)).unwrap();
+    assert_eq!(nearest.segment.event_index, 55);
+    assert_eq!(nearest.position, point(25.0, 5.0));
+    assert!((nearest.t - 0.5).abs() < 0.0001);
+    assert_eq!(nearest.distance, 1.0);
+
+    let nearest = index.nearest(point(0.0, 25.0)).unwrap();
+    assert_eq!(nearest.segment.event_index, 52);
+    assert!((nearest.distance - (50.0f32.sqrt() - 5.0)).abs() < 0.01);
+
+    let hits = index.ray_intersections(point(25.5, -10.0), vector(0.0, 1.0));
+    let events: Vec<usize> = hits.iter().map(|hit| hit.segment.event_index).collect();
+    assert_eq!(events, vec![26, 55, 51]);
+    assert!((hits[0].position.y - 0.5).abs() < 0.0001);
+    assert!((hits[0].t - 0.5).abs() < 0.0001);
+
+    // Both segments are hit at the point they share.
+    let hits = index.ray_intersections(point(25.0, -10.0), vector(0.0, 1.0));
+    let events: Vec<usize> = hits.iter().map(|hit| hit.segment.event_index).collect();
+    assert_eq!(events, vec![25, 26, 55, 51]);
+    assert!((hits[2].ray_t - 15.0).abs() < 0.0001);
+    assert!((hits[3].segment.segment.sample(hits[3].t) - hits[3].position).length() < 0.05);
+
+    assert_eq!(index.first_ray_intersection(point(25.0, -10.0), vector(0.0, -1.0)), None);
+
+    for &p in &[point(25.0, 7.0), point(25.0, 4.0), point(5.0, 18.0), point(55.0, 10.0), point(-1.0, 0.5), point(25.0, 10.0)] {
+        assert_eq!(index.winding_number(p), hit_test::winding_number(path.path_iter(), p, 0.01));
+    }
+    assert!(index.contains_point(point(25.0, 4.0), FillRule::EvenOdd));
+    assert!(index.contains_point(point(25.0, 10.0), FillRule::EvenOdd));
+    // Inside of both sub-paths.
+    assert!(!index.contains_point(point(25.0, 7.0), FillRule::EvenOdd));
+    assert!(!index.contains_point(point(-1.0, 0.5), FillRule::EvenOdd));
+}
+
+#[test]
+fn test_segment_index_without_move_to() {
+    use PathEvent;
+    use iterator::PathIter;
+
+    let events = [
+        PathEvent::LineTo(point(10.0, 0.0)),
+        PathEvent::LineTo(point(10.0, 10.0)),
+        PathEvent::LineTo(point(0.0, 10.0)),
+    ];
+    let index = SegmentIndex::new(PathIter::new(events.iter().cloned()), 0.01);
+
+    let ends: Vec<Point> = index.segments().iter().map(|s| s.segment.to()).collect();
+    assert_eq!(ends, vec![point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)]);
+    assert_eq!(index.segments()[1].segment.from(), point(10.0, 0.0));
+    // The sub-path is implicitly closed.
+    assert!(index.contains_point(point(5.0, 5.0), FillRule::EvenOdd));
+}
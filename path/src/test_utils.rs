@@ -0,0 +1,37 @@
+//! Helpers shared by the tests of this crate.
+
+use default::Path;
+use builder::{FlatPathBuilder, PolygonBuilder};
+use orientation::signed_area;
+use math::point;
+use PathEvent;
+
+/// Builds an axis-aligned square with a positive winding.
+pub fn square(x: f32, y: f32, size: f32) -> Path {
+    let mut builder = Path::builder();
+    builder.polygon(&[
+        point(x, y),
+        point(x + size, y),
+        point(x + size, y + size),
+        point(x, y + size),
+    ]);
+    builder.build()
+}
+
+/// Returns the signed area and the number of sub-paths of a path.
+pub fn area_and_count(path: &Path) -> (f32, usize) {
+    let area = signed_area(path.path_iter(), 0.0001);
+    let count = path.iter().filter(|evt| match *evt {
+        PathEvent::MoveTo(..) => true,
+        _ => false,
+    }).count();
+
+    (area, count)
+}
+
+pub fn assert_area_and_count(path: &Path, expected_area: f32, expected_count: usize) {
+    let (area, count) = area_and_count(path);
+    if (area - expected_area).abs() > 0.01 || count != expected_count {
+        panic!("Expected area {} and {} sub-paths, got {} and {}.", expected_area, expected_count, area, count);
+    }
+}
@@ -10,7 +10,9 @@ use geom::math::*;
 use geom::Arc;
 use path::builder::FlatPathBuilder;
 use path::iterator::FromPolyline;
-use {FillOptions, FillVertex, StrokeVertex, StrokeOptions, Side};
+
+pub use path::builder::BorderRadii;
+use {FillOptions, FillVertex, StrokeVertex, StrokeOptions, Side, VertexSource};
 
 use std::f32::consts::PI;
 
@@ -37,18 +39,21 @@ pub fn fill_triangle(
         FillVertex {
             position: v1,
             normal: compute_normal(t31, t12),
+            source: VertexSource::NONE,
         }
     );
     let b = output.add_vertex(
         FillVertex {
             position: v2,
             normal: compute_normal(t12, t23),
+            source: VertexSource::NONE,
         }
     );
     let c = output.add_vertex(
         FillVertex {
             position: v3,
             normal: compute_normal(t23, t31),
+            source: VertexSource::NONE,
         }
     );
 
@@ -94,24 +99,28 @@ pub fn fill_quad(
         FillVertex {
             position: v1,
             normal: compute_normal(t41, t12),
+            source: VertexSource::NONE,
         }
     );
     let b = output.add_vertex(
         FillVertex {
             position: v2,
             normal: compute_normal(t12, t23),
+            source: VertexSource::NONE,
         }
     );
     let c = output.add_vertex(
         FillVertex {
             position: v3,
             normal: compute_normal(t23, t34),
+            source: VertexSource::NONE,
         }
     );
     let d = output.add_vertex(
         FillVertex {
             position: v4,
             normal: compute_normal(t34, t41),
+            source: VertexSource::NONE,
         }
     );
     output.add_triangle(a, b, c);
@@ -143,24 +152,28 @@ pub fn fill_rectangle(
         FillVertex {
             position: rect.origin,
             normal: vector(-1.0, -1.0),
+            source: VertexSource::NONE,
         }
     );
     let b = output.add_vertex(
         FillVertex {
             position: rect.bottom_left(),
             normal: vector(-1.0, 1.0),
+            source: VertexSource::NONE,
         }
     );
     let c = output.add_vertex(
         FillVertex {
             position: rect.bottom_right(),
             normal: vector(1.0, 1.0),
+            source: VertexSource::NONE,
         }
     );
     let d = output.add_vertex(
         FillVertex {
             position: rect.top_right(),
             normal: vector(1.0, -1.0),
+            source: VertexSource::NONE,
         }
     );
     output.add_triangle(a, b, c);
@@ -182,6 +195,7 @@ pub fn stroke_rectangle(
             normal: -vector(-1.0, -1.0),
             advancement: 0.0,
             side: Side::Right,
+            source: VertexSource::NONE,
         }
     );
     let a2 = output.add_vertex(
@@ -190,6 +204,7 @@ pub fn stroke_rectangle(
             normal: vector(-1.0, -1.0),
             advancement: 0.0,
             side: Side::Left,
+            source: VertexSource::NONE,
         }
     );
 
@@ -199,6 +214,7 @@ pub fn stroke_rectangle(
             normal: -vector(1.0, -1.0),
             advancement: 0.0,
             side: Side::Right,
+            source: VertexSource::NONE,
         }
     );
     let b2 = output.add_vertex(
@@ -207,6 +223,7 @@ pub fn stroke_rectangle(
             normal: vector(1.0, -1.0),
             advancement: 0.0,
             side: Side::Left,
+            source: VertexSource::NONE,
         }
     );
 
@@ -216,6 +233,7 @@ pub fn stroke_rectangle(
             normal: -vector(1.0, 1.0),
             advancement: 0.0,
             side: Side::Right,
+            source: VertexSource::NONE,
         }
     );
     let c2 = output.add_vertex(
@@ -224,6 +242,7 @@ pub fn stroke_rectangle(
             normal: vector(1.0, 1.0),
             advancement: 0.0,
             side: Side::Left,
+            source: VertexSource::NONE,
         }
     );
 
@@ -233,6 +252,7 @@ pub fn stroke_rectangle(
             normal: -vector(1.0, 0.0),
             advancement: 0.0,
             side: Side::Right,
+            source: VertexSource::NONE,
         }
     );
     let d2 = output.add_vertex(
@@ -241,6 +261,7 @@ pub fn stroke_rectangle(
             normal: vector(1.0, 0.0),
             advancement: 0.0,
             side: Side::Left,
+            source: VertexSource::NONE,
         }
     );
 
@@ -256,40 +277,6 @@ pub fn stroke_rectangle(
     return output.end_geometry();
 }
 
-/// The radius of each corner of a rounded rectangle.
-pub struct BorderRadii {
-    pub top_left: f32,
-    pub top_right: f32,
-    pub bottom_left: f32,
-    pub bottom_right: f32,
-}
-
-impl BorderRadii {
-    pub fn new(
-        top_left: f32,
-        top_right: f32,
-        bottom_left: f32,
-        bottom_right: f32,
-    ) -> Self {
-        BorderRadii {
-            top_left: top_left.abs(),
-            top_right: top_right.abs(),
-            bottom_left: bottom_left.abs(),
-            bottom_right: bottom_right.abs(),
-        }
-    }
-
-    pub fn new_all_same(radius: f32) -> Self {
-        let r = radius.abs();
-        BorderRadii {
-            top_left: r,
-            top_right: r,
-            bottom_left: r,
-            bottom_right: r,
-        }
-    }
-}
-
 /// Tessellate an axis-aligned rounded rectangle.
 pub fn fill_rounded_rectangle(
     rect: &Rect,
@@ -299,39 +286,13 @@ pub fn fill_rounded_rectangle(
 ) -> Count {
     output.begin_geometry();
 
-    let w = rect.size.width;
-    let h = rect.size.height;
     let x_min = rect.min_x();
     let y_min = rect.min_y();
     let x_max = rect.max_x();
     let y_max = rect.max_y();
-    let min_wh = w.min(h);
-    let mut tl = radii.top_left.abs().min(min_wh);
-    let mut tr = radii.top_right.abs().min(min_wh);
-    let mut bl = radii.bottom_left.abs().min(min_wh);
-    let mut br = radii.bottom_right.abs().min(min_wh);
-
-    // clamp border radii if they don't fit in the rectangle.
-    if tl + tr > w {
-        let x = (tl + tr - w) * 0.5;
-        tl -= x;
-        tr -= x;
-    }
-    if bl + br > w {
-        let x = (bl + br - w) * 0.5;
-        bl -= x;
-        br -= x;
-    }
-    if tr + br > h {
-        let x = (tr + br - h) * 0.5;
-        tr -= x;
-        br -= x;
-    }
-    if tl + bl > h {
-        let x = (tl + bl - h) * 0.5;
-        tl -= x;
-        bl -= x;
-    }
+    let radii = radii.clamped(rect.size);
+    let (tl, tr) = (radii.top_left, radii.top_right);
+    let (bl, br) = (radii.bottom_left, radii.bottom_right);
 
     // top
     let p1 = point(x_min + tl, y_min);
@@ -356,14 +317,14 @@ pub fn fill_rounded_rectangle(
 
 
     let v = [
-        output.add_vertex(FillVertex { position: p7, normal: left }),
-        output.add_vertex(FillVertex { position: p6, normal: down }),
-        output.add_vertex(FillVertex { position: p5, normal: down }),
-        output.add_vertex(FillVertex { position: p4, normal: right }),
-        output.add_vertex(FillVertex { position: p3, normal: right }),
-        output.add_vertex(FillVertex { position: p2, normal: up }),
-        output.add_vertex(FillVertex { position: p1, normal: up }),
-        output.add_vertex(FillVertex { position: p0, normal: left }),
+        output.add_vertex(FillVertex { position: p7, normal: left, source: VertexSource::NONE }),
+        output.add_vertex(FillVertex { position: p6, normal: down, source: VertexSource::NONE }),
+        output.add_vertex(FillVertex { position: p5, normal: down, source: VertexSource::NONE }),
+        output.add_vertex(FillVertex { position: p4, normal: right, source: VertexSource::NONE }),
+        output.add_vertex(FillVertex { position: p3, normal: right, source: VertexSource::NONE }),
+        output.add_vertex(FillVertex { position: p2, normal: up, source: VertexSource::NONE }),
+        output.add_vertex(FillVertex { position: p1, normal: up, source: VertexSource::NONE }),
+        output.add_vertex(FillVertex { position: p0, normal: left, source: VertexSource::NONE }),
     ];
 
     output.add_triangle(v[6], v[7], v[0]);
@@ -436,6 +397,7 @@ fn fill_border_radius(
     let vertex = output.add_vertex(FillVertex {
         position: pos,
         normal: normal,
+        source: VertexSource::NONE,
     });
 
     output.add_triangle(vb, vertex, va);
@@ -469,39 +431,13 @@ pub fn stroke_rounded_rectangle(
 ) -> Count {
     output.begin_geometry();
 
-    let w = rect.size.width;
-    let h = rect.size.height;
     let x_min = rect.min_x();
     let y_min = rect.min_y();
     let x_max = rect.max_x();
     let y_max = rect.max_y();
-    let min_wh = w.min(h);
-    let mut tl = radii.top_left.abs().min(min_wh);
-    let mut tr = radii.top_right.abs().min(min_wh);
-    let mut bl = radii.bottom_left.abs().min(min_wh);
-    let mut br = radii.bottom_right.abs().min(min_wh);
-
-    // clamp border radii if they don't fit in the rectangle.
-    if tl + tr > w {
-        let x = (tl + tr - w) * 0.5;
-        tl -= x;
-        tr -= x;
-    }
-    if bl + br > w {
-        let x = (bl + br - w) * 0.5;
-        bl -= x;
-        br -= x;
-    }
-    if tr + br > h {
-        let x = (tr + br - h) * 0.5;
-        tr -= x;
-        br -= x;
-    }
-    if tl + bl > h {
-        let x = (tl + bl - h) * 0.5;
-        tl -= x;
-        bl -= x;
-    }
+    let radii = radii.clamped(rect.size);
+    let (tl, tr) = (radii.top_left, radii.top_right);
+    let (bl, br) = (radii.bottom_left, radii.bottom_right);
 
     // top
     let p1 = point(x_min + tl, y_min);
@@ -592,19 +528,23 @@ pub fn fill_circle(
     let v = [
         output.add_vertex(FillVertex {
             position: center + (left * radius),
-            normal: left
+            normal: left,
+            source: VertexSource::NONE,
         }),
         output.add_vertex(FillVertex {
             position: center + (up * radius),
-            normal: up
+            normal: up,
+            source: VertexSource::NONE,
         }),
         output.add_vertex(FillVertex {
             position: center + (right * radius),
-            normal: right
+            normal: right,
+            source: VertexSource::NONE,
         }),
         output.add_vertex(FillVertex {
             position: center + (down * radius),
-            normal: down
+            normal: down,
+            source: VertexSource::NONE,
         }),
     ];
 
@@ -796,12 +736,14 @@ where
             FillVertex {
                 position: a2,
                 normal: compute_normal(a2 - a1, a3 - a2),
+                source: VertexSource::NONE,
             }
         );
         let mut b = output.add_vertex(
             FillVertex {
                 position: b3,
                 normal: compute_normal(b3 - b2, b4 - b3),
+                source: VertexSource::NONE,
             }
         );
 
@@ -810,6 +752,7 @@ where
                 FillVertex {
                     position: p2,
                     normal: compute_normal(p2 - p1, p3 - p2),
+                    source: VertexSource::NONE,
                 }
             );
 
@@ -188,7 +188,7 @@
 //!
 //! ```
 //! use lyon_tessellation::geometry_builder::*;
-//! use lyon_tessellation::FillVertex;
+//! use lyon_tessellation::{FillVertex, VertexSource};
 //! use lyon_tessellation::math::{Rect, vector};
 //!
 //! // A tessellator that generates an axis-aligned quad.
@@ -201,16 +201,16 @@
 //!     output.begin_geometry();
 //!     // Create the vertices...
 //!     let a = output.add_vertex(
-//!         FillVertex { position: rect.origin, normal: vector(-1.0, -1.0) }
+//!         FillVertex { position: rect.origin, normal: vector(-1.0, -1.0), source: VertexSource::NONE }
 //!     );
 //!     let b = output.add_vertex(
-//!         FillVertex { position: rect.top_right(), normal: vector(1.0, -1.0) }
+//!         FillVertex { position: rect.top_right(), normal: vector(1.0, -1.0), source: VertexSource::NONE }
 //!     );
 //!     let c = output.add_vertex(
-//!         FillVertex { position: rect.bottom_right(), normal: vector(1.0, 1.0) }
+//!         FillVertex { position: rect.bottom_right(), normal: vector(1.0, 1.0), source: VertexSource::NONE }
 //!     );
 //!     let d = output.add_vertex(
-//!         FillVertex { position: rect.bottom_left(), normal: vector(-1.0, 1.0) }
+//!         FillVertex { position: rect.bottom_left(), normal: vector(-1.0, 1.0), source: VertexSource::NONE }
 //!     );
 //!     // ...and create triangle form these points. a, b, c, and d are relative offsets in the
 //!     // vertex buffer.
@@ -33,6 +33,8 @@
 //!
 //! * [FillTessellator](struct.FillTessellator.html) - Tessellator for complex path fill operations.
 //! * [StrokeTessellator](struct.StrokeTessellator.html) - Tessellator for complex path stroke operations.
+//! * [StrokeOutliner](struct.StrokeOutliner.html) - Computes the outline of a stroke as a fillable path.
+//! * [PathOffsetter](struct.PathOffsetter.html) - Grows or shrinks filled shapes.
 //! * [`GeometryBuilder`](geometry_builder/trait.GeometryBuilder.html) - (See the documentation of the
 //!   [geometry_builder module](geometry_builder/index.html)) Which the above two are built on. This trait
 //!   provides an interface for types that help with building and assembling the vertices and triangles that
@@ -184,10 +186,19 @@ pub extern crate lyon_path as path;
 
 extern crate sid;
 
+#[cfg(feature = "serialization")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(all(test, feature = "serialization"))]
+extern crate serde_json;
+
 pub mod basic_shapes;
 pub mod geometry_builder;
 mod path_fill;
 mod path_stroke;
+mod stroke_outline;
+mod path_offset;
+mod line_join;
 mod math_utils;
 mod fixed;
 
@@ -207,6 +218,12 @@ pub use path_fill::*;
 #[doc(inline)]
 pub use path_stroke::*;
 
+#[doc(inline)]
+pub use stroke_outline::*;
+
+#[doc(inline)]
+pub use path_offset::*;
+
 #[doc(inline)]
 pub use geometry_builder::{GeometryBuilder, BezierGeometryBuilder, VertexBuffers, BuffersBuilder, VertexConstructor, Count};
 
@@ -230,6 +247,38 @@ impl Side {
     pub fn is_right(self) -> bool { self == Side::Right }
 }
 
+/// The location on the original path that a vertex was generated from.
+///
+/// Vertices that don't correspond to any path, for example the ones produced by the
+/// `basic_shapes` tessellators, use `VertexSource::NONE`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VertexSource {
+    /// The endpoint at the start of the segment that generated the vertex.
+    pub from: path::EndpointId,
+    /// The endpoint at the end of the segment that generated the vertex.
+    pub to: path::EndpointId,
+    /// Curve parameter of the vertex on the segment.
+    pub t: f32,
+    /// The sub-path containing the segment.
+    pub sub_path: path::SubPathId,
+}
+
+impl VertexSource {
+    pub const NONE: VertexSource = VertexSource {
+        from: path::EndpointId::INVALID,
+        to: path::EndpointId::INVALID,
+        t: 0.0,
+        sub_path: path::SubPathId::INVALID,
+    };
+
+    /// Returns a source at the endpoint of a segment.
+    pub fn endpoint(id: path::EndpointId, sub_path: path::SubPathId) -> Self {
+        VertexSource { from: id, to: id, t: 0.0, sub_path: sub_path }
+    }
+
+    pub fn is_none(&self) -> bool { !self.from.is_valid() }
+}
+
 /// Vertex produced by the stroke tessellators.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct StrokeVertex {
@@ -243,6 +292,8 @@ pub struct StrokeVertex {
     pub advancement: f32,
     /// Whether the vertex is on the left or right side of the path.
     pub side: Side,
+    /// The location on the path that the vertex was generated from.
+    pub source: VertexSource,
 }
 
 /// Vertex produced by the fill tessellators.
@@ -256,6 +307,11 @@ pub struct FillVertex {
     /// Note that some tessellators aren't fully implemented and don't provide the
     /// normal (a nil vector is provided instead). Refer the documentation of each tessellator.
     pub normal: math::Vector,
+    /// The location on the path that the vertex was generated from.
+    ///
+    /// Vertices created at the intersection of two edges report their location on
+    /// one of the two intersecting segments.
+    pub source: VertexSource,
 }
 
 /// Line cap as defined by the SVG specification.
@@ -282,6 +338,7 @@ pub struct FillVertex {
 ///   </g>
 /// </svg>
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum LineCap {
     /// The stroke for each subpath does not extend beyond its two endpoints.
     /// A zero length subpath will therefore not have any stroke.
@@ -304,6 +361,7 @@ pub enum LineCap {
 ///
 /// See: https://svgwg.org/specs/strokes/#StrokeLinejoinProperty
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum LineJoin {
     /// A sharp corner is to be used to join path segments.
     Miter,
@@ -321,6 +379,7 @@ pub enum LineJoin {
 
 /// Parameters for the tessellator.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct StrokeOptions {
     /// What cap to use at the start of each sub-path.
     ///
@@ -365,6 +424,7 @@ pub struct StrokeOptions {
 
     // To be able to add fields without making it a breaking change, add an empty private field
     // which makes it impossible to create a StrokeOptions without calling the constructor.
+    #[cfg_attr(feature = "serialization", serde(skip))]
     _private: (),
 }
 
@@ -442,16 +502,10 @@ impl StrokeOptions {
     }
 }
 
-/// The fill rule defines how to determine what is inside and what is outside of the shape.
-///
-/// See the SVG specification.
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub enum FillRule {
-    EvenOdd,
-    NonZero,
-}
+pub use path::FillRule;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 /// Parameters for the fill tessellator.
 pub struct FillOptions {
     /// Maximum allowed distance to the path when building an approximation.
@@ -490,6 +544,7 @@ pub struct FillOptions {
 
     // To be able to add fields without making it a breaking change, add an empty private field
     // which makes it impossible to create a FillOptions without the calling constructor.
+    #[cfg_attr(feature = "serialization", serde(skip))]
     _private: (),
 }
 
@@ -545,6 +600,82 @@ impl Default for FillOptions {
     fn default() -> Self { FillOptions::DEFAULT }
 }
 
+/// Parameters for the path offsetter.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct OffsetOptions {
+    /// How to join the offset edges around the convex corners of the shape
+    /// (or around its concave corners when insetting).
+    ///
+    /// Default value: `LineJoin::Miter`.
+    pub line_join: LineJoin,
+
+    /// See the SVG specification.
+    ///
+    /// Must be greater than or equal to 1.0.
+    /// Default value: `StrokeOptions::DEFAULT_MITER_LIMIT`.
+    pub miter_limit: f32,
+
+    /// Maximum allowed distance to the path when building an approximation.
+    ///
+    /// See [Flattening and tolerance](index.html#flattening-and-tolerance).
+    /// Default value: `OffsetOptions::DEFAULT_TOLERANCE`.
+    pub tolerance: f32,
+
+    /// The fill rule that defines the inside of the input path.
+    ///
+    /// Default value: `EvenOdd`.
+    pub fill_rule: FillRule,
+
+    // To be able to add fields without making it a breaking change, add an empty private field
+    // which makes it impossible to create an OffsetOptions without calling the constructor.
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    _private: (),
+}
+
+impl OffsetOptions {
+    const DEFAULT_TOLERANCE: f32 = 0.1;
+
+    const DEFAULT: OffsetOptions = OffsetOptions {
+        line_join: StrokeOptions::DEFAULT_LINE_JOIN,
+        miter_limit: StrokeOptions::DEFAULT_MITER_LIMIT,
+        tolerance: OffsetOptions::DEFAULT_TOLERANCE,
+        fill_rule: FillRule::EvenOdd,
+        _private: (),
+    };
+
+    pub fn default() -> Self { OffsetOptions::DEFAULT }
+
+    pub fn tolerance(tolerance: f32) -> Self {
+        OffsetOptions::DEFAULT.with_tolerance(tolerance)
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        return self;
+    }
+
+    pub fn with_line_join(mut self, join: LineJoin) -> Self {
+        self.line_join = join;
+        return self;
+    }
+
+    pub fn with_miter_limit(mut self, limit: f32) -> Self {
+        assert!(limit >= StrokeOptions::MINIMUM_MITER_LIMIT);
+        self.miter_limit = limit;
+        return self;
+    }
+
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        return self;
+    }
+}
+
+impl Default for OffsetOptions {
+    fn default() -> Self { OffsetOptions::DEFAULT }
+}
+
 #[test]
 fn test_without_miter_limit(){
     let expected_limit = 4.0;
@@ -566,3 +697,21 @@ fn test_with_miter_limit(){
 fn test_with_invalid_miter_limit(){
     let _ = StrokeOptions::default().with_miter_limit(0.0);
 }
+
+#[cfg(feature = "serialization")]
+#[test]
+fn test_options_serialization() {
+    use serde_json;
+
+    let stroke_options = StrokeOptions::default()
+        .with_line_width(3.0)
+        .with_start_cap(LineCap::Round)
+        .with_line_join(LineJoin::Bevel)
+        .with_miter_limit(5.0);
+    let json = serde_json::to_string(&stroke_options).unwrap();
+    assert_eq!(serde_json::from_str::<StrokeOptions>(&json).unwrap(), stroke_options);
+
+    let fill_options = FillOptions::tolerance(0.01).with_normals(false);
+    let json = serde_json::to_string(&fill_options).unwrap();
+    assert_eq!(serde_json::from_str::<FillOptions>(&json).unwrap(), fill_options);
+}
@@ -0,0 +1,76 @@
+//! Join geometry shared by the stroke tessellator and the stroke outliner.
+//!
+//! Offsets are relative to the join point, on the front (outer) side of the join and in
+//! units of half of the line width.
+
+use math_utils::compute_normal;
+use geom::math::*;
+use LineJoin;
+
+/// Returns the join to use between two edges for a given `line_join` option.
+///
+/// Almost aligned edges are joined with a miter. Per SVG spec, miter joins exceeding the
+/// miter limit fall back to bevel joins, and miter-clip joins that don't exceed it are
+/// regular miter joins.
+pub(crate) fn effective_join(
+    line_join: LineJoin,
+    prev_tangent: Vector,
+    next_tangent: Vector,
+    miter_limit: f32,
+) -> LineJoin {
+    let threshold = 0.95; // TODO: look for a good constant here.
+    if prev_tangent.dot(next_tangent) >= threshold {
+        return LineJoin::Miter;
+    }
+
+    let exceeded = miter_limit_is_exceeded(prev_tangent, next_tangent, miter_limit);
+    match line_join {
+        LineJoin::Miter if exceeded => LineJoin::Bevel,
+        LineJoin::MiterClip if !exceeded => LineJoin::Miter,
+        join => join,
+    }
+}
+
+/// Whether the tip of the miter join is further than `miter_limit` from the join point.
+///
+/// This is always the case for u-turns since the tip of their miter is at infinity.
+pub(crate) fn miter_limit_is_exceeded(prev_tangent: Vector, next_tangent: Vector, miter_limit: f32) -> bool {
+    is_u_turn(prev_tangent, next_tangent)
+        || compute_normal(prev_tangent, next_tangent).square_length() > miter_limit * miter_limit
+}
+
+pub(crate) fn is_u_turn(prev_tangent: Vector, next_tangent: Vector) -> bool {
+    prev_tangent.dot(next_tangent) < -0.9999
+}
+
+/// Computes the two offsets of a miter-clip join, given the normals of the edges on the
+/// front side.
+///
+/// The miter is clipped by a line perpendicular to its direction, at a distance of
+/// `miter_limit` from the join point. The first offset is on the previous edge's side and
+/// the second one on the next edge's side. If the clip line doesn't cross the offset
+/// edges, the join falls back to a bevel.
+pub(crate) fn miter_clip_offsets(
+    prev_tangent: Vector,
+    next_tangent: Vector,
+    prev_normal: Vector,
+    next_normal: Vector,
+    miter_limit: f32,
+) -> (Vector, Vector) {
+    let direction = if is_u_turn(prev_tangent, next_tangent) {
+        prev_tangent
+    } else {
+        (prev_normal + next_normal).normalize()
+    };
+
+    let d1 = prev_tangent.dot(direction);
+    let d2 = -next_tangent.dot(direction);
+    if d1 <= 0.0 || d2 <= 0.0 {
+        return (prev_normal, next_normal);
+    }
+
+    let s1 = (miter_limit - prev_normal.dot(direction)) / d1;
+    let s2 = (miter_limit - next_normal.dot(direction)) / d2;
+
+    (prev_normal + prev_tangent * s1, next_normal - next_tangent * s2)
+}
@@ -26,21 +26,27 @@ use std::cmp::{PartialOrd, Ordering};
 use sid::{Id, IdVec};
 
 use FillVertex as Vertex;
-use {FillOptions, FillRule, Side};
+use {FillOptions, FillRule, Side, VertexSource};
 use geom::utils::fast_atan2;
 use geom::math::*;
 use geom::euclid;
 use math_utils::*;
 use geometry_builder::{GeometryBuilder, Count, VertexId};
-use path::PathEvent;
-use path::builder::{FlatPathBuilder, PathBuilder};
-use path::iterator::PathIterator;
+use path::{PathEvent, IdEvent, EndpointId, SubPathId};
+use path::builder::FlatPathBuilder;
+use path::iterator::{PathIter, PathIterator};
+use geom::{QuadraticBezierSegment, CubicBezierSegment, Arc};
+use geom::utils::vector_angle;
 
 #[cfg(test)]
 use geometry_builder::{VertexBuffers, simple_builder};
 #[cfg(test)]
 use path::default::{Path, PathSlice};
 #[cfg(test)]
+use path::PathSegment;
+#[cfg(test)]
+use path::builder::PathBuilder;
+#[cfg(test)]
 use extra::rust_logo::build_logo_path;
 
 #[cfg(test)]
@@ -96,29 +102,66 @@ impl Edge {
     }
 }
 
+/// The segment of the original path that an edge was generated from.
+///
+/// Edges keep their source when they are split, so the start and end of the edge
+/// (in path order) are stored along with their curve parameters in order to find
+/// the curve parameter at any point of the edge.
+#[derive(Copy, Clone, Debug)]
+struct EdgeSource {
+    from: EndpointId,
+    to: EndpointId,
+    sub_path: SubPathId,
+    start: TessPoint,
+    end: TessPoint,
+    t_start: f32,
+    t_end: f32,
+}
+
+impl EdgeSource {
+    fn vertex_source(&self, position: TessPoint) -> VertexSource {
+        let start = to_f32_point(self.start);
+        let v = to_f32_point(self.end) - start;
+        let sq_len = v.square_length();
+        let r = if sq_len > 0.0 {
+            ((to_f32_point(position) - start).dot(v) / sq_len).max(0.0).min(1.0)
+        } else {
+            0.0
+        };
+
+        VertexSource {
+            from: self.from,
+            to: self.to,
+            t: self.t_start + (self.t_end - self.t_start) * r,
+            sub_path: self.sub_path,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct OrientedEdge {
     pub upper: TessPoint,
     pub lower: TessPoint,
     pub winding: i16,
+    source: EdgeSource,
 }
 
 impl OrientedEdge {
-    fn new(mut a: TessPoint, mut b: TessPoint) -> Self {
+    fn new(mut a: TessPoint, mut b: TessPoint, source: EdgeSource) -> Self {
         let mut winding = 1;
         if is_after(a, b) {
             swap(&mut a, &mut b);
             winding = -1;
         }
-        OrientedEdge { upper: a, lower: b, winding }
+        OrientedEdge { upper: a, lower: b, winding, source }
     }
 
-    fn with_winding(mut a: TessPoint, mut b: TessPoint, winding: i16) -> Self {
+    fn with_winding(mut a: TessPoint, mut b: TessPoint, winding: i16, source: EdgeSource) -> Self {
         debug_assert!(winding != 0);
         if is_after(a, b) {
             swap(&mut a, &mut b);
         }
-        OrientedEdge { upper: a, lower: b, winding }
+        OrientedEdge { upper: a, lower: b, winding, source }
     }
 
     fn to_active_edge(&self, upper_id: VertexId) -> ActiveEdge {
@@ -130,6 +173,7 @@ impl OrientedEdge {
             upper_id,
             winding: self.winding,
             merge: false,
+            source: self.source,
         }
     }
 
@@ -144,6 +188,7 @@ struct PendingEdge {
     lower: TessPoint,
     angle: f32,
     winding: i16,
+    source: EdgeSource,
 }
 
 impl PendingEdge {
@@ -152,6 +197,7 @@ impl PendingEdge {
             upper,
             lower: self.lower,
             winding: self.winding,
+            source: self.source,
         }
     }
 
@@ -164,6 +210,7 @@ impl PendingEdge {
             upper_id,
             winding: self.winding,
             merge: false,
+            source: self.source,
         }
     }
 
@@ -272,6 +319,8 @@ pub struct FillTessellator {
     pending_edges: Vec<PendingEdge>,
     // The current position of the sweep line.
     current_position: TessPoint,
+    // The location on the path of the vertices added at the current position.
+    current_source: VertexSource,
 
     // various options
     assume_no_intersections: bool,
@@ -298,6 +347,7 @@ impl FillTessellator {
             monotone_tessellators: IdVec::with_capacity(16),
             intersections: Vec::with_capacity(8),
             current_position: TessPoint::new(FixedPoint32::min_val(), FixedPoint32::min_val()),
+            current_source: VertexSource::NONE,
             error: None,
             assume_no_intersections: true,
             compute_normals: true,
@@ -417,6 +467,7 @@ impl FillTessellator {
                         lower: edge.lower,
                         angle,
                         winding: edge.winding,
+                        source: edge.source,
                     });
                     tess_log!(self, " edge at {:?} -> {:?} (angle={:?})", edge.upper, edge.lower, angle);
 
@@ -454,6 +505,7 @@ impl FillTessellator {
                                 lower: inter.lower,
                                 angle: edge_angle(inter.lower - self.current_position),
                                 winding: inter.winding,
+                                source: inter.source,
                             }
                         );
                     }
@@ -511,7 +563,7 @@ impl FillTessellator {
             (next - position).normalize(),
         );
 
-        output.add_vertex(Vertex { position, normal })
+        output.add_vertex(Vertex { position, normal, source: self.current_source })
     }
 
     fn process_vertex(
@@ -538,6 +590,8 @@ impl FillTessellator {
             _winding_number,
         ) = self.find_interesting_active_edges();
 
+        self.current_source = self.find_current_source(first_edge_above, num_edges_above);
+
         // We'll bump above_idx as we process active edges that interact with
         // the current point.
         let mut above_idx = first_edge_above;
@@ -561,6 +615,7 @@ impl FillTessellator {
                 Vertex {
                     position: vector_position,
                     normal: vector(0.0, 0.0),
+                    source: self.current_source,
                 }
             )
         } else {
@@ -819,6 +874,7 @@ impl FillTessellator {
                     lower: active_edge.points.lower,
                     angle: edge_angle(active_edge.points.lower - self.current_position),
                     winding: active_edge.winding,
+                    source: active_edge.source,
                 });
                 active_edge.points.lower = self.current_position;
             }
@@ -855,6 +911,24 @@ impl FillTessellator {
         );
     }
 
+    // Pick the edge that the vertices at the current position are reported to come from,
+    // preferring the edges that start at this position.
+    fn find_current_source(
+        &self,
+        first_edge_above: ActiveEdgeId,
+        num_edges_above: usize,
+    ) -> VertexSource {
+        if let Some(edge) = self.pending_edges.first() {
+            return edge.source.vertex_source(self.current_position);
+        }
+
+        if num_edges_above > 0 && self.active_edges.has_id(first_edge_above) {
+            return self.active_edges[first_edge_above].source.vertex_source(self.current_position);
+        }
+
+        VertexSource::NONE
+    }
+
     // Look for eventual merge vertices on this span above the current vertex, and connect
     // them to the current vertex.
     // This should be called when processing a vertex that is on the left side of a span.
@@ -1062,22 +1136,29 @@ impl FillTessellator {
 
         let active_edge_lower;
         let active_edge_winding;
+        let active_edge_source;
         {
             let active_edge = &mut self.active_edges[edge_idx];
             active_edge_lower = active_edge.points.lower;
             active_edge_winding = active_edge.winding;
+            active_edge_source = active_edge.source;
             active_edge.points.lower = intersection;
         }
 
+        // The lower parts of the split edges keep the source of the original edges, so the
+        // vertex at the intersection reports its location on one of the intersecting
+        // segments of the path.
         self.intersections.push(OrientedEdge::with_winding(
             intersection,
             original_edge.lower,
-            new_edge.winding
+            new_edge.winding,
+            new_edge.source,
         ));
         self.intersections.push(OrientedEdge::with_winding(
             intersection,
             active_edge_lower,
-            active_edge_winding
+            active_edge_winding,
+            active_edge_source,
         ));
 
         // We sill sort the intersection vector lazily.
@@ -1296,7 +1377,8 @@ fn prepare_pending_edges(
                 if edge_a.lower != edge_b.lower {
                     let furthest = if is_after(edge_a.lower, edge_b.lower) { i } else { i + 1 };
                     let winding = pending_edges[furthest].winding;
-                    intersections.push(OrientedEdge::with_winding(edge_a.lower, edge_b.lower, winding));
+                    let source = pending_edges[furthest].source;
+                    intersections.push(OrientedEdge::with_winding(edge_a.lower, edge_b.lower, winding, source));
                 }
                 i += 2;
             } else {
@@ -1317,6 +1399,7 @@ struct ActiveEdge {
     upper_id: VertexId,
     winding: i16,
     merge: bool,
+    source: EdgeSource,
 }
 
 impl ActiveEdge {
@@ -1389,10 +1472,10 @@ impl FillEvents {
 
         let mut builder = EventsBuilder::new();
         builder.recycle(tmp);
+        builder.tolerance = tolerance;
 
-        let mut builder = builder.flattened(tolerance);
-        for evt in it {
-            builder.path_event(evt);
+        for evt in PathIter::new(it).with_ids() {
+            builder.id_event(evt);
         }
 
         swap(self, &mut builder.build());
@@ -1409,6 +1492,15 @@ pub(crate) struct EventsBuilder {
     current: TessPoint,
     nth: u32,
     tolerance: f32,
+
+    // The path segment that the edges being added come from and the curve
+    // parameter at the current position. The identifiers are invalid when
+    // the events are not built from a path iterator.
+    segment_from: EndpointId,
+    segment_to: EndpointId,
+    sub_path: SubPathId,
+    first_endpoint: EndpointId,
+    current_t: f32,
 }
 
 impl EventsBuilder {
@@ -1423,6 +1515,12 @@ impl EventsBuilder {
             current: TessPoint::new(fixed(0.0), fixed(0.0)),
             nth: 0,
             tolerance: 0.1,
+
+            segment_from: EndpointId::INVALID,
+            segment_to: EndpointId::INVALID,
+            sub_path: SubPathId::INVALID,
+            first_endpoint: EndpointId::INVALID,
+            current_t: 0.0,
         }
     }
 
@@ -1431,12 +1529,114 @@ impl EventsBuilder {
         self.vertices = events.vertices;
     }
 
-    fn add_edge(&mut self, a: TessPoint, b: TessPoint) {
+    fn add_edge(&mut self, a: TessPoint, b: TessPoint, source: EdgeSource) {
         if a != b {
-            self.edges.push(OrientedEdge::new(a, b));
+            self.edges.push(OrientedEdge::new(a, b, source));
+        }
+    }
+
+    fn closing_edge_source(&self) -> EdgeSource {
+        EdgeSource {
+            from: self.segment_to,
+            to: self.first_endpoint,
+            sub_path: self.sub_path,
+            start: self.current,
+            end: self.first,
+            t_start: 0.0,
+            t_end: 1.0,
+        }
+    }
+
+    fn id_event(&mut self, evt: IdEvent) {
+        match evt.event {
+            PathEvent::MoveTo(to) => {
+                self.move_to(to);
+                self.segment_from = evt.from;
+                self.segment_to = evt.to;
+                self.sub_path = evt.sub_path;
+                self.first_endpoint = evt.to;
+                return;
+            }
+            PathEvent::Close => {
+                self.close();
+                return;
+            }
+            _ => {}
+        }
+
+        if evt.sub_path != self.sub_path {
+            // A path that doesn't start with a MoveTo event.
+            self.sub_path = evt.sub_path;
+            self.first_endpoint = evt.from;
+        }
+        self.segment_from = evt.from;
+        self.segment_to = evt.to;
+        self.current_t = 0.0;
+
+        let from = self.current_position();
+        let tolerance = self.tolerance;
+        match evt.event {
+            PathEvent::LineTo(to) => {
+                self.edge_to(to, 1.0);
+            }
+            PathEvent::QuadraticTo(ctrl, to) => {
+                let curve = QuadraticBezierSegment { from, ctrl, to };
+                for (point, t) in curve.flattened_with_t(tolerance) {
+                    self.edge_to(point, t);
+                }
+            }
+            PathEvent::CubicTo(ctrl1, ctrl2, to) => {
+                let curve = CubicBezierSegment { from, ctrl1, ctrl2, to };
+                for (point, t) in curve.flattened_with_t(tolerance) {
+                    self.edge_to(point, t);
+                }
+            }
+            PathEvent::Arc(center, radii, sweep_angle, x_rotation) => {
+                let arc = Arc {
+                    center, radii,
+                    start_angle: vector_angle(from - center),
+                    sweep_angle,
+                    x_rotation,
+                };
+                for (point, t) in arc.flattened_with_t(tolerance) {
+                    self.edge_to(point, t);
+                }
+            }
+            PathEvent::MoveTo(..) | PathEvent::Close => { unreachable!(); }
         }
     }
 
+    fn edge_to(&mut self, to: Point, t: f32) {
+        //debug_assert!(to.x.abs() <= 32767.0);
+        //debug_assert!(to.y.abs() <= 32767.0);
+        let next = to_internal(to);
+        if next == self.current {
+            return;
+        }
+        if self.nth == 0 {
+            self.second = next;
+        }
+        let current = self.current;
+        let previous = self.previous;
+        let source = EdgeSource {
+            from: self.segment_from,
+            to: self.segment_to,
+            sub_path: self.sub_path,
+            start: current,
+            end: next,
+            t_start: self.current_t,
+            t_end: t,
+        };
+        self.add_edge(current, next, source);
+        if self.nth > 0 {
+            self.vertex(previous, current, next);
+        }
+        self.previous = self.current;
+        self.current = next;
+        self.current_t = t;
+        self.nth += 1;
+    }
+
     fn vertex(&mut self, previous: TessPoint, current: TessPoint, next: TessPoint) {
         if is_after(current, previous) && is_after(current, next) {
             self.vertices.push(current);
@@ -1460,7 +1660,8 @@ impl FlatPathBuilder for EventsBuilder {
             let previous = self.previous;
             let first = self.first;
             let second = self.second;
-            self.add_edge(current, first);
+            let source = self.closing_edge_source();
+            self.add_edge(current, first, source);
             self.vertex(previous, current, first);
             self.vertex(current, first, second);
         }
@@ -1470,24 +1671,8 @@ impl FlatPathBuilder for EventsBuilder {
     }
 
     fn line_to(&mut self, to: Point) {
-        //debug_assert!(to.x.abs() <= 32767.0);
-        //debug_assert!(to.y.abs() <= 32767.0);
-        let next = to_internal(to);
-        if next == self.current {
-            return;
-        }
-        if self.nth == 0 {
-            self.second = next;
-        }
-        let current = self.current;
-        let previous = self.previous;
-        self.add_edge(current, next);
-        if self.nth > 0 {
-            self.vertex(previous, current, next);
-        }
-        self.previous = self.current;
-        self.current = next;
-        self.nth += 1;
+        self.current_t = 0.0;
+        self.edge_to(to, 1.0);
     }
 
     fn close(&mut self) {
@@ -1497,7 +1682,8 @@ impl FlatPathBuilder for EventsBuilder {
         let second = self.second;
         if self.current != self.first {
             if self.nth > 0 {
-                self.add_edge(current, first);
+                let source = self.closing_edge_source();
+                self.add_edge(current, first, source);
                 self.vertex(previous, current, first);
             }
             if self.nth > 1 {
@@ -1532,6 +1718,11 @@ impl FlatPathBuilder for EventsBuilder {
         self.previous = TessPoint::new(fixed(0.0), fixed(0.0));
         self.current = TessPoint::new(fixed(0.0), fixed(0.0));
         self.nth = 0;
+        self.segment_from = EndpointId::INVALID;
+        self.segment_to = EndpointId::INVALID;
+        self.sub_path = SubPathId::INVALID;
+        self.first_endpoint = EndpointId::INVALID;
+        self.current_t = 0.0;
 
         self.edges.sort_by(|a, b| compare_positions(a.upper, b.upper));
         self.vertices.sort_by(|a, b| compare_positions(*a, *b));
@@ -2721,3 +2912,102 @@ fn test_no_close() {
 fn test_empty_path() {
     test_path_and_count_triangles(Path::new().as_slice(), 0);
 }
+
+// Returns the position on the path that a vertex source refers to.
+#[cfg(test)]
+pub(crate) fn sample_vertex_source(path: &Path, source: &VertexSource) -> Point {
+    let mut endpoints = Vec::new();
+    let mut current = point(0.0, 0.0);
+    let mut first = current;
+    for evt in path.id_iter() {
+        match evt.event {
+            PathEvent::MoveTo(to) => {
+                first = to;
+                current = to;
+            }
+            PathEvent::Close => {
+                current = first;
+                continue;
+            }
+            _ => {
+                current = PathSegment::from_event(current, evt.event).unwrap().to();
+            }
+        }
+        endpoints.push(current);
+    }
+
+    for evt in path.id_iter() {
+        if evt.from != source.from || evt.to != source.to {
+            continue;
+        }
+        let from = endpoints[evt.from.to_usize()];
+        let to = endpoints[evt.to.to_usize()];
+        return match PathSegment::from_event(from, evt.event) {
+            Some(segment) => segment.sample(source.t),
+            None => from.lerp(to, source.t),
+        };
+    }
+
+    panic!("No segment for {:?}", source);
+}
+
+#[test]
+fn test_vertex_sources() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(10.0, 10.0), point(0.0, 10.0));
+    builder.close();
+    // A triangle intersecting with the first edge at (3.5, 0.0) and (6.5, 0.0).
+    builder.move_to(point(5.0, -5.0));
+    builder.line_to(point(8.0, 5.0));
+    builder.line_to(point(2.0, 5.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Vertex> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        path.path_iter(),
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    let intersections = [point(3.5, 0.0), point(6.5, 0.0)];
+    let mut found = [false, false];
+    for vertex in &buffers.vertices {
+        assert!(!vertex.source.is_none());
+        let expected = sample_vertex_source(&path, &vertex.source);
+        assert!((expected - vertex.position).length() < 0.01, "{:?} {:?}", vertex, expected);
+
+        for i in 0..2 {
+            if (vertex.position - intersections[i]).length() < 0.01 {
+                assert!(vertex.source.t > 0.0 && vertex.source.t < 1.0);
+                found[i] = true;
+            }
+        }
+    }
+    assert_eq!(found, [true, true]);
+}
+
+#[test]
+fn test_vertex_sources_arc() {
+    let mut builder = Path::builder();
+    builder.move_to(point(10.0, 0.0));
+    builder.arc(point(0.0, 0.0), vector(10.0, 10.0), Radians::new(3.0), Radians::new(0.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Vertex> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        path.path_iter(),
+        &FillOptions::tolerance(0.01),
+        &mut simple_builder(&mut buffers),
+    ).unwrap();
+
+    assert!(buffers.vertices.len() > 3);
+    for vertex in &buffers.vertices {
+        assert!(!vertex.source.is_none());
+        let expected = sample_vertex_source(&path, &vertex.source);
+        assert!((expected - vertex.position).length() < 0.01, "{:?} {:?}", vertex, expected);
+    }
+}
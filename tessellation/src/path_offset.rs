@@ -0,0 +1,215 @@
+use path::builder::{FlatPathBuilder, PathBuilder};
+use path::iterator::{PathIterator, PathIter};
+use path::boolean::{boolean_op_with_curves, BooleanOp};
+use path::default::Path;
+use path::{PathEvent, FillRule};
+use stroke_outline::StrokeOutliner;
+use {StrokeOptions, OffsetOptions};
+
+/// Grows or shrinks filled shapes by a given distance.
+///
+/// ## Overview
+///
+/// The offset of a shape is the set of points that are at most at the offset distance
+/// from the shape (outset) or the set of points of the shape that are at least at the
+/// offset distance from its boundary (inset).
+///
+/// The offsetter computes the outline of the stroke of the input path with a line width of
+/// twice the offset distance using a [`StrokeOutliner`](struct.StrokeOutliner.html), and
+/// adds it to or subtracts it from the input using the
+/// [boolean operations](path/boolean/index.html) of `lyon_path`. As a result the corners
+/// of the offset shape follow the `line_join` and `miter_limit` parameters like a
+/// stroke would, and the output doesn't contain any self-intersection, even if the input
+/// or the offset edges self-intersect.
+///
+/// Positive distances grow the shape and negative distances shrink it. Sub-paths that
+/// are not explicitly closed are treated as if they were.
+///
+/// The offsetter doesn't call `build` on the output builder.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lyon_tessellation as tess;
+/// # use tess::path::default::Path;
+/// # use tess::path::builder::*;
+/// # use tess::path::iterator::*;
+/// # use tess::geom::math::*;
+/// # use tess::*;
+/// # fn main() {
+/// let mut path_builder = Path::builder();
+/// path_builder.polygon(&[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)]);
+/// let path = path_builder.build();
+///
+/// let mut halo = Path::builder();
+/// PathOffsetter::new().offset_path(
+///     path.path_iter(),
+///     2.0,
+///     &OffsetOptions::default().with_line_join(LineJoin::Round),
+///     &mut halo,
+/// );
+/// let halo = halo.build();
+/// # }
+/// ```
+pub struct PathOffsetter {
+    outliner: StrokeOutliner,
+    events: Vec<PathEvent>,
+}
+
+impl PathOffsetter {
+    pub fn new() -> PathOffsetter {
+        PathOffsetter {
+            outliner: StrokeOutliner::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Offset a path by a given distance and write the result into a path builder.
+    pub fn offset_path<Input, Output>(
+        &mut self,
+        input: Input,
+        distance: f32,
+        options: &OffsetOptions,
+        output: &mut Output,
+    )
+    where
+        Input: PathIterator,
+        Output: PathBuilder,
+    {
+        // Gather the input and make sure all of its sub-paths are closed so that
+        // the stroke doesn't get caps.
+        self.events.clear();
+        let mut is_open = false;
+        for evt in input {
+            match evt {
+                PathEvent::MoveTo(..) => {
+                    if is_open {
+                        self.events.push(PathEvent::Close);
+                    }
+                    is_open = true;
+                }
+                PathEvent::Close => {
+                    is_open = false;
+                }
+                _ => {}
+            }
+            self.events.push(evt);
+        }
+        if is_open {
+            self.events.push(PathEvent::Close);
+        }
+
+        let mut outline = Path::builder();
+        if distance != 0.0 {
+            let stroke_options = StrokeOptions::tolerance(options.tolerance)
+                .with_line_width(distance.abs() * 2.0)
+                .with_line_join(options.line_join)
+                .with_miter_limit(options.miter_limit);
+
+            self.outliner.outline_path(
+                PathIter::new(self.events.iter().cloned()),
+                &stroke_options,
+                &mut outline,
+            );
+        }
+        let outline = outline.build();
+
+        let op = if distance < 0.0 { BooleanOp::Difference } else { BooleanOp::Union };
+        boolean_op_with_curves(
+            op,
+            PathIter::new(self.events.iter().cloned()), options.fill_rule,
+            outline.path_iter(), FillRule::NonZero,
+            options.tolerance,
+            output,
+        );
+    }
+}
+
+impl Default for PathOffsetter {
+    fn default() -> Self { PathOffsetter::new() }
+}
+
+#[cfg(test)]
+use path::builder::PolygonBuilder;
+#[cfg(test)]
+use stroke_outline::assert_outline_area;
+#[cfg(test)]
+use math::point;
+#[cfg(test)]
+use LineJoin;
+#[cfg(test)]
+use std::f32::consts::PI;
+
+#[cfg(test)]
+fn offset(path: &Path, distance: f32, options: &OffsetOptions) -> Path {
+    let mut builder = Path::builder();
+    PathOffsetter::new().offset_path(path.path_iter(), distance, options, &mut builder);
+    builder.build()
+}
+
+#[test]
+fn outset_square() {
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)]);
+    let path = builder.build();
+    let options = OffsetOptions::default();
+
+    assert_outline_area(&offset(&path, 1.0, &options), 144.0, 1);
+    assert_outline_area(&offset(&path, 1.0, &options.with_line_join(LineJoin::Bevel)), 142.0, 1);
+    assert_outline_area(
+        &offset(&path, 1.0, &options.with_line_join(LineJoin::Round)),
+        100.0 + 40.0 + PI,
+        1,
+    );
+}
+
+#[test]
+fn inset_square() {
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)]);
+    let path = builder.build();
+    let options = OffsetOptions::default();
+
+    assert_outline_area(&offset(&path, -1.0, &options), 64.0, 1);
+    // The square vanishes when it is inset by more than half of its size.
+    assert_outline_area(&offset(&path, -6.0, &options), 0.0, 0);
+
+    // Insetting a square with a hole grows the hole.
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0), point(0.0, 10.0)]);
+    builder.polygon(&[point(3.0, 3.0), point(7.0, 3.0), point(7.0, 7.0), point(3.0, 7.0)]);
+    let path = builder.build();
+    assert_outline_area(&offset(&path, -1.0, &options), 64.0 - 36.0, 2);
+    // Outsetting it shrinks the hole.
+    assert_outline_area(&offset(&path, 1.0, &options), 144.0 - 4.0, 2);
+}
+
+#[test]
+fn offset_self_intersecting_path() {
+    // Two overlapping squares in the same path.
+    let mut builder = Path::builder();
+    builder.polygon(&[point(0.0, 0.0), point(2.0, 0.0), point(2.0, 2.0), point(0.0, 2.0)]);
+    builder.polygon(&[point(1.0, 1.0), point(3.0, 1.0), point(3.0, 3.0), point(1.0, 3.0)]);
+    let path = builder.build();
+
+    let options = OffsetOptions::default().with_fill_rule(FillRule::NonZero);
+    assert_outline_area(&offset(&path, 0.0, &options), 7.0, 1);
+
+    // The union of the two squares has a perimeter of 12, six convex corners that each
+    // add a 0.5x0.5 square and two concave corners where the offset edges overlap.
+    let expected = 7.0 + 12.0 * 0.5 + (6.0 - 2.0) * 0.25;
+    assert_outline_area(&offset(&path, 0.5, &options), expected, 1);
+}
+
+#[test]
+fn offset_open_sub_path() {
+    // Open sub-paths are closed implicitly.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    let path = builder.build();
+
+    assert_outline_area(&offset(&path, 1.0, &OffsetOptions::default()), 144.0, 1);
+}
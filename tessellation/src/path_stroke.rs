@@ -1,13 +1,15 @@
 use math_utils::compute_normal;
+use line_join::{effective_join, miter_clip_offsets};
 use geom::math::*;
-use geom::{QuadraticBezierSegment, CubicBezierSegment, LineSegment, Arc};
+use geom::{QuadraticBezierSegment, CubicBezierSegment, Arc};
 use geom::utils::{normalized_tangent, directed_angle, fast_atan2, vector_angle};
 use geometry_builder::{VertexId, GeometryBuilder, Count};
 use basic_shapes::circle_flattening_step;
+use path::{PathEvent, IdState, IdEvent, EndpointId, SubPathId};
 use path::builder::{FlatPathBuilder, PathBuilder};
 use path::iterator::PathIterator;
 use StrokeVertex as Vertex;
-use {Side, LineCap, LineJoin, StrokeOptions};
+use {Side, LineCap, LineJoin, StrokeOptions, VertexSource};
 
 use std::f32::consts::PI;
 
@@ -138,6 +140,14 @@ pub struct StrokeBuilder<'l> {
     sub_path_start_length: f32,
     options: StrokeOptions,
     previous_command_was_move: bool,
+    // Identifiers of the endpoints and sub-paths of the path being stroked, and the
+    // location on the path of the first, second, previous and current points.
+    ids: IdState,
+    segment: IdEvent,
+    first_source: VertexSource,
+    second_source: VertexSource,
+    previous_source: VertexSource,
+    current_source: VertexSource,
     output: &'l mut GeometryBuilder<Vertex>,
 }
 
@@ -147,6 +157,11 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
     fn move_to(&mut self, to: Point) {
         self.finish();
 
+        let evt = self.ids.path_event(PathEvent::MoveTo(to));
+        self.first_source = VertexSource::endpoint(evt.to, evt.sub_path);
+        self.current_source = self.first_source;
+        self.segment = evt;
+
         self.first = to;
         self.current = to;
         self.nth = 0;
@@ -156,7 +171,9 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
 
     fn line_to(&mut self, to: Point) {
         self.previous_command_was_move = false;
-        self.edge_to(to, true);
+        self.segment = self.ids.path_event(PathEvent::LineTo(to));
+        let source = self.segment_source(1.0);
+        self.edge_to(to, source, true);
     }
 
     fn close(&mut self) {
@@ -165,14 +182,17 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
         // normal will be plagued with floating point precision
         // issues.
         let threshold = 0.001;
+        self.segment = self.ids.path_event(PathEvent::Close);
         if (self.first - self.current).square_length() > threshold {
             let first = self.first;
-            self.edge_to(first, true);
+            let source = self.segment_source(1.0);
+            self.edge_to(first, source, true);
         }
 
         if self.nth > 1 {
             let second = self.second;
-            self.edge_to(second, true);
+            let second_source = self.second_source;
+            self.edge_to(second, second_source, true);
 
             let first_left_id = add_vertex!(
                 self,
@@ -181,6 +201,7 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
                     normal: self.prev_normal,
                     advancement: self.sub_path_start_length,
                     side: Side::Left,
+                    source: self.previous_source,
                 }
             );
             let first_right_id = add_vertex!(
@@ -190,6 +211,7 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
                     normal: -self.prev_normal,
                     advancement: self.sub_path_start_length,
                     side: Side::Right,
+                    source: self.previous_source,
                 }
             );
 
@@ -198,6 +220,7 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
         }
         self.nth = 0;
         self.current = self.first;
+        self.current_source = self.first_source;
         self.sub_path_start_length = self.length;
         self.previous_command_was_move = false;
     }
@@ -218,41 +241,47 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
         self.length = 0.0;
         self.sub_path_start_length = 0.0;
         self.previous_command_was_move = false;
+        self.ids = IdState::new();
+        self.segment = no_segment();
+        self.first_source = VertexSource::NONE;
+        self.second_source = VertexSource::NONE;
+        self.previous_source = VertexSource::NONE;
+        self.current_source = VertexSource::NONE;
     }
 }
 
 impl<'l> PathBuilder for StrokeBuilder<'l> {
     fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
         self.previous_command_was_move = false;
+        self.segment = self.ids.path_event(PathEvent::QuadraticTo(ctrl, to));
         let mut first = true;
-        QuadraticBezierSegment {
+        let curve = QuadraticBezierSegment {
             from: self.current,
             ctrl: ctrl,
             to: to,
-        }.flattened_for_each(
-            self.options.tolerance,
-            &mut |point| {
-                self.edge_to(point, first);
-                first = false;
-            }
-        );
+        };
+        for (point, t) in curve.flattened_with_t(self.options.tolerance) {
+            let source = self.segment_source(t);
+            self.edge_to(point, source, first);
+            first = false;
+        }
     }
 
     fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
         self.previous_command_was_move = false;
+        self.segment = self.ids.path_event(PathEvent::CubicTo(ctrl1, ctrl2, to));
         let mut first = true;
-        CubicBezierSegment {
+        let curve = CubicBezierSegment {
             from: self.current,
             ctrl1: ctrl1,
             ctrl2: ctrl2,
             to: to,
-        }.flattened_for_each(
-            self.options.tolerance,
-            &mut |point| {
-                self.edge_to(point, first);
-                first = false;
-            }
-        );
+        };
+        for (point, t) in curve.flattened_with_t(self.options.tolerance) {
+            let source = self.segment_source(t);
+            self.edge_to(point, source, first);
+            first = false;
+        }
     }
 
     fn arc(
@@ -262,21 +291,21 @@ impl<'l> PathBuilder for StrokeBuilder<'l> {
         sweep_angle: Radians,
         x_rotation: Radians
     ) {
+        self.segment = self.ids.path_event(PathEvent::Arc(center, radii, sweep_angle, x_rotation));
         let start_angle = vector_angle(self.current - center);
         let mut first = true;
-        Arc {
+        let arc = Arc {
             center,
             radii,
             start_angle,
             sweep_angle,
             x_rotation,
-        }.flattened_for_each(
-            self.options.tolerance,
-            &mut |point| {
-                self.edge_to(point, first);
-                first = false;
-            }
-        );
+        };
+        for (point, t) in arc.flattened_with_t(self.options.tolerance) {
+            let source = self.segment_source(t);
+            self.edge_to(point, source, first);
+            first = false;
+        }
     }
 }
 
@@ -301,12 +330,27 @@ impl<'l> StrokeBuilder<'l> {
             sub_path_start_length: 0.0,
             options: *options,
             previous_command_was_move: false,
+            ids: IdState::new(),
+            segment: no_segment(),
+            first_source: VertexSource::NONE,
+            second_source: VertexSource::NONE,
+            previous_source: VertexSource::NONE,
+            current_source: VertexSource::NONE,
             output: builder,
         };
     }
 
     pub fn set_options(&mut self, options: &StrokeOptions) { self.options = *options; }
 
+    fn segment_source(&self, t: f32) -> VertexSource {
+        VertexSource {
+            from: self.segment.from,
+            to: self.segment.to,
+            t: t,
+            sub_path: self.segment.sub_path,
+        }
+    }
+
     fn tessellate_empty_square_cap(&mut self) {
         let a = add_vertex!(
             self,
@@ -315,6 +359,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: vector(1.0, 1.0),
                 advancement: 0.0,
                 side: Side::Right,
+                source: self.current_source,
             }
         );
         let b = add_vertex!(
@@ -324,6 +369,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: vector(1.0, -1.0),
                 advancement: 0.0,
                 side: Side::Left,
+                source: self.current_source,
             }
         );
         let c = add_vertex!(
@@ -333,6 +379,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: vector(-1.0, -1.0),
                 advancement: 0.0,
                 side: Side::Left,
+                source: self.current_source,
             }
         );
         let d = add_vertex!(
@@ -342,6 +389,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: vector(-1.0, 1.0),
                 advancement: 0.0,
                 side: Side::Right,
+                source: self.current_source,
             }
         );
         self.output.add_triangle(a, b, c);
@@ -357,6 +405,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: vector(-1.0, 0.0),
                 advancement: 0.0,
                 side: Side::Left,
+                source: self.current_source,
             }
         );
         let right_id = add_vertex!(
@@ -366,6 +415,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: vector(1.0, 0.0),
                 advancement: 0.0,
                 side: Side::Right,
+                source: self.current_source,
             }
         );
         self.tessellate_round_cap(center, vector(0.0, -1.0), left_id, right_id, true);
@@ -398,7 +448,8 @@ impl<'l> StrokeBuilder<'l> {
                 self.current += d.normalize();
             }
             let p = self.current + d;
-            self.edge_to(p, true);
+            let source = self.current_source;
+            self.edge_to(p, source, true);
             // Restore the real current position.
             self.current = current;
 
@@ -427,6 +478,7 @@ impl<'l> StrokeBuilder<'l> {
                     normal: n1,
                     advancement: self.sub_path_start_length,
                     side: Side::Left,
+                    source: self.first_source,
                 }
             );
             let first_right_id = add_vertex!(
@@ -436,6 +488,7 @@ impl<'l> StrokeBuilder<'l> {
                     normal: n2,
                     advancement: self.sub_path_start_length,
                     side: Side::Right,
+                    source: self.first_source,
                 }
             );
 
@@ -448,7 +501,7 @@ impl<'l> StrokeBuilder<'l> {
         }
     }
 
-    fn edge_to(&mut self, to: Point, with_join: bool) {
+    fn edge_to(&mut self, to: Point, source: VertexSource, with_join: bool) {
         if to == self.current {
             return;
         }
@@ -458,6 +511,8 @@ impl<'l> StrokeBuilder<'l> {
             // vertices (and thus the current join) yet.
             self.previous = self.first;
             self.current = to;
+            self.previous_source = self.first_source;
+            self.current_source = source;
             self.nth += 1;
             return;
         }
@@ -490,9 +545,12 @@ impl<'l> StrokeBuilder<'l> {
         self.previous_left_id = end_left_id;
         self.previous_right_id = end_right_id;
         self.current = to;
+        self.previous_source = self.current_source;
+        self.current_source = source;
 
         if self.nth == 1 {
             self.second = self.previous;
+            self.second_source = self.previous_source;
             self.second_left_id = start_left_id;
             self.second_right_id = start_right_id;
         }
@@ -520,6 +578,7 @@ impl<'l> StrokeBuilder<'l> {
 
         let dir = dir.normalize();
         let advancement = self.length;
+        let source = if is_start { self.first_source } else { self.current_source };
 
         let quarter_angle = if is_start { -PI * 0.5 } else { PI * 0.5 };
         let mid_angle = directed_angle(vector(1.0, 0.0), dir);
@@ -533,6 +592,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: dir,
                 advancement: advancement,
                 side: Side::Left,
+                source: source,
             }
         );
 
@@ -557,6 +617,7 @@ impl<'l> StrokeBuilder<'l> {
             num_recursions,
             advancement,
             Side::Left,
+            source,
             apply_width,
             !is_start,
             self.output
@@ -569,6 +630,7 @@ impl<'l> StrokeBuilder<'l> {
             num_recursions,
             advancement,
             Side::Right,
+            source,
             apply_width,
             !is_start,
             self.output
@@ -578,7 +640,7 @@ impl<'l> StrokeBuilder<'l> {
     fn tessellate_join(&mut self,
         prev_tangent: Vector,
         next_tangent: Vector,
-        mut join_type: LineJoin,
+        join_type: LineJoin,
     ) -> (VertexId, VertexId, VertexId, VertexId) {
         // This function needs to differentiate the "front" of the join (aka. the pointy side)
         // from the back. The front is where subdivision or adjustments may be needed.
@@ -599,22 +661,11 @@ impl<'l> StrokeBuilder<'l> {
                 normal: -front_normal,
                 advancement: self.length,
                 side: front_side.opposite(),
+                source: self.current_source,
             }
         );
 
-        let threshold = 0.95; // TODO: look for a good constant here.
-        if prev_tangent.dot(next_tangent) >= threshold {
-            // The two edges are almost aligned, just use a simple miter join.
-            // TODO: the 0.95 threshold above is completely arbitrary and needs
-            // adjustments.
-            join_type = LineJoin::Miter;
-        } else if join_type == LineJoin::Miter && self.miter_limit_is_exceeded(normal) {
-            // Per SVG spec: If the stroke-miterlimit is exceeded, the line join
-            // falls back to bevel.
-            join_type = LineJoin::Bevel;
-        } else if join_type == LineJoin::MiterClip && !self.miter_limit_is_exceeded(normal) {
-            join_type = LineJoin::Miter;
-        }
+        let join_type = effective_join(join_type, prev_tangent, next_tangent, self.options.miter_limit);
 
         let (start_vertex, end_vertex) = match join_type {
             LineJoin::Round => {
@@ -651,6 +702,7 @@ impl<'l> StrokeBuilder<'l> {
                         normal: front_normal,
                         advancement: self.length,
                         side: front_side,
+                        source: self.current_source,
                     }
                 );
                 self.prev_normal = normal;
@@ -683,6 +735,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: prev_normal * neg_if_right,
                 advancement: self.length,
                 side: front_side,
+                source: self.current_source,
             }
         );
         let last_vertex = add_vertex!(
@@ -692,6 +745,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: next_normal * neg_if_right,
                 advancement: self.length,
                 side: front_side,
+                source: self.current_source,
             }
         );
         self.prev_normal = next_normal;
@@ -733,6 +787,7 @@ impl<'l> StrokeBuilder<'l> {
                 normal: initial_normal,
                 advancement: self.length,
                 side: front_side,
+                source: self.current_source,
             }
         );
         let start_vertex = last_vertex;
@@ -760,6 +815,7 @@ impl<'l> StrokeBuilder<'l> {
                     normal: n,
                     advancement: self.length,
                     side: front_side,
+                    source: self.current_source,
                 }
             );
 
@@ -790,15 +846,22 @@ impl<'l> StrokeBuilder<'l> {
         let prev_normal: Vector = vector(-prev_tangent.y, prev_tangent.x);
         let next_normal: Vector = vector(-next_tangent.y, next_tangent.x);
 
-        let (v1, v2) = self.get_clip_intersections(prev_normal, next_normal, normal);
+        let (v1, v2) = miter_clip_offsets(
+            prev_tangent,
+            next_tangent,
+            prev_normal * neg_if_right,
+            next_normal * neg_if_right,
+            self.options.miter_limit,
+        );
 
         let start_vertex = add_vertex!(
             self,
             Vertex {
                 position: self.current,
-                normal: v1 * neg_if_right,
+                normal: v1,
                 advancement: self.length,
                 side: front_side,
+                source: self.current_source,
             }
         );
 
@@ -806,9 +869,10 @@ impl<'l> StrokeBuilder<'l> {
             self,
             Vertex {
                 position: self.current,
-                normal: v2 * neg_if_right,
+                normal: v2,
                 advancement: self.length,
                 side: front_side,
+                source: self.current_source,
             }
         );
 
@@ -823,33 +887,14 @@ impl<'l> StrokeBuilder<'l> {
 
         (start_vertex, last_vertex)
     }
+}
 
-    fn miter_limit_is_exceeded(&self, normal: Vector ) -> bool {
-        normal.square_length() > self.options.miter_limit * self.options.miter_limit
-    }
-
-    fn get_clip_intersections(&self, prev_normal: Vector, next_normal: Vector, normal: Vector) -> (Vector, Vector) {
-        let miter_length = self.options.miter_limit * self.options.line_width;
-        let normal_limit = normal.normalize() * miter_length;
-
-        let normal_limit_perp = LineSegment{
-            from: point(normal_limit.x - normal_limit.y, normal_limit.y + normal_limit.x),
-            to: point(normal_limit.x + normal_limit.y, normal_limit.y - normal_limit.x)
-        };
-
-        let l1 = LineSegment{
-            from : point(prev_normal.x, prev_normal.y),
-            to: point(normal.x, normal.y)
-        };
-        let l2 = LineSegment{
-            from: point(next_normal.x, next_normal.y),
-            to: point(normal.x, normal.y)
-        };
-
-        let i1 = l1.sample(l1.intersection(&normal_limit_perp).unwrap().0);
-        let i2 = l1.sample(l2.intersection(&normal_limit_perp).unwrap().0);
-
-        (vector(i1.x, i1.y), vector(i2.x, i2.y))
+fn no_segment() -> IdEvent {
+    IdEvent {
+        event: PathEvent::Close,
+        from: EndpointId::INVALID,
+        to: EndpointId::INVALID,
+        sub_path: SubPathId::INVALID,
     }
 }
 
@@ -881,6 +926,7 @@ fn tess_round_cap(
     num_recursions: u32,
     advancement: f32,
     side: Side,
+    source: VertexSource,
     line_width: f32,
     invert_winding: bool,
     output: &mut GeometryBuilder<Vertex>
@@ -898,6 +944,7 @@ fn tess_round_cap(
         normal: normal,
         advancement,
         side,
+        source: source,
     });
 
     let (v1, v2, v3) = if invert_winding {
@@ -916,6 +963,7 @@ fn tess_round_cap(
         num_recursions - 1,
         advancement,
         side,
+        source,
         line_width,
         invert_winding,
         output
@@ -929,6 +977,7 @@ fn tess_round_cap(
         num_recursions - 1,
         advancement,
         side,
+        source,
         line_width,
         invert_winding,
         output
@@ -938,6 +987,8 @@ fn tess_round_cap(
 #[cfg(test)]
 use path::default::{Path, PathSlice};
 #[cfg(test)]
+use path_fill::sample_vertex_source;
+#[cfg(test)]
 use geometry_builder::{
     SimpleBuffersBuilder, simple_builder, VertexBuffers,
 };
@@ -1104,3 +1155,31 @@ fn test_empty_caps() {
         None,
     );
 }
+
+#[test]
+fn test_vertex_sources() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.cubic_bezier_to(point(15.0, 0.0), point(15.0, 10.0), point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(20.0, 0.0));
+    builder.quadratic_bezier_to(point(30.0, 0.0), point(30.0, 10.0));
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Vertex> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path(
+        path.path_iter(),
+        &StrokeOptions::default().with_line_join(LineJoin::Round).dont_apply_line_width(),
+        &mut simple_builder(&mut buffers),
+    );
+
+    let mut sub_paths = [false, false];
+    for vertex in &buffers.vertices {
+        let expected = sample_vertex_source(&path, &vertex.source);
+        assert!((expected - vertex.position).length() < 0.001, "{:?} {:?}", vertex, expected);
+        sub_paths[vertex.source.sub_path.to_usize()] = true;
+    }
+    assert_eq!(sub_paths, [true, true]);
+}
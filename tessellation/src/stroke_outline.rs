@@ -0,0 +1,620 @@
+use math_utils::compute_normal;
+use line_join::{effective_join, is_u_turn, miter_clip_offsets};
+use geom::math::*;
+use geom::Arc;
+use geom::utils::vector_angle;
+use path::builder::PathBuilder;
+use path::iterator::PathIterator;
+use path::FlattenedEvent;
+use {LineCap, LineJoin, StrokeOptions};
+
+use std::f32::consts::PI;
+
+/// Computes the outline of a stroke as a fillable path.
+///
+/// ## Overview
+///
+/// Where the [`StrokeTessellator`](struct.StrokeTessellator.html) produces triangles,
+/// the `StrokeOutliner` produces the contour of the area covered by the stroke and sends
+/// it to a [`PathBuilder`](path/builder/trait.PathBuilder.html). The resulting path can be
+/// exported, used as an input of boolean operations or for hit-testing.
+///
+/// The outline follows the same `line_join`, `start_cap`, `end_cap`, `miter_limit` and
+/// `tolerance` parameters as the stroke tessellator. The input curves are approximated
+/// with line segments, while round joins and caps are produced as quadratic bézier curves.
+/// `apply_line_width` is ignored.
+///
+/// Each open sub-path of the input produces a single closed sub-path going along one
+/// side of the stroke and back along the other side. Each closed sub-path of the input
+/// produces two sub-paths, one for each side of the stroke, in opposite directions.
+///
+/// The outline can overlap with itself where the input self-intersects or turns sharply,
+/// but the winding of all of its parts is consistent, so it must be filled using the
+/// `NonZero` fill rule.
+///
+/// The outliner doesn't call `build` on the output builder.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lyon_tessellation as tess;
+/// # use tess::path::default::Path;
+/// # use tess::path::builder::*;
+/// # use tess::path::iterator::*;
+/// # use tess::geom::math::*;
+/// # use tess::*;
+/// # fn main() {
+/// let mut path_builder = Path::builder();
+/// path_builder.move_to(point(0.0, 0.0));
+/// path_builder.line_to(point(10.0, 0.0));
+/// path_builder.line_to(point(10.0, 10.0));
+/// let path = path_builder.build();
+///
+/// let mut outline = Path::builder();
+/// StrokeOutliner::new().outline_path(
+///     path.path_iter(),
+///     &StrokeOptions::default().with_line_join(LineJoin::Round),
+///     &mut outline,
+/// );
+/// let outline = outline.build();
+/// # }
+/// ```
+pub struct StrokeOutliner {
+    points: Vec<Point>,
+    joins: Vec<bool>,
+    arc_buffer: Vec<(Point, Point)>,
+}
+
+impl StrokeOutliner {
+    pub fn new() -> StrokeOutliner {
+        StrokeOutliner {
+            points: Vec::new(),
+            joins: Vec::new(),
+            arc_buffer: Vec::new(),
+        }
+    }
+
+    /// Compute the outline of the stroke of a path and write it into a path builder.
+    pub fn outline_path<Input, Output>(
+        &mut self,
+        input: Input,
+        options: &StrokeOptions,
+        output: &mut Output,
+    )
+    where
+        Input: PathIterator,
+        Output: PathBuilder,
+    {
+        self.points.clear();
+        self.joins.clear();
+        let mut sub_path_start = None;
+
+        for evt in input.flattened_with_t(options.tolerance) {
+            match evt.event {
+                FlattenedEvent::MoveTo(to) => {
+                    if let Some(start) = sub_path_start {
+                        self.outline_sub_path(start, false, options, output);
+                    }
+                    self.points.clear();
+                    self.joins.clear();
+                    self.points.push(to);
+                    self.joins.push(true);
+                    sub_path_start = Some(to);
+                }
+                FlattenedEvent::LineTo(to) => {
+                    // Points in the middle of a curve are joined with a miter join
+                    // regardless of the line join option.
+                    let with_join = evt.t == 1.0;
+                    self.push_point(to, with_join);
+                }
+                FlattenedEvent::Close => {
+                    if let Some(start) = sub_path_start {
+                        self.outline_sub_path(start, true, options, output);
+                    }
+                    self.points.clear();
+                    self.joins.clear();
+                    sub_path_start = None;
+                }
+            }
+        }
+
+        if let Some(start) = sub_path_start {
+            self.outline_sub_path(start, false, options, output);
+        }
+    }
+
+    fn push_point(&mut self, to: Point, with_join: bool) {
+        if self.points.last() == Some(&to) {
+            let last = self.joins.len() - 1;
+            self.joins[last] |= with_join;
+            return;
+        }
+
+        self.points.push(to);
+        self.joins.push(with_join);
+    }
+
+    fn outline_sub_path<Output: PathBuilder>(
+        &mut self,
+        start: Point,
+        closed: bool,
+        options: &StrokeOptions,
+        output: &mut Output,
+    ) {
+        if self.points.is_empty() {
+            // The sub-path was closed before any segment was added. Like the
+            // tessellator, don't produce anything in this case.
+            return;
+        }
+
+        let mut outliner = Outliner {
+            options: options,
+            half_width: options.line_width * 0.5,
+            output: output,
+            arc_buffer: &mut self.arc_buffer,
+            move_pending: true,
+        };
+
+        if closed {
+            // If we close almost at the first point, we skip the last point,
+            // otherwise the join would be plagued with floating point precision issues.
+            let threshold = 0.001;
+            if self.points.len() > 1 && (self.points[self.points.len() - 1] - start).square_length() <= threshold {
+                self.points.pop();
+                self.joins.pop();
+            }
+            if self.points.len() < 2 {
+                return;
+            }
+
+            self.joins[0] = true;
+            outliner.closed_side(self.points.iter().cloned(), self.joins.iter().cloned());
+            outliner.closed_side(self.points.iter().rev().cloned(), self.joins.iter().rev().cloned());
+        } else if self.points.len() == 1 {
+            outliner.empty_sub_path(self.points[0]);
+        } else {
+            outliner.open(&self.points, &self.joins);
+        }
+    }
+}
+
+impl Default for StrokeOutliner {
+    fn default() -> Self { StrokeOutliner::new() }
+}
+
+struct Outliner<'l, Output: 'l + PathBuilder> {
+    options: &'l StrokeOptions,
+    half_width: f32,
+    output: &'l mut Output,
+    arc_buffer: &'l mut Vec<(Point, Point)>,
+    move_pending: bool,
+}
+
+impl<'l, Output: PathBuilder> Outliner<'l, Output> {
+    fn point_to(&mut self, to: Point) {
+        if self.move_pending {
+            self.output.move_to(to);
+            self.move_pending = false;
+        } else {
+            self.output.line_to(to);
+        }
+    }
+
+    fn close(&mut self) {
+        if !self.move_pending {
+            self.output.close();
+        }
+        self.move_pending = true;
+    }
+
+    // Adds a circular arc around center going from the current position (at
+    // center + from) to `to`.
+    fn arc_to(&mut self, center: Point, from: Vector, sweep_angle: f32, to: Point) {
+        self.arc_buffer.clear();
+        {
+            let arc_buffer = &mut self.arc_buffer;
+            Arc {
+                center: center,
+                radii: vector(self.half_width, self.half_width),
+                start_angle: vector_angle(from),
+                sweep_angle: Radians::new(sweep_angle),
+                x_rotation: Radians::new(0.0),
+            }.to_quadratic_beziers(&mut |ctrl, to| {
+                arc_buffer.push((ctrl, to));
+            });
+        }
+
+        let n = self.arc_buffer.len();
+        for i in 0..n {
+            let (ctrl, mut end) = self.arc_buffer[i];
+            if i == n - 1 {
+                // Make sure we end up exactly at the expected position.
+                end = to;
+            }
+            self.output.quadratic_bezier_to(ctrl, end);
+        }
+    }
+
+    fn open(&mut self, points: &[Point], joins: &[bool]) {
+        let n = points.len();
+        let hw = self.half_width;
+
+        let first_tangent = (points[1] - points[0]).normalize();
+        let last_tangent = (points[n - 1] - points[n - 2]).normalize();
+
+        // Left side.
+        self.point_to(points[0] + left_normal(first_tangent) * hw);
+        for i in 1..(n - 1) {
+            self.join(points[i - 1], points[i], points[i + 1], joins[i]);
+        }
+        self.point_to(points[n - 1] + left_normal(last_tangent) * hw);
+
+        let end_cap = self.options.end_cap;
+        self.cap(points[n - 1], last_tangent, end_cap, false);
+
+        // Right side, which is the left side of the reversed sub-path.
+        for i in (1..(n - 1)).rev() {
+            self.join(points[i + 1], points[i], points[i - 1], joins[i]);
+        }
+        self.point_to(points[0] - left_normal(first_tangent) * hw);
+
+        let start_cap = self.options.start_cap;
+        self.cap(points[0], -first_tangent, start_cap, true);
+
+        self.close();
+    }
+
+    // Outlines the left side of a closed sub-path.
+    fn closed_side<Points, Joins>(&mut self, points: Points, joins: Joins)
+    where
+        Points: Iterator<Item = Point> + Clone,
+        Joins: Iterator<Item = bool>,
+    {
+        let last = points.clone().last().unwrap();
+        let mut prev = last;
+        let mut current = points.clone().next().unwrap();
+        for (next, with_join) in points.skip(1).chain(Some(current)).zip(joins) {
+            self.join(prev, current, next, with_join);
+            prev = current;
+            current = next;
+        }
+
+        self.close();
+    }
+
+    // Adds the outline of the join on the left side of the edges (prev, current) and
+    // (current, next), starting on the offset of the previous edge and ending on the offset
+    // of the next edge.
+    fn join(&mut self, prev: Point, current: Point, next: Point, with_join: bool) {
+        let hw = self.half_width;
+        let prev_edge = current - prev;
+        let next_edge = next - current;
+        let prev_length = prev_edge.length();
+        let next_length = next_edge.length();
+        let prev_tangent = prev_edge / prev_length;
+        let next_tangent = next_edge / next_length;
+        let prev_normal = left_normal(prev_tangent) * hw;
+        let next_normal = left_normal(next_tangent) * hw;
+
+        let cross = prev_tangent.cross(next_tangent);
+        let dot = prev_tangent.dot(next_tangent);
+        let is_u_turn = is_u_turn(prev_tangent, next_tangent);
+        let miter = compute_normal(prev_tangent, next_tangent) * hw;
+
+        if cross > 0.0 && !is_u_turn {
+            // Inner side of the join. If the offset edges intersect close enough to the join,
+            // connect them at their intersection. Otherwise, go through the center of the join
+            // so that the winding stays consistent. Using at most half of each edge guarantees
+            // that the joins at both ends of an edge don't overlap.
+            if miter.dot(prev_tangent).abs() <= prev_length * 0.5
+                && miter.dot(next_tangent).abs() <= next_length * 0.5 {
+                self.point_to(current + miter);
+            } else {
+                self.point_to(current + prev_normal);
+                self.point_to(current);
+                self.point_to(current + next_normal);
+            }
+            return;
+        }
+
+        let join_type = if with_join { self.options.line_join } else { LineJoin::Miter };
+        let join_type = effective_join(join_type, prev_tangent, next_tangent, self.options.miter_limit);
+
+        match join_type {
+            LineJoin::Miter => {
+                self.point_to(current + miter);
+            }
+            LineJoin::MiterClip => {
+                let (v1, v2) = miter_clip_offsets(
+                    prev_tangent,
+                    next_tangent,
+                    left_normal(prev_tangent),
+                    left_normal(next_tangent),
+                    self.options.miter_limit,
+                );
+                self.point_to(current + v1 * hw);
+                self.point_to(current + v2 * hw);
+            }
+            LineJoin::Round => {
+                let sweep_angle = -cross.abs().atan2(dot);
+                self.point_to(current + prev_normal);
+                self.arc_to(current, prev_normal, sweep_angle, current + next_normal);
+            }
+            LineJoin::Bevel => {
+                self.point_to(current + prev_normal);
+                self.point_to(current + next_normal);
+            }
+        }
+    }
+
+    // Adds a cap at the end of an edge of direction `tangent`, starting on the left side
+    // of the edge and ending on the right side.
+    fn cap(&mut self, position: Point, tangent: Vector, cap: LineCap, is_last: bool) {
+        let hw = self.half_width;
+        let normal = left_normal(tangent) * hw;
+        match cap {
+            LineCap::Butt => {
+                if !is_last {
+                    self.point_to(position - normal);
+                }
+            }
+            LineCap::Square => {
+                self.point_to(position + normal + tangent * hw);
+                self.point_to(position - normal + tangent * hw);
+                if !is_last {
+                    self.point_to(position - normal);
+                }
+            }
+            LineCap::Round => {
+                self.arc_to(position, normal, -PI, position - normal);
+            }
+        }
+    }
+
+    // A sub-path made of a single point only has a stroke with square and
+    // round caps.
+    fn empty_sub_path(&mut self, position: Point) {
+        let hw = self.half_width;
+        let cap = self.options.start_cap;
+        match cap {
+            LineCap::Butt => {}
+            LineCap::Square => {
+                self.point_to(position + vector(hw, hw));
+                self.point_to(position + vector(hw, -hw));
+                self.point_to(position + vector(-hw, -hw));
+                self.point_to(position + vector(-hw, hw));
+                self.close();
+            }
+            LineCap::Round => {
+                let from = vector(hw, 0.0);
+                self.point_to(position + from);
+                self.arc_to(position, from, -2.0 * PI, position + from);
+                self.close();
+            }
+        }
+    }
+}
+
+#[inline]
+fn left_normal(v: Vector) -> Vector {
+    vector(-v.y, v.x)
+}
+
+#[cfg(test)]
+use path::default::Path;
+#[cfg(test)]
+use path::builder::FlatPathBuilder;
+#[cfg(test)]
+use path::orientation::signed_area;
+
+#[cfg(test)]
+fn outline(path: &Path, options: &StrokeOptions) -> Path {
+    let mut builder = Path::builder();
+    StrokeOutliner::new().outline_path(path.path_iter(), options, &mut builder);
+    builder.build()
+}
+
+// Outlines can have either winding, so this compares the absolute value of their area.
+#[cfg(test)]
+pub(crate) fn assert_outline_area(path: &Path, expected_area: f32, expected_sub_paths: usize) {
+    let area = signed_area(path.path_iter(), 0.0001).abs();
+    let sub_paths = path.subpaths().count();
+    // Round joins and caps are approximated with quadratic bézier curves.
+    if (area - expected_area).abs() > 0.05 || sub_paths != expected_sub_paths {
+        panic!(
+            "Expected area {} and {} sub-paths, got {} and {}.",
+            expected_area, expected_sub_paths, area, sub_paths
+        );
+    }
+}
+
+#[test]
+fn outline_line_caps() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0);
+
+    assert_outline_area(&outline(&path, &options), 20.0, 1);
+    assert_outline_area(&outline(&path, &options.with_line_cap(LineCap::Square)), 24.0, 1);
+    assert_outline_area(&outline(&path, &options.with_line_cap(LineCap::Round)), 20.0 + PI, 1);
+}
+
+#[test]
+fn outline_closed_square() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0);
+
+    // The outer and inner sides are in opposite directions.
+    assert_outline_area(&outline(&path, &options), 144.0 - 64.0, 2);
+    // Each bevel removes a triangle of area 0.5 from the corners.
+    assert_outline_area(&outline(&path, &options.with_line_join(LineJoin::Bevel)), 142.0 - 64.0, 2);
+    // Each round join removes (1 - PI / 4) from the corners.
+    assert_outline_area(
+        &outline(&path, &options.with_line_join(LineJoin::Round)),
+        144.0 - 4.0 * (1.0 - PI / 4.0) - 64.0,
+        2,
+    );
+}
+
+#[test]
+fn outline_short_edges() {
+    // The inner offset edges intersect further than half of the edges' length. Connecting
+    // them at their intersection at both ends of the edges would flip the inner side and
+    // leave a hole in the middle of the square.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.5, 0.0));
+    builder.line_to(point(1.5, 1.5));
+    builder.line_to(point(0.0, 1.5));
+    builder.close();
+    let path = builder.build();
+
+    let result = outline(&path, &StrokeOptions::default().with_line_width(2.0));
+
+    // Winding number of the outline around the center of the square.
+    let center: Point = point(0.75, 0.75);
+    let mut winding = 0;
+    let mut first = point(0.0, 0.0);
+    let mut prev = point(0.0, 0.0);
+    for evt in result.path_iter().flattened(0.0001) {
+        let (from, to) = match evt {
+            FlattenedEvent::MoveTo(p) => {
+                first = p;
+                prev = p;
+                continue;
+            }
+            FlattenedEvent::LineTo(p) => (prev, p),
+            FlattenedEvent::Close => (prev, first),
+        };
+        prev = to;
+        if (from.y <= center.y) != (to.y <= center.y) {
+            let x = from.x + (to.x - from.x) * (center.y - from.y) / (to.y - from.y);
+            if x > center.x {
+                winding += if to.y > from.y { 1 } else { -1 };
+            }
+        }
+    }
+
+    assert!(winding != 0);
+}
+
+#[test]
+fn outline_miter_limit() {
+    // A sharp corner.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(0.0, 1.0));
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0);
+    let miter = outline(&path, &options.with_miter_limit(100.0));
+    let bevel = outline(&path, &options);
+    let clip = outline(&path, &options.with_line_join(LineJoin::MiterClip));
+    let explicit_bevel = outline(&path, &options.with_line_join(LineJoin::Bevel));
+
+    let miter_area = signed_area(miter.path_iter(), 0.0001).abs();
+    let bevel_area = signed_area(bevel.path_iter(), 0.0001).abs();
+    let clip_area = signed_area(clip.path_iter(), 0.0001).abs();
+    let explicit_bevel_area = signed_area(explicit_bevel.path_iter(), 0.0001).abs();
+
+    // With the default miter limit, the miter falls back to a bevel.
+    assert!((bevel_area - explicit_bevel_area).abs() < 0.0001);
+    assert!(miter_area > clip_area);
+    assert!(clip_area > bevel_area);
+
+    // The outline doesn't extend further than the miter limit.
+    let tip = miter.iter().map(|evt| match evt {
+        ::path::PathEvent::LineTo(p) => p.x,
+        _ => 0.0,
+    }).fold(0.0, f32::max);
+    let clipped_tip = clip.iter().map(|evt| match evt {
+        ::path::PathEvent::LineTo(p) => p.x,
+        _ => 0.0,
+    }).fold(0.0, f32::max);
+    assert!(tip > 14.0);
+    // The clip line isn't exactly vertical, so the clipped corners can be slightly
+    // further than the miter limit along the x axis.
+    assert!(clipped_tip < 10.0 + 4.0 + 0.1);
+}
+
+#[test]
+fn outline_empty_sub_paths() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.move_to(point(10.0, 0.0));
+    let path = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0);
+
+    assert_outline_area(&outline(&path, &options), 0.0, 0);
+    assert_outline_area(&outline(&path, &options.with_line_cap(LineCap::Square)), 8.0, 2);
+    assert_outline_area(&outline(&path, &options.with_line_cap(LineCap::Round)), 2.0 * PI, 2);
+}
+
+#[test]
+fn outline_curves() {
+    use path::builder::PathBuilder;
+
+    // A circle of radius 10 made of four cubic bézier curves.
+    let k = 5.522848;
+    let mut builder = Path::builder();
+    builder.move_to(point(10.0, 0.0));
+    builder.cubic_bezier_to(point(10.0, k), point(k, 10.0), point(0.0, 10.0));
+    builder.cubic_bezier_to(point(-k, 10.0), point(-10.0, k), point(-10.0, 0.0));
+    builder.cubic_bezier_to(point(-10.0, -k), point(-k, -10.0), point(0.0, -10.0));
+    builder.cubic_bezier_to(point(k, -10.0), point(10.0, -k), point(10.0, 0.0));
+    builder.close();
+    let path = builder.build();
+
+    let options = StrokeOptions::tolerance(0.001).with_line_width(2.0);
+    let expected_area = PI * (11.0 * 11.0 - 9.0 * 9.0);
+    let result = outline(&path, &options);
+    let area = signed_area(result.path_iter(), 0.0001).abs();
+    assert_eq!(result.subpaths().count(), 2);
+    assert!((area - expected_area).abs() < 0.1);
+}
+
+#[test]
+fn outline_miter_clip_matches_tessellation() {
+    use {StrokeTessellator, StrokeVertex};
+    use geometry_builder::{VertexBuffers, simple_builder};
+
+    // A right angle, with a miter limit that clips the corner.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    let path = builder.build();
+
+    let options = StrokeOptions::default()
+        .with_line_width(2.0)
+        .with_line_join(LineJoin::MiterClip)
+        .with_miter_limit(1.0);
+
+    let mut buffers: VertexBuffers<StrokeVertex> = VertexBuffers::new();
+    StrokeTessellator::new().tessellate_path(path.path_iter(), &options, &mut simple_builder(&mut buffers));
+    let mut triangle_area = 0.0;
+    for triangle in buffers.indices.chunks(3) {
+        let a = buffers.vertices[triangle[0] as usize].position;
+        let b = buffers.vertices[triangle[1] as usize].position;
+        let c = buffers.vertices[triangle[2] as usize].position;
+        triangle_area += (b - a).cross(c - a).abs() * 0.5;
+    }
+
+    // Two 10x2 rectangles overlapping on a unit square, and the corner of the miter
+    // without a triangle of height sqrt(2) - 1 at its tip.
+    let expected_area = 39.0 + 1.0 - (2.0f32.sqrt() - 1.0) * (2.0f32.sqrt() - 1.0);
+    assert!((triangle_area - expected_area).abs() < 0.001, "{}", triangle_area);
+    assert_outline_area(&outline(&path, &options), expected_area, 1);
+}